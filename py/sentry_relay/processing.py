@@ -25,6 +25,7 @@ __all__ = [
     "validate_pii_config",
     "convert_datascrubbing_config",
     "pii_strip_event",
+    "pii_explain_event",
     "pii_selector_suggestions_from_event",
     "VALID_PLATFORMS",
     "validate_rule_condition",
@@ -226,13 +227,52 @@ def pii_strip_event(
     event,
     json_dumps: Callable[[Any], Any] = json.dumps,
     json_loads: Callable[[str | bytes], Any] = json.loads,
+    *,
+    hash_key: str | None = None,
 ):
     """
     Scrub an event using new PII stripping config.
+
+    `hash_key` is the secret key for keyed hash redactions. Without a key, values matched by such
+    rules are removed.
+    """
+    raw_config = encode_str(json_dumps(config))
+    raw_event = encode_str(json_dumps(event))
+    if hash_key is None:
+        raw_rv = rustcall(lib.relay_pii_strip_event, raw_config, raw_event)
+    else:
+        raw_hash_key = encode_str(hash_key)
+        raw_rv = rustcall(
+            lib.relay_pii_strip_event_with_hash_key, raw_config, raw_event, raw_hash_key
+        )
+    return json_loads(decode_str(raw_rv, free=True))
+
+
+def pii_explain_event(
+    config,
+    event,
+    json_dumps: Callable[[Any], Any] = json.dumps,
+    json_loads: Callable[[str | bytes], Any] = json.loads,
+    *,
+    scope: str | None = None,
+    hash_key: str | None = None,
+):
+    """
+    Explain which PII rules redact which values of an event, without modifying
+    the event. Returns a list of dicts with the path, selector, rule id,
+    redaction method and original length of each redacted value.
+
+    `scope` is the data category of the event, such as `"error"`, which enables
+    applications scoped to that category. `hash_key` is the secret key for keyed
+    hash redactions.
     """
     raw_config = encode_str(json_dumps(config))
     raw_event = encode_str(json_dumps(event))
-    raw_rv = rustcall(lib.relay_pii_strip_event, raw_config, raw_event)
+    raw_scope = encode_str(scope or "")
+    raw_hash_key = encode_str(hash_key or "")
+    raw_rv = rustcall(
+        lib.relay_pii_explain_event, raw_config, raw_event, raw_scope, raw_hash_key
+    )
     return json_loads(decode_str(raw_rv, free=True))
 
 
@@ -188,6 +188,49 @@ def test_pii_strip_event():
     assert sentry_relay.pii_strip_event({}, event) == event
 
 
+def test_pii_strip_event_keyed_hash():
+    config = {
+        "rules": {"0": {"type": "email", "redaction": {"method": "keyed_hash"}}},
+        "applications": {"$string": ["0"]},
+    }
+    event = {"logentry": {"formatted": "test@example.com"}}
+
+    hashed = sentry_relay.pii_strip_event(config, event, hash_key="secret")
+    assert hashed["logentry"]["formatted"] not in ("", "test@example.com")
+
+    removed = sentry_relay.pii_strip_event(config, event)
+    assert removed["logentry"]["formatted"] == ""
+
+
+def test_pii_explain_event():
+    config = {"applications": {"$string": ["@ip"]}}
+    event = {"logentry": {"formatted": "from 127.0.0.1"}}
+    explanations = sentry_relay.pii_explain_event(config, event)
+    assert [
+        (
+            e["path"],
+            e["selector"],
+            e["rule_id"],
+            e["original_range"],
+            e["original_length"],
+        )
+        for e in explanations
+    ] == [("logentry.formatted", "$string", "@ip", [5, 14], 9)]
+
+
+def test_pii_explain_event_scope():
+    config = {
+        "applications": {"$string": ["@ip"]},
+        "scopes": {"$string": ["transaction"]},
+    }
+    event = {"logentry": {"formatted": "from 127.0.0.1"}}
+
+    assert sentry_relay.pii_explain_event(config, event) == []
+    assert sentry_relay.pii_explain_event(config, event, scope="error") == []
+    explanations = sentry_relay.pii_explain_event(config, event, scope="transaction")
+    assert [e["rule_id"] for e in explanations] == ["@ip"]
+
+
 def test_pii_selector_suggestions_from_event():
     event = {"logentry": {"formatted": "hi"}}
     assert sentry_relay.pii_selector_suggestions_from_event(event) == [
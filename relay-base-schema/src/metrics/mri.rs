@@ -134,7 +134,7 @@ pub enum MetricNamespace {
 
 impl MetricNamespace {
     /// Returns all namespaces/variants of this enum.
-    pub fn all() -> [Self; 7] {
+    pub const fn all() -> [Self; 7] {
         [
             Self::Sessions,
             Self::Transactions,
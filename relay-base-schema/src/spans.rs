@@ -119,6 +119,33 @@ impl SpanStatus {
             SpanStatus::DataLoss => "data_loss",
         }
     }
+
+    /// Returns the status for a [gRPC status code](https://grpc.github.io/grpc/core/md_doc_statuscodes.html).
+    ///
+    /// The numeric values of span statuses are the gRPC status codes. Returns `None` for codes
+    /// outside of the range defined by gRPC.
+    pub fn from_grpc_code(code: i64) -> Option<Self> {
+        Some(match code {
+            0 => SpanStatus::Ok,
+            1 => SpanStatus::Cancelled,
+            2 => SpanStatus::Unknown,
+            3 => SpanStatus::InvalidArgument,
+            4 => SpanStatus::DeadlineExceeded,
+            5 => SpanStatus::NotFound,
+            6 => SpanStatus::AlreadyExists,
+            7 => SpanStatus::PermissionDenied,
+            8 => SpanStatus::ResourceExhausted,
+            9 => SpanStatus::FailedPrecondition,
+            10 => SpanStatus::Aborted,
+            11 => SpanStatus::OutOfRange,
+            12 => SpanStatus::Unimplemented,
+            13 => SpanStatus::InternalError,
+            14 => SpanStatus::Unavailable,
+            15 => SpanStatus::DataLoss,
+            16 => SpanStatus::Unauthenticated,
+            _ => return None,
+        })
+    }
 }
 
 impl AsRef<str> for SpanStatus {
@@ -192,33 +219,15 @@ impl FromValue for SpanStatus {
                     Annotated(None, meta)
                 }
             },
-            Annotated(Some(Value::I64(value)), mut meta) => Annotated(
-                Some(match value {
-                    0 => SpanStatus::Ok,
-                    1 => SpanStatus::Cancelled,
-                    2 => SpanStatus::Unknown,
-                    3 => SpanStatus::InvalidArgument,
-                    4 => SpanStatus::DeadlineExceeded,
-                    5 => SpanStatus::NotFound,
-                    6 => SpanStatus::AlreadyExists,
-                    7 => SpanStatus::PermissionDenied,
-                    8 => SpanStatus::ResourceExhausted,
-                    9 => SpanStatus::FailedPrecondition,
-                    10 => SpanStatus::Aborted,
-                    11 => SpanStatus::OutOfRange,
-                    12 => SpanStatus::Unimplemented,
-                    13 => SpanStatus::InternalError,
-                    14 => SpanStatus::Unavailable,
-                    15 => SpanStatus::DataLoss,
-                    16 => SpanStatus::Unauthenticated,
-                    _ => {
-                        meta.add_error(Error::expected("a trace status"));
-                        meta.set_original_value(Some(value));
-                        return Annotated(None, meta);
-                    }
-                }),
-                meta,
-            ),
+            Annotated(Some(Value::I64(value)), mut meta) => match SpanStatus::from_grpc_code(value)
+            {
+                Some(status) => Annotated(Some(status), meta),
+                None => {
+                    meta.add_error(Error::expected("a trace status"));
+                    meta.set_original_value(Some(value));
+                    Annotated(None, meta)
+                }
+            },
             Annotated(None, meta) => Annotated(None, meta),
             Annotated(Some(value), mut meta) => {
                 meta.add_error(Error::expected("a string"));
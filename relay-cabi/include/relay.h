@@ -601,10 +601,38 @@ struct RelayStr relay_convert_datascrubbing_config(const struct RelayStr *config
 
 /**
  * Scrub an event using new PII stripping config.
+ *
+ * Values matched by keyed hash redactions are removed. Use
+ * [`relay_pii_strip_event_with_hash_key`] to hash them instead.
  */
 struct RelayStr relay_pii_strip_event(const struct RelayStr *config,
                                       const struct RelayStr *event);
 
+/**
+ * Scrub an event using new PII stripping config and a secret key for keyed hash redactions.
+ *
+ * Pass an empty `hash_key` if there is no key, in which case values matched by keyed hash
+ * redactions are removed.
+ */
+struct RelayStr relay_pii_strip_event_with_hash_key(const struct RelayStr *config,
+                                                    const struct RelayStr *event,
+                                                    const struct RelayStr *hash_key);
+
+/**
+ * Explain which PII rules redact which values of an event, without modifying the event.
+ *
+ * Returns a JSON list of objects with the path of the value, the matching selector, the rule
+ * id, the redaction method and the range of the original value that was replaced.
+ *
+ * `scope` is the name of the event's data category, such as `"error"` or `"transaction"`, which
+ * enables applications scoped to that category. `hash_key` is the secret key for keyed hash
+ * redactions. Pass empty strings to omit them.
+ */
+struct RelayStr relay_pii_explain_event(const struct RelayStr *config,
+                                        const struct RelayStr *event,
+                                        const struct RelayStr *scope,
+                                        const struct RelayStr *hash_key);
+
 /**
  * Walk through the event and collect selectors that can be applied to it in a PII config. This
  * function is used in the UI to provide auto-completion of selectors.
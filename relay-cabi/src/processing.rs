@@ -10,6 +10,7 @@ use std::slice;
 use std::sync::OnceLock;
 
 use chrono::{DateTime, Utc};
+use relay_base_schema::data_category::DataCategory;
 use relay_cardinality::CardinalityLimit;
 use relay_common::glob::{glob_match_bytes, GlobOptions};
 use relay_dynamic_config::{normalize_json, GlobalConfig, ProjectConfig};
@@ -21,8 +22,8 @@ use relay_event_normalization::{
 use relay_event_schema::processor::{process_value, split_chunks, ProcessingState};
 use relay_event_schema::protocol::{Event, IpAddr, VALID_PLATFORMS};
 use relay_pii::{
-    selector_suggestions_from_value, DataScrubbingConfig, InvalidSelectorError, PiiConfig,
-    PiiConfigError, PiiProcessor, SelectorSpec,
+    explain_pii, selector_suggestions_from_value, DataScrubbingConfig, InvalidSelectorError,
+    PiiConfig, PiiConfigError, PiiProcessor, SelectorSpec,
 };
 use relay_protocol::{Annotated, Remark, RuleCondition};
 use relay_sampling::SamplingConfig;
@@ -272,6 +273,9 @@ pub unsafe extern "C" fn relay_store_normalizer_normalize_event(
         enrich_spans: false,
         max_tag_value_length: usize::MAX,
         span_description_rules: None,
+        span_grouping_rules: Default::default(), // only supported in relay
+        fingerprinting: None,                    // only supported in relay
+        stacktrace_rules: None,                  // only supported in relay
         performance_score: None,
         geoip_lookup: None,   // only supported in relay
         ai_model_costs: None, // only supported in relay
@@ -339,19 +343,72 @@ pub unsafe extern "C" fn relay_convert_datascrubbing_config(config: *const Relay
 }
 
 /// Scrub an event using new PII stripping config.
+///
+/// Values matched by keyed hash redactions are removed. Use
+/// [`relay_pii_strip_event_with_hash_key`] to hash them instead.
 #[no_mangle]
 #[relay_ffi::catch_unwind]
 pub unsafe extern "C" fn relay_pii_strip_event(
     config: *const RelayStr,
     event: *const RelayStr,
 ) -> RelayStr {
-    let config = serde_json::from_str::<PiiConfig>((*config).as_str())?;
-    let mut processor = PiiProcessor::new(config.compiled());
+    strip_event((*config).as_str(), (*event).as_str(), None)?
+}
 
-    let mut event = Annotated::<Event>::from_json((*event).as_str())?;
+/// Scrub an event using new PII stripping config and a secret key for keyed hash redactions.
+///
+/// Pass an empty `hash_key` if there is no key, in which case values matched by keyed hash
+/// redactions are removed.
+#[no_mangle]
+#[relay_ffi::catch_unwind]
+pub unsafe extern "C" fn relay_pii_strip_event_with_hash_key(
+    config: *const RelayStr,
+    event: *const RelayStr,
+    hash_key: *const RelayStr,
+) -> RelayStr {
+    let hash_key = Some((*hash_key).as_str()).filter(|key| !key.is_empty());
+    strip_event((*config).as_str(), (*event).as_str(), hash_key)?
+}
+
+fn strip_event(config: &str, event: &str, hash_key: Option<&str>) -> anyhow::Result<RelayStr> {
+    let config = serde_json::from_str::<PiiConfig>(config)?;
+    let mut processor = PiiProcessor::new(config.compiled()).with_hash_key(hash_key);
+
+    let mut event = Annotated::<Event>::from_json(event)?;
     process_value(&mut event, &mut processor, ProcessingState::root())?;
 
-    RelayStr::from_string(event.to_json()?)
+    Ok(RelayStr::from_string(event.to_json()?))
+}
+
+/// Explain which PII rules redact which values of an event, without modifying the event.
+///
+/// Returns a JSON list of objects with the path of the value, the matching selector, the rule
+/// id, the redaction method and the range of the original value that was replaced.
+///
+/// `scope` is the name of the event's data category, such as `"error"` or `"transaction"`, which
+/// enables applications scoped to that category. `hash_key` is the secret key for keyed hash
+/// redactions. Pass empty strings to omit them.
+#[no_mangle]
+#[relay_ffi::catch_unwind]
+pub unsafe extern "C" fn relay_pii_explain_event(
+    config: *const RelayStr,
+    event: *const RelayStr,
+    scope: *const RelayStr,
+    hash_key: *const RelayStr,
+) -> RelayStr {
+    let config = serde_json::from_str::<PiiConfig>((*config).as_str())?;
+    let event = Annotated::<Event>::from_json((*event).as_str())?;
+
+    let hash_key = Some((*hash_key).as_str()).filter(|key| !key.is_empty());
+    let mut processor = PiiProcessor::new(config.compiled()).with_hash_key(hash_key);
+    let scope = (*scope).as_str();
+    if !scope.is_empty() {
+        processor = processor.with_scope(DataCategory::from_name(scope));
+    }
+
+    let explanations = explain_pii(&event, processor)?;
+
+    RelayStr::from_string(serde_json::to_string(&explanations)?)
 }
 
 /// Walk through the event and collect selectors that can be applied to it in a PII config. This
@@ -15,7 +15,9 @@ use relay_kafka::{
     TopicAssignments,
 };
 use relay_metrics::aggregator::{AggregatorConfig, FlushBatching};
-use relay_metrics::{AggregatorServiceConfig, MetricNamespace, ScopedAggregatorConfig};
+use relay_metrics::{
+    AggregatorServiceConfig, MetricNamespace, ScopedAggregatorConfig, SnapshotConfig,
+};
 use relay_redis::RedisConfig;
 use serde::de::{DeserializeOwned, Unexpected, Visitor};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
@@ -567,6 +569,11 @@ struct Limits {
     max_attachment_size: ByteSize,
     /// The maximum combined size for all attachments in an envelope or request.
     max_attachments_size: ByteSize,
+    /// The maximum decompressed size of a compressed attachment that is scrubbed for PII.
+    ///
+    /// Attachments that exceed this size when decompressed are dropped if they need to be
+    /// scrubbed.
+    max_attachment_scrubbing_size: ByteSize,
     /// The maximum combined size for all client reports in an envelope or request.
     max_client_reports_size: ByteSize,
     /// The maximum payload size for a monitor check-in.
@@ -623,6 +630,7 @@ impl Default for Limits {
             max_event_size: ByteSize::mebibytes(1),
             max_attachment_size: ByteSize::mebibytes(100),
             max_attachments_size: ByteSize::mebibytes(100),
+            max_attachment_scrubbing_size: ByteSize::mebibytes(20),
             max_client_reports_size: ByteSize::kibibytes(4),
             max_check_in_size: ByteSize::kibibytes(100),
             max_envelope_size: ByteSize::mebibytes(100),
@@ -881,11 +889,42 @@ impl Default for EnvelopeSpool {
     }
 }
 
+/// Default interval to snapshot the metrics aggregator state, 60 seconds.
+fn spool_metrics_snapshot_interval() -> u64 {
+    60
+}
+
+/// Persistence configuration for the state of the metrics aggregators.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MetricsSpool {
+    /// Enables periodic snapshots of the metrics aggregators.
+    ///
+    /// Snapshots are written next to the envelope spool file, so this requires
+    /// `spool.envelopes.path` to be configured. Buckets from the last snapshot are restored when
+    /// Relay starts.
+    #[serde(default)]
+    enabled: bool,
+    /// The interval in seconds in which snapshots are written.
+    #[serde(default = "spool_metrics_snapshot_interval")]
+    snapshot_interval: u64,
+}
+
+impl Default for MetricsSpool {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            snapshot_interval: spool_metrics_snapshot_interval(), // 60s
+        }
+    }
+}
+
 /// Persistent buffering configuration.
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct Spool {
     #[serde(default)]
     envelopes: EnvelopeSpool,
+    #[serde(default)]
+    metrics: MetricsSpool,
 }
 
 /// Controls internal caching behavior.
@@ -1062,6 +1101,24 @@ pub struct Normalization {
     /// Level of normalization for Relay to apply to incoming data.
     #[serde(default)]
     pub level: NormalizationLevel,
+    /// Path to a user agent regexes file in the format of uap-core's `regexes.yaml`.
+    ///
+    /// If set, this file is used to parse user agents instead of the regexes built into Relay.
+    /// If the file cannot be loaded, Relay falls back to the built-in regexes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent_regexes: Option<PathBuf>,
+}
+
+/// Configuration for PII scrubbing in this Relay.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Pii {
+    /// Secret key for keyed hash redactions in PII rules.
+    ///
+    /// This key is used for `keyed_hash` redactions unless the project config provides its own
+    /// key. If neither is set, values matched by such rules are removed. Keys are only used in
+    /// processing mode or if full project configs are requested.
+    pub hash_key: Option<String>,
 }
 
 /// Configuration for the level of normalization this Relay should do.
@@ -1305,11 +1362,36 @@ pub struct AuthConfig {
     pub static_relays: HashMap<RelayId, RelayInfo>,
 }
 
+/// Default for the GeoIP database reload interval, 60s.
+fn default_geoip_reload_interval() -> u64 {
+    60
+}
+
 /// GeoIp database configuration options.
-#[derive(Serialize, Deserialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct GeoIpConfig {
     /// The path to GeoIP database.
     path: Option<PathBuf>,
+    /// The path to an additional GeoIP ASN or ISP database.
+    ///
+    /// This database is used to add the autonomous system number and the organization of the
+    /// network to the user's geo location.
+    network_path: Option<PathBuf>,
+    /// Interval in seconds to check the database files for changes on disk.
+    ///
+    /// Changed files are reloaded without restarting Relay. Set to `0` to disable reloading.
+    #[serde(default = "default_geoip_reload_interval")]
+    reload_interval: u64,
+}
+
+impl Default for GeoIpConfig {
+    fn default() -> Self {
+        Self {
+            path: None,
+            network_path: None,
+            reload_interval: default_geoip_reload_interval(),
+        }
+    }
 }
 
 /// Cardinality Limiter configuration options.
@@ -1455,6 +1537,8 @@ struct ConfigValues {
     #[serde(default)]
     normalization: Normalization,
     #[serde(default)]
+    pii: Pii,
+    #[serde(default)]
     cardinality_limiter: CardinalityLimiter,
     #[serde(default)]
     health: Health,
@@ -2068,6 +2152,23 @@ impl Config {
         self.values.spool.envelopes.max_memory_size.as_bytes()
     }
 
+    /// Returns the snapshot configuration for the metrics aggregators.
+    ///
+    /// Returns `None` if snapshots are disabled or no envelope spool path is configured, since
+    /// snapshots are stored in the same directory as the envelope spool.
+    pub fn spool_metrics_snapshot(&self) -> Option<SnapshotConfig> {
+        let spool = &self.values.spool;
+        if !spool.metrics.enabled {
+            return None;
+        }
+
+        let path = spool.envelopes.path.as_ref()?;
+        Some(SnapshotConfig {
+            directory: path.parent()?.to_owned(),
+            interval: Duration::from_secs(spool.metrics.snapshot_interval.max(1)),
+        })
+    }
+
     /// Returns the maximum size of an event payload in bytes.
     pub fn max_event_size(&self) -> usize {
         self.values.limits.max_event_size.as_bytes()
@@ -2084,6 +2185,11 @@ impl Config {
         self.values.limits.max_attachments_size.as_bytes()
     }
 
+    /// Returns the maximum decompressed size of an attachment that is scrubbed for PII.
+    pub fn max_attachment_scrubbing_size(&self) -> usize {
+        self.values.limits.max_attachment_scrubbing_size.as_bytes()
+    }
+
     /// Returns the maximum combined size of client reports in bytes.
     pub fn max_client_reports_size(&self) -> usize {
         self.values.limits.max_client_reports_size.as_bytes()
@@ -2226,6 +2332,18 @@ impl Config {
         self.values.normalization.level
     }
 
+    /// The path to the user agent regexes file, if configured.
+    pub fn user_agent_regexes_path(&self) -> Option<&Path> {
+        self.values.normalization.user_agent_regexes.as_deref()
+    }
+
+    /// Secret key for keyed hash redactions in PII rules.
+    ///
+    /// Project configs may override this key.
+    pub fn pii_hash_key(&self) -> Option<&str> {
+        self.values.pii.hash_key.as_deref()
+    }
+
     /// The path to the GeoIp database required for event processing.
     pub fn geoip_path(&self) -> Option<&Path> {
         self.values
@@ -2235,6 +2353,21 @@ impl Config {
             .or(self.values.processing.geoip_path.as_deref())
     }
 
+    /// The path to the GeoIp ASN or ISP database, if configured.
+    pub fn geoip_network_path(&self) -> Option<&Path> {
+        self.values.geoip.network_path.as_deref()
+    }
+
+    /// Interval to check the GeoIp databases for changes on disk.
+    ///
+    /// Returns `None` if reloading is disabled.
+    pub fn geoip_reload_interval(&self) -> Option<Duration> {
+        match self.values.geoip.reload_interval {
+            0 => None,
+            secs => Some(Duration::from_secs(secs)),
+        }
+    }
+
     /// Maximum future timestamp of ingested data.
     ///
     /// Events past this timestamp will be adjusted to `now()`. Sessions will be dropped.
@@ -2395,6 +2528,10 @@ impl Config {
             max_tag_key_length,
             max_tag_value_length,
             max_project_key_bucket_bytes,
+            // Namespace budgets are enforced by the configured aggregators.
+            max_namespace_bucket_bytes: BTreeMap::new(),
+            // Rollups are applied by the configured aggregators.
+            rollups: BTreeMap::new(),
             initial_delay: 30,
             flush_partitions: None,
             flush_batching: FlushBatching::Project,
@@ -46,6 +46,9 @@ const CACHE_SPAN_OPS: &[&str] = &[
     "cache.flush",
 ];
 
+/// A list of span ops for outgoing remote procedure calls, such as gRPC.
+const RPC_SPAN_OPS: &[&str] = &["grpc.client", "rpc.client"];
+
 const QUEUE_SPAN_OPS: &[&str] = &[
     "queue.task.*",
     "queue.submit.*",
@@ -141,6 +144,8 @@ pub fn hardcoded_span_metrics() -> Vec<(GroupKey, Vec<MetricSpec>, Vec<TagMappin
 
     let is_http = RuleCondition::eq("span.op", "http.client");
 
+    let is_rpc = RuleCondition::glob("span.op", RPC_SPAN_OPS);
+
     let is_queue_op = RuleCondition::glob("span.op", QUEUE_SPAN_OPS);
 
     let is_allowed_browser = RuleCondition::eq(
@@ -212,7 +217,8 @@ pub fn hardcoded_span_metrics() -> Vec<(GroupKey, Vec<MetricSpec>, Vec<TagMappin
                                 | is_resource.clone()
                                 | is_mobile.clone()
                                 | is_interaction.clone()
-                                | is_http.clone())
+                                | is_http.clone()
+                                | is_rpc.clone())
                             & duration_condition.clone(),
                     ),
                     tags: vec![
@@ -222,14 +228,15 @@ pub fn hardcoded_span_metrics() -> Vec<(GroupKey, Vec<MetricSpec>, Vec<TagMappin
                                 is_db.clone()
                                     | is_resource.clone()
                                     | is_mobile.clone()
-                                    | is_http.clone(),
+                                    | is_http.clone()
+                                    | is_rpc.clone(),
                             ),
                         Tag::with_key("transaction.op")
                             .from_field("span.sentry_tags.transaction.op")
                             .when(is_mobile.clone()),
                         Tag::with_key("span.action")
                             .from_field("span.sentry_tags.action")
-                            .when(is_db.clone()),
+                            .when(is_db.clone() | is_rpc.clone()),
                         Tag::with_key("span.category")
                             .from_field("span.sentry_tags.category")
                             .always(),
@@ -238,7 +245,12 @@ pub fn hardcoded_span_metrics() -> Vec<(GroupKey, Vec<MetricSpec>, Vec<TagMappin
                             .always(),
                         Tag::with_key("span.domain")
                             .from_field("span.sentry_tags.domain")
-                            .when(is_db.clone() | is_resource.clone() | is_http.clone()),
+                            .when(
+                                is_db.clone()
+                                    | is_resource.clone()
+                                    | is_http.clone()
+                                    | is_rpc.clone(),
+                            ),
                         Tag::with_key("span.group")
                             .from_field("span.sentry_tags.group")
                             .always(),
@@ -262,10 +274,10 @@ pub fn hardcoded_span_metrics() -> Vec<(GroupKey, Vec<MetricSpec>, Vec<TagMappin
                         Tag::with_key("resource.render_blocking_status")
                             .from_field("span.sentry_tags.resource.render_blocking_status")
                             .when(is_resource.clone()),
-                        // HTTP module:
+                        // HTTP and RPC modules:
                         Tag::with_key("span.status_code")
                             .from_field("span.sentry_tags.status_code")
-                            .when(is_http.clone()),
+                            .when(is_http.clone() | is_rpc.clone()),
                     ],
                 },
                 MetricSpec {
@@ -640,6 +652,7 @@ pub fn hardcoded_span_metrics() -> Vec<(GroupKey, Vec<MetricSpec>, Vec<TagMappin
                                 | is_resource.clone()
                                 | is_mobile.clone()
                                 | is_http.clone()
+                                | is_rpc.clone()
                                 | is_ai.clone() // guarded by is_addon
                                 | is_queue_op.clone())  // guarded by is_addon
                                 & duration_condition.clone(),
@@ -652,6 +665,7 @@ pub fn hardcoded_span_metrics() -> Vec<(GroupKey, Vec<MetricSpec>, Vec<TagMappin
                                 | is_resource.clone()
                                 | is_mobile.clone()
                                 | is_http.clone()
+                                | is_rpc.clone()
                                 | is_queue_op.clone()
                             | is_ai.clone() // guarded by is_addon
                                 | is_queue_op.clone())  // guarded by is_addon
@@ -666,6 +680,7 @@ pub fn hardcoded_span_metrics() -> Vec<(GroupKey, Vec<MetricSpec>, Vec<TagMappin
                                 | is_resource.clone()
                                 | is_mobile.clone()
                                 | is_http.clone()
+                                | is_rpc.clone()
                                 | is_queue_op.clone()| is_ai.clone() // guarded by is_addon
                                 | is_queue_op.clone())  // guarded by is_addon)
                                 & duration_condition.clone(),
@@ -676,10 +691,12 @@ pub fn hardcoded_span_metrics() -> Vec<(GroupKey, Vec<MetricSpec>, Vec<TagMappin
                         .when(is_db.clone() | is_mobile.clone() | is_http.clone()), // groups by method + txn, e.g. `GET /users`
                     Tag::with_key("span.action")
                         .from_field("span.sentry_tags.action")
-                        .when(is_db.clone()),
+                        .when(is_db.clone() | is_rpc.clone()),
                     Tag::with_key("span.domain")
                         .from_field("span.sentry_tags.domain")
-                        .when(is_db.clone() | is_resource.clone() | is_http.clone()),
+                        .when(
+                            is_db.clone() | is_resource.clone() | is_http.clone() | is_rpc.clone(),
+                        ),
                     // Mobile module:
                     Tag::with_key("device.class")
                         .from_field("span.sentry_tags.device.class")
@@ -713,10 +730,10 @@ pub fn hardcoded_span_metrics() -> Vec<(GroupKey, Vec<MetricSpec>, Vec<TagMappin
                     Tag::with_key("resource.render_blocking_status")
                         .from_field("span.sentry_tags.resource.render_blocking_status")
                         .when(is_resource.clone()),
-                    // HTTP module:
+                    // HTTP and RPC modules:
                     Tag::with_key("span.status_code")
                         .from_field("span.sentry_tags.status_code")
-                        .when(is_http.clone()),
+                        .when(is_http.clone() | is_rpc.clone()),
                 ],
             }],
         ),
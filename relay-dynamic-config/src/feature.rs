@@ -64,6 +64,12 @@ pub enum Feature {
     #[serde(rename = "projects:profiling-ingest-unsampled-profiles")]
     IngestUnsampledProfiles,
 
+    /// Enables data scrubbing of profiles and profile chunks.
+    ///
+    /// Serialized as `projects:profiling-pii-scrubbing`.
+    #[serde(rename = "projects:profiling-pii-scrubbing")]
+    ProfileScrubbing,
+
     /// Discard transactions in a spans-only world.
     ///
     /// Serialized as `projects:discard-transaction`.
@@ -103,6 +109,13 @@ pub enum Feature {
     #[serde(rename = "organizations:indexed-spans-extraction")]
     ExtractSpansFromEvent,
 
+    /// Appends the executed GraphQL operation to the names of transactions serving GraphQL
+    /// requests.
+    ///
+    /// Serialized as `projects:graphql-transaction-names`.
+    #[serde(rename = "projects:graphql-transaction-names")]
+    GraphQlTransactionNames,
+
     /// Deprecated, still forwarded for older downstream Relays.
     #[doc(hidden)]
     #[serde(rename = "organizations:transaction-name-mark-scrubbed-as-sanitized")]
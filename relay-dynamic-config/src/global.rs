@@ -6,7 +6,7 @@ use std::path::Path;
 
 use relay_base_schema::metrics::MetricNamespace;
 use relay_event_normalization::{MeasurementsConfig, ModelCosts};
-use relay_filter::GenericFiltersConfig;
+use relay_filter::{GenericFiltersConfig, LegacyBrowsersConfig, WebCrawlersConfig};
 use relay_quotas::Quota;
 use serde::{de, Deserialize, Serialize};
 use serde_json::Value;
@@ -33,6 +33,18 @@ pub struct GlobalConfig {
     /// applying.
     #[serde(skip_serializing_if = "is_err_or_empty")]
     pub filters: ErrorBoundary<GenericFiltersConfig>,
+    /// Additional web crawlers for the inbound filter.
+    ///
+    /// These patterns extend the web crawlers built into Relay and apply to all projects that
+    /// enable the web crawlers filter.
+    #[serde(skip_serializing_if = "is_err_or_empty_web_crawlers")]
+    pub web_crawlers: ErrorBoundary<WebCrawlersConfig>,
+    /// Additional legacy browsers for the inbound filter.
+    ///
+    /// These patterns extend the browser versions selected in project configs and apply to all
+    /// projects that enable the legacy browsers filter.
+    #[serde(skip_serializing_if = "is_err_or_empty_legacy_browsers")]
+    pub legacy_browsers: ErrorBoundary<LegacyBrowsersConfig>,
     /// Sentry options passed down to Relay.
     #[serde(
         deserialize_with = "default_on_error",
@@ -76,6 +88,22 @@ impl GlobalConfig {
         }
     }
 
+    /// Returns the additional web crawlers for the inbound filter.
+    pub fn web_crawlers(&self) -> Option<&WebCrawlersConfig> {
+        match &self.web_crawlers {
+            ErrorBoundary::Err(_) => None,
+            ErrorBoundary::Ok(c) => Some(c),
+        }
+    }
+
+    /// Returns the additional legacy browsers for the inbound filter.
+    pub fn legacy_browsers(&self) -> Option<&LegacyBrowsersConfig> {
+        match &self.legacy_browsers {
+            ErrorBoundary::Err(_) => None,
+            ErrorBoundary::Ok(c) => Some(c),
+        }
+    }
+
     /// Modifies the global config after deserialization.
     ///
     /// - Adds hard-coded groups to metrics extraction configs.
@@ -103,6 +131,20 @@ fn is_err_or_empty(filters_config: &ErrorBoundary<GenericFiltersConfig>) -> bool
     }
 }
 
+fn is_err_or_empty_web_crawlers(web_crawlers: &ErrorBoundary<WebCrawlersConfig>) -> bool {
+    match web_crawlers {
+        ErrorBoundary::Err(_) => true,
+        ErrorBoundary::Ok(config) => config.is_empty(),
+    }
+}
+
+fn is_err_or_empty_legacy_browsers(legacy_browsers: &ErrorBoundary<LegacyBrowsersConfig>) -> bool {
+    match legacy_browsers {
+        ErrorBoundary::Err(_) => true,
+        ErrorBoundary::Ok(config) => config.is_empty(),
+    }
+}
+
 /// All options passed down from Sentry to Relay.
 #[derive(Default, Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(default)]
@@ -458,6 +500,19 @@ mod tests {
       }
     ]
   },
+  "webCrawlers": {
+    "patterns": [
+      "ExampleBot"
+    ],
+    "allowed": [
+      "ExampleBot/2"
+    ]
+  },
+  "legacyBrowsers": {
+    "patterns": [
+      "ExampleBrowser/1\\."
+    ]
+  },
   "options": {
     "profiling.profile_metrics.unsampled_profiles.enabled": true
   }
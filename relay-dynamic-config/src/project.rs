@@ -1,7 +1,7 @@
 use relay_auth::PublicKey;
 use relay_event_normalization::{
-    BreakdownsConfig, MeasurementsConfig, PerformanceScoreConfig, SpanDescriptionRule,
-    TransactionNameRule,
+    BreakdownsConfig, FingerprintingConfig, MeasurementsConfig, PerformanceScoreConfig,
+    SpanDescriptionRule, SpanGroupingRules, StacktraceRules, TransactionNameRule,
 };
 use relay_filter::ProjectFiltersConfig;
 use relay_pii::{DataScrubbingConfig, PiiConfig};
@@ -28,6 +28,12 @@ pub struct ProjectConfig {
     pub trusted_relays: Vec<PublicKey>,
     /// Configuration for PII stripping.
     pub pii_config: Option<PiiConfig>,
+    /// Secret key for keyed hash redactions in [`pii_config`](Self::pii_config).
+    ///
+    /// Takes precedence over the key in the Relay configuration. This key is never passed to
+    /// external Relays.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pii_hash_key: Option<String>,
     /// The grouping configuration.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub grouping_config: Option<Value>,
@@ -83,6 +89,15 @@ pub struct ProjectConfig {
     /// relays that might still need them.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub span_description_rules: Option<Vec<SpanDescriptionRule>>,
+    /// User-defined rules for grouping span descriptions and transaction names.
+    #[serde(skip_serializing_if = "SpanGroupingRules::is_empty")]
+    pub span_grouping_rules: SpanGroupingRules,
+    /// Configuration for server-side fingerprinting of error events.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fingerprinting: Option<ErrorBoundary<FingerprintingConfig>>,
+    /// Rules for marking stack frames as in-app and assigning categories to them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stacktrace_rules: Option<ErrorBoundary<StacktraceRules>>,
     /// Configuration for metrics.
     #[serde(default, skip_serializing_if = "skip_metrics")]
     pub metrics: ErrorBoundary<Metrics>,
@@ -108,6 +123,7 @@ impl Default for ProjectConfig {
             allowed_domains: vec!["*".to_string()],
             trusted_relays: vec![],
             pii_config: None,
+            pii_hash_key: None,
             grouping_config: None,
             filter_settings: ProjectFiltersConfig::default(),
             datascrubbing_settings: DataScrubbingConfig::default(),
@@ -125,6 +141,9 @@ impl Default for ProjectConfig {
             tx_name_rules: Vec::new(),
             tx_name_ready: false,
             span_description_rules: None,
+            span_grouping_rules: SpanGroupingRules::default(),
+            fingerprinting: None,
+            stacktrace_rules: None,
             metrics: Default::default(),
         }
     }
@@ -187,6 +206,12 @@ pub struct LimitedProjectConfig {
     /// relays that might still need them.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub span_description_rules: Option<Vec<SpanDescriptionRule>>,
+    #[serde(skip_serializing_if = "SpanGroupingRules::is_empty")]
+    pub span_grouping_rules: SpanGroupingRules,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fingerprinting: Option<ErrorBoundary<FingerprintingConfig>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stacktrace_rules: Option<ErrorBoundary<StacktraceRules>>,
 }
 
 fn is_false(value: &bool) -> bool {
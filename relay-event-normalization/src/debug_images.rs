@@ -0,0 +1,472 @@
+//! Normalization of native debug images and instruction addresses for symbolication.
+//!
+//! Native stack traces are symbolicated by looking up each instruction address in the debug image
+//! that was loaded at this address. This requires debug identifiers that match the debug files,
+//! non-overlapping address ranges, and instruction addresses without pointer authentication codes.
+//! The helpers in this module are shared by the normalization of events and profiles.
+
+use relay_event_schema::processor::{self, ProcessingAction};
+use relay_event_schema::protocol::{CodeId, DebugId, DebugImage, NativeDebugImage};
+use relay_protocol::{Annotated, Array, Error};
+use uuid::Uuid;
+
+/// Mask to strip pointer authentication codes from arm64 instruction addresses on Apple platforms.
+///
+/// See <https://github.com/microsoft/plcrashreporter/blob/748087386cfc517936315c107f722b146b0ad1ab/Source/PLCrashAsyncThread_arm.c#L84>.
+const POINTER_AUTHENTICATION_MASK: u64 = 0x0000000FFFFFFFFF;
+
+/// Returns the mask to strip pointer authentication codes from instruction addresses.
+///
+/// Pointer authentication is used on arm64 Apple platforms. Returns `None` if addresses must not
+/// be masked, including when the architecture is unknown.
+pub fn pointer_authentication_mask(platform: &str, architecture: Option<&str>) -> Option<u64> {
+    match (platform, architecture) {
+        ("cocoa", Some("arm64" | "arm64e")) => Some(POINTER_AUTHENTICATION_MASK),
+        _ => None,
+    }
+}
+
+/// Strips the pointer authentication code from an instruction address.
+///
+/// The `mask` is obtained from [`pointer_authentication_mask`].
+pub fn strip_pointer_authentication_code(addr: u64, mask: u64) -> u64 {
+    addr & mask
+}
+
+/// The address range of a debug image in the memory of a process.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ImageRange {
+    /// Address at which the image is loaded.
+    pub addr: u64,
+    /// Size of the image in bytes, or `0` if unknown.
+    pub size: u64,
+    /// Whether the image overlaps with the image loaded at the next higher address.
+    pub overlaps: bool,
+}
+
+/// Infers missing image sizes and flags overlapping images.
+///
+/// Images are ordered by their address, and an image without a size is assumed to span up to the
+/// next image. The size of the image at the highest address cannot be inferred and remains `0`.
+/// The order of `ranges` is not changed.
+pub fn layout_images(ranges: &mut [ImageRange]) {
+    let mut order: Vec<usize> = (0..ranges.len()).collect();
+    order.sort_by_key(|&index| ranges[index].addr);
+
+    for pair in order.windows(2) {
+        let next_addr = ranges[pair[1]].addr;
+        let range = &mut ranges[pair[0]];
+
+        if range.size == 0 {
+            range.size = next_addr - range.addr;
+        }
+
+        range.overlaps =
+            range.addr == next_addr || range.addr.saturating_add(range.size) > next_addr;
+    }
+}
+
+/// Decodes the first 16 bytes of a code identifier, padding shorter identifiers with zeros.
+fn code_id_prefix(code_id: &CodeId) -> Option<[u8; 16]> {
+    let hex = code_id.as_str();
+    if hex.is_empty() || hex.len() % 2 != 0 {
+        return None;
+    }
+
+    let mut bytes = [0; 16];
+    for (byte, chunk) in bytes.iter_mut().zip(hex.as_bytes().chunks(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(chunk).ok()?, 16).ok()?;
+    }
+
+    Some(bytes)
+}
+
+/// Derives the debug identifier of an ELF image from its build ID.
+///
+/// The debug identifier is the little-endian UUID representation of the first 16 bytes.
+fn elf_debug_id(code_id: &CodeId) -> Option<DebugId> {
+    code_id_prefix(code_id).map(|bytes| Uuid::from_bytes_le(bytes).into())
+}
+
+/// Derives the debug identifier of a Mach-O image from its `LC_UUID`, which is the same value.
+fn macho_debug_id(code_id: &CodeId) -> Option<DebugId> {
+    if code_id.as_str().len() != 32 {
+        return None;
+    }
+    code_id_prefix(code_id).map(|bytes| Uuid::from_bytes(bytes).into())
+}
+
+/// Parses the image size from the code identifier of a PE image.
+///
+/// PE code identifiers consist of the `time_date_stamp` padded to eight hex digits, followed by
+/// the unpadded `size_of_image`.
+pub fn pe_image_size(code_id: &CodeId) -> Option<u64> {
+    let size = code_id.as_str().get(8..).filter(|size| size.len() <= 8)?;
+    u64::from_str_radix(size, 16).ok().filter(|&size| size > 0)
+}
+
+/// Returns the canonical debug identifier of an ELF or Mach-O image.
+///
+/// These images are identified by a plain UUID, so a debug identifier with an age is replaced by
+/// its UUID. Returns `None` if the debug identifier is canonical already.
+pub fn canonical_debug_id(debug_id: &DebugId) -> Option<DebugId> {
+    (debug_id.appendix() != 0).then(|| debug_id.uuid().into())
+}
+
+/// Canonicalizes the debug identifier of an ELF or Mach-O image.
+///
+/// See [`canonical_debug_id`]. A missing debug identifier is derived from the code identifier.
+fn normalize_debug_id(image: &mut NativeDebugImage, derive: fn(&CodeId) -> Option<DebugId>) {
+    match image.debug_id.value() {
+        Some(debug_id) => {
+            if let Some(canonical) = canonical_debug_id(debug_id) {
+                let original = debug_id.clone();
+                image.debug_id.set_value(Some(canonical));
+                image.debug_id.meta_mut().set_original_value(Some(original));
+            }
+        }
+        // Do not replace debug identifiers that were sent but failed to parse.
+        None if image.debug_id.meta().original_value().is_some() => (),
+        None => {
+            if let Some(debug_id) = image.code_id.value().and_then(derive) {
+                image.debug_id = Annotated::new(debug_id);
+            }
+        }
+    }
+}
+
+/// Returns the native image if the debug image is loaded into the address space of the process.
+fn native_image(image: &mut DebugImage) -> Option<&mut NativeDebugImage> {
+    match image {
+        DebugImage::Symbolic(image)
+        | DebugImage::MachO(image)
+        | DebugImage::Elf(image)
+        | DebugImage::Pe(image) => Some(image),
+        _ => None,
+    }
+}
+
+/// Infers missing image sizes and flags overlapping images with an error.
+fn normalize_image_ranges(images: &mut Array<DebugImage>) {
+    let mut native_images: Vec<_> = images
+        .iter_mut()
+        .filter_map(|annotated_image| {
+            let Annotated(Some(image), meta) = annotated_image else {
+                return None;
+            };
+            let image = native_image(image)?;
+            let addr = image.image_addr.value()?.0;
+            Some((addr, image, meta))
+        })
+        .collect();
+
+    let mut ranges: Vec<_> = native_images
+        .iter()
+        .map(|(addr, image, _)| ImageRange {
+            addr: *addr,
+            size: image.image_size.value().copied().unwrap_or_default(),
+            overlaps: false,
+        })
+        .collect();
+
+    layout_images(&mut ranges);
+
+    for ((_, image, meta), range) in native_images.iter_mut().zip(ranges) {
+        if image.image_size.value().map_or(true, |&size| size == 0) && range.size > 0 {
+            image.image_size.set_value(Some(range.size));
+        }
+
+        if range.overlaps {
+            meta.add_error(Error::invalid("debug image overlaps with another image"));
+        }
+    }
+}
+
+/// Normalizes the debug images of an event for symbolication.
+///
+/// Removes debug images of unknown types, canonicalizes debug identifiers, infers missing image
+/// sizes, and flags images whose address ranges overlap.
+pub fn normalize_debug_images(images: &mut Array<DebugImage>) {
+    for annotated_image in images.iter_mut() {
+        let _ = processor::apply(annotated_image, |image, meta| {
+            match image {
+                DebugImage::Other(_) => {
+                    meta.add_error(Error::invalid("unsupported debug image type"));
+                    return Err(ProcessingAction::DeleteValueSoft);
+                }
+                DebugImage::Elf(image) => normalize_debug_id(image, elf_debug_id),
+                DebugImage::MachO(image) => normalize_debug_id(image, macho_debug_id),
+                DebugImage::Pe(image) => {
+                    if image.image_size.value().map_or(true, |&size| size == 0) {
+                        if let Some(size) = image.code_id.value().and_then(pe_image_size) {
+                            image.image_size.set_value(Some(size));
+                        }
+                    }
+                }
+                _ => (),
+            }
+            Ok(())
+        });
+    }
+
+    normalize_image_ranges(images);
+}
+
+#[cfg(test)]
+mod tests {
+    use relay_event_schema::protocol::DebugMeta;
+
+    use super::*;
+
+    fn normalize(json: &str) -> Annotated<DebugMeta> {
+        let mut debug_meta = Annotated::<DebugMeta>::from_json(json).unwrap();
+        if let Some(images) = debug_meta
+            .value_mut()
+            .as_mut()
+            .and_then(|d| d.images.value_mut())
+        {
+            normalize_debug_images(images);
+        }
+        debug_meta
+    }
+
+    #[test]
+    fn test_pointer_authentication_mask() {
+        assert_eq!(
+            pointer_authentication_mask("cocoa", Some("arm64e")),
+            Some(POINTER_AUTHENTICATION_MASK)
+        );
+        assert_eq!(pointer_authentication_mask("cocoa", None), None);
+        assert_eq!(pointer_authentication_mask("cocoa", Some("x86_64")), None);
+        assert_eq!(pointer_authentication_mask("native", Some("arm64")), None);
+    }
+
+    #[test]
+    fn test_strip_pointer_authentication_code() {
+        assert_eq!(
+            strip_pointer_authentication_code(0x8a5c_0001_9a6e_2c4c, POINTER_AUTHENTICATION_MASK),
+            0x0001_9a6e_2c4c
+        );
+    }
+
+    #[test]
+    fn test_layout_images() {
+        let mut ranges = [
+            ImageRange {
+                addr: 0x3000,
+                size: 0x1000,
+                overlaps: false,
+            },
+            ImageRange {
+                addr: 0x1000,
+                size: 0,
+                overlaps: false,
+            },
+            ImageRange {
+                addr: 0x2000,
+                size: 0x1800,
+                overlaps: false,
+            },
+        ];
+
+        layout_images(&mut ranges);
+
+        assert_eq!(
+            ranges,
+            [
+                ImageRange {
+                    addr: 0x3000,
+                    size: 0x1000,
+                    overlaps: false,
+                },
+                ImageRange {
+                    addr: 0x1000,
+                    size: 0x1000,
+                    overlaps: false,
+                },
+                ImageRange {
+                    addr: 0x2000,
+                    size: 0x1800,
+                    overlaps: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_layout_images_same_address() {
+        let mut ranges = [
+            ImageRange {
+                addr: 0x1000,
+                size: 0,
+                overlaps: false,
+            },
+            ImageRange {
+                addr: 0x1000,
+                size: 0,
+                overlaps: false,
+            },
+        ];
+
+        layout_images(&mut ranges);
+
+        assert!(ranges[0].overlaps);
+        assert_eq!(ranges[0].size, 0);
+    }
+
+    #[test]
+    fn test_derive_debug_ids() {
+        let debug_meta = normalize(
+            r#"{
+                "images": [
+                    {
+                        "type": "elf",
+                        "code_id": "f1c3bcc0279865fe3058404b2831d9e64135386c",
+                        "code_file": "/lib/libc.so.6",
+                        "image_addr": "0x1000",
+                        "image_size": 4096
+                    },
+                    {
+                        "type": "macho",
+                        "code_id": "84a04d240e603810a8c090a65e2df61a",
+                        "code_file": "/usr/lib/libDiagnosticMessagesClient.dylib",
+                        "image_addr": "0x2000",
+                        "image_size": 4096
+                    }
+                ]
+            }"#,
+        );
+
+        let images = debug_meta.value().unwrap().images.value().unwrap();
+        let debug_ids: Vec<_> = images
+            .iter()
+            .filter_map(|image| match image.value()? {
+                DebugImage::Elf(image) | DebugImage::MachO(image) => {
+                    Some(image.debug_id.value()?.to_string())
+                }
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(
+            debug_ids,
+            [
+                "c0bcc3f1-9827-fe65-3058-404b2831d9e6",
+                "84a04d24-0e60-3810-a8c0-90a65e2df61a",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_canonicalize_debug_id() {
+        let debug_meta = normalize(
+            r#"{
+                "images": [
+                    {
+                        "type": "macho",
+                        "code_file": "/usr/lib/libDiagnosticMessagesClient.dylib",
+                        "debug_id": "84a04d24-0e60-3810-a8c0-90a65e2df61a-1",
+                        "image_addr": "0x1000",
+                        "image_size": 4096
+                    }
+                ]
+            }"#,
+        );
+
+        let images = debug_meta.value().unwrap().images.value().unwrap();
+        let Some(DebugImage::MachO(image)) = images[0].value() else {
+            panic!("expected a Mach-O image");
+        };
+        assert_eq!(
+            image.debug_id.value().unwrap().to_string(),
+            "84a04d24-0e60-3810-a8c0-90a65e2df61a"
+        );
+        assert_eq!(
+            image.debug_id.meta().original_value(),
+            Some(&"84a04d24-0e60-3810-a8c0-90a65e2df61a-1".to_owned().into())
+        );
+    }
+
+    #[test]
+    fn test_infer_image_sizes() {
+        let debug_meta = normalize(
+            r#"{
+                "images": [
+                    {
+                        "type": "pe",
+                        "code_id": "5ab380779000",
+                        "code_file": "C:\\Windows\\System32\\dbghelp.dll",
+                        "debug_id": "9c2a902b-6fdf-40ad-8308-588a41d572a0-1",
+                        "image_addr": "0x70850000"
+                    },
+                    {
+                        "type": "elf",
+                        "code_file": "/lib/libgcc_s.so.1",
+                        "debug_id": "e20a2268-5dc6-c165-b6aa-a12fa6765a6e",
+                        "image_addr": "0x7f5140527000"
+                    },
+                    {
+                        "type": "elf",
+                        "code_file": "/lib/libc.so.6",
+                        "debug_id": "c0bcc3f1-9827-fe65-3058-404b2831d9e6",
+                        "image_addr": "0x7f514053d000"
+                    }
+                ]
+            }"#,
+        );
+
+        let sizes: Vec<_> = debug_meta
+            .value()
+            .unwrap()
+            .images
+            .value()
+            .unwrap()
+            .iter()
+            .map(|image| match image.value() {
+                Some(DebugImage::Pe(image) | DebugImage::Elf(image)) => {
+                    image.image_size.value().copied()
+                }
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(sizes, [Some(0x9000), Some(0x16000), None]);
+    }
+
+    #[test]
+    fn test_overlapping_images() {
+        let debug_meta = normalize(
+            r#"{
+                "images": [
+                    {
+                        "type": "elf",
+                        "code_file": "/lib/libgcc_s.so.1",
+                        "debug_id": "e20a2268-5dc6-c165-b6aa-a12fa6765a6e",
+                        "image_addr": "0x1000",
+                        "image_size": 8192
+                    },
+                    {
+                        "type": "elf",
+                        "code_file": "/lib/libc.so.6",
+                        "debug_id": "c0bcc3f1-9827-fe65-3058-404b2831d9e6",
+                        "image_addr": "0x2000",
+                        "image_size": 4096
+                    }
+                ]
+            }"#,
+        );
+
+        let images = debug_meta.value().unwrap().images.value().unwrap();
+        assert!(images[0].meta().has_errors());
+        assert!(!images[1].meta().has_errors());
+        assert!(images[0].value().is_some());
+    }
+
+    #[test]
+    fn test_other_images_removed() {
+        let debug_meta = normalize(r#"{"images": [{"type": "unknown"}]}"#);
+        let images = debug_meta.value().unwrap().images.value().unwrap();
+        assert!(images[0].value().is_none());
+        assert!(images[0].meta().has_errors());
+    }
+}
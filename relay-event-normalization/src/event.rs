@@ -15,10 +15,11 @@ use relay_base_schema::metrics::{
 };
 use relay_event_schema::processor::{self, ProcessingAction, ProcessingState, Processor};
 use relay_event_schema::protocol::{
-    AsPair, ClientSdkInfo, Context, ContextInner, Contexts, DebugImage, DeviceClass, Event,
-    EventId, EventType, Exception, Headers, IpAddr, Level, LogEntry, Measurement, Measurements,
-    MetricSummaryMapping, NelContext, PerformanceScoreContext, ReplayContext, Request, SpanStatus,
-    Tags, Timestamp, TraceContext, User, VALID_PLATFORMS,
+    AsPair, ClientSdkInfo, ContainerContext, Context, ContextInner, Contexts, DeviceClass,
+    DeviceContext, Event, EventId, EventType, Exception, Headers, IpAddr, KubernetesContext, Level,
+    LogEntry, Measurement, Measurements, MetricSummaryMapping, NelContext, PairList,
+    PerformanceScoreContext, ReplayContext, Request, SpanStatus, TagEntry, Tags, Timestamp,
+    TraceContext, User, VALID_PLATFORMS,
 };
 use relay_protocol::{
     Annotated, Empty, Error, ErrorKind, FromValue, IntoValue, Meta, Object, Remark, RemarkType,
@@ -32,10 +33,11 @@ use crate::span::ai::normalize_ai_measurements;
 use crate::span::tag_extraction::extract_span_tags_from_event;
 use crate::utils::{self, get_event_user_tag, MAX_DURATION_MOBILE_MS};
 use crate::{
-    breakdowns, event_error, legacy, mechanism, remove_other, schema, span, stacktrace,
-    transactions, trimming, user_agent, BreakdownsConfig, CombinedMeasurementsConfig, GeoIpLookup,
-    MaxChars, ModelCosts, PerformanceScoreConfig, RawUserAgentInfo, SpanDescriptionRule,
-    TransactionNameConfig,
+    breakdowns, debug_images, event_error, legacy, mechanism, normalize_grouping_hash,
+    remove_other, schema, span, stacktrace, transactions, trimming, user_agent, BreakdownsConfig,
+    CombinedMeasurementsConfig, FingerprintingConfig, GeoIpLookup, MaxChars, ModelCosts,
+    PerformanceScoreConfig, RawUserAgentInfo, SpanDescriptionRule, SpanGroupingRules,
+    StacktraceRules, TransactionNameConfig,
 };
 
 /// Configuration for [`normalize_event`].
@@ -131,6 +133,19 @@ pub struct NormalizationConfig<'a> {
     /// This is similar to `transaction_name_config`, but applies to span descriptions.
     pub span_description_rules: Option<&'a Vec<SpanDescriptionRule>>,
 
+    /// User-defined rules for grouping span descriptions.
+    ///
+    /// These are applied to the normalized description when extracting span tags.
+    pub span_grouping_rules: &'a SpanGroupingRules,
+
+    /// Configuration for server-side fingerprinting of error events.
+    ///
+    /// When set, a grouping hash is computed for every error event.
+    pub fingerprinting: Option<&'a FingerprintingConfig>,
+
+    /// Project rules for marking stack frames as in-app and assigning categories to them.
+    pub stacktrace_rules: Option<&'a StacktraceRules>,
+
     /// Configuration for generating performance score measurements for web vitals
     pub performance_score: Option<&'a PerformanceScoreConfig>,
 
@@ -178,6 +193,9 @@ impl<'a> Default for NormalizationConfig<'a> {
             enrich_spans: Default::default(),
             max_tag_value_length: usize::MAX,
             span_description_rules: Default::default(),
+            span_grouping_rules: Default::default(),
+            fingerprinting: Default::default(),
+            stacktrace_rules: Default::default(),
             performance_score: Default::default(),
             geoip_lookup: Default::default(),
             ai_model_costs: Default::default(),
@@ -283,14 +301,16 @@ fn normalize(event: &mut Event, meta: &mut Meta, config: &NormalizationConfig) {
     normalize_debug_meta(event);
     normalize_breadcrumbs(event);
     normalize_release_dist(event); // dist is a tag extracted along with other metrics from transactions
+    normalize_resource_contexts(&mut event.contexts); // Resource contexts are promoted to tags
     normalize_event_tags(event); // Tags are added to every metric
 
     // TODO: Consider moving to store normalization
     if config.device_class_synthesis_config {
         normalize_device_class(event);
     }
-    normalize_stacktraces(event);
+    normalize_stacktraces(event, config.stacktrace_rules);
     normalize_exceptions(event); // Browser extension filters look at the stacktrace
+    normalize_grouping_hash(event, config.fingerprinting); // Repeated errors filter looks at the hash
     normalize_user_agent(event, config.normalize_user_agent); // Legacy browsers filter
     normalize_event_measurements(
         event,
@@ -317,7 +337,11 @@ fn normalize(event: &mut Event, meta: &mut Meta, config: &NormalizationConfig) {
     }
 
     if config.enrich_spans {
-        extract_span_tags_from_event(event, config.max_tag_value_length);
+        extract_span_tags_from_event(
+            event,
+            config.max_tag_value_length,
+            config.span_grouping_rules,
+        );
     }
 
     if let Some(context) = event.context_mut::<TraceContext>() {
@@ -531,15 +555,7 @@ fn normalize_debug_meta(event: &mut Event) {
         return;
     };
 
-    for annotated_image in debug_images {
-        let _ = processor::apply(annotated_image, |image, meta| match image {
-            DebugImage::Other(_) => {
-                meta.add_error(Error::invalid("unsupported debug image type"));
-                Err(ProcessingAction::DeleteValueSoft)
-            }
-            _ => Ok(()),
-        });
-    }
+    debug_images::normalize_debug_images(debug_images);
 }
 
 fn normalize_breadcrumbs(event: &mut Event) {
@@ -605,6 +621,52 @@ impl DedupCache {
     }
 }
 
+/// Adds contexts derived from the OpenTelemetry resource of the event.
+fn normalize_resource_contexts(contexts: &mut Annotated<Contexts>) {
+    if let Some(contexts) = contexts.value_mut() {
+        crate::normalize::contexts::normalize_resource_contexts(contexts);
+    }
+}
+
+/// Promotes select fields of the Kubernetes and container contexts to tags.
+///
+/// Tags that were set explicitly take precedence.
+fn promote_context_tags(contexts: &Annotated<Contexts>, tags: &mut PairList<TagEntry>) {
+    let Some(contexts) = contexts.value() else {
+        return;
+    };
+
+    let kubernetes = contexts.get::<KubernetesContext>();
+    let container = contexts.get::<ContainerContext>();
+
+    let promoted = [
+        (
+            "kubernetes.cluster",
+            kubernetes.and_then(|k| k.cluster_name.value()),
+        ),
+        (
+            "kubernetes.namespace",
+            kubernetes.and_then(|k| k.namespace.value()),
+        ),
+        (
+            "kubernetes.deployment",
+            kubernetes.and_then(|k| k.deployment_name.value()),
+        ),
+        (
+            "container.image",
+            container.and_then(|c| c.image_name.value()),
+        ),
+    ];
+
+    for (key, value) in promoted {
+        if let Some(value) = value {
+            if !tags.contains(key) {
+                tags.insert(key.to_owned(), Annotated::new(value.clone()));
+            }
+        }
+    }
+}
+
 /// Removes internal tags and adds tags for well-known attributes.
 fn normalize_event_tags(event: &mut Event) {
     let tags = &mut event.tags.value_mut().get_or_insert_with(Tags::default).0;
@@ -618,6 +680,8 @@ fn normalize_event_tags(event: &mut Event) {
         environment.get_or_insert_with(|| tag);
     }
 
+    promote_context_tags(&event.contexts, tags);
+
     // Remove internal tags, that are generated with a `sentry:` prefix when saving the event.
     // They are not allowed to be set by the client due to ambiguity. Also, deduplicate tags.
     let mut tag_cache = DedupCache::new();
@@ -686,24 +750,43 @@ fn normalize_device_class(event: &mut Event) {
 ///
 /// Normalized stack traces are `event.stacktrace`, `event.exceptions.stacktrace`, and
 /// `event.thread.stacktrace`. Raw stack traces are not normalized.
-fn normalize_stacktraces(event: &mut Event) {
-    normalize_event_stacktrace(event);
-    normalize_exception_stacktraces(event);
-    normalize_thread_stacktraces(event);
+fn normalize_stacktraces(event: &mut Event, rules: Option<&StacktraceRules>) {
+    let platform = event.platform.value().cloned();
+    let platform = platform.as_deref();
+
+    let architecture = event
+        .context::<DeviceContext>()
+        .and_then(|device| device.arch.as_str());
+    let pac_mask = platform
+        .and_then(|platform| debug_images::pointer_authentication_mask(platform, architecture));
+
+    normalize_event_stacktrace(event, rules, platform, pac_mask);
+    normalize_exception_stacktraces(event, rules, platform, pac_mask);
+    normalize_thread_stacktraces(event, rules, platform, pac_mask);
 }
 
 /// Normalizes an event's stack trace, in `event.stacktrace`.
-fn normalize_event_stacktrace(event: &mut Event) {
+fn normalize_event_stacktrace(
+    event: &mut Event,
+    rules: Option<&StacktraceRules>,
+    platform: Option<&str>,
+    pac_mask: Option<u64>,
+) {
     let Annotated(Some(stacktrace), meta) = &mut event.stacktrace else {
         return;
     };
-    stacktrace::normalize_stacktrace(&mut stacktrace.0, meta);
+    stacktrace::normalize_stacktrace(&mut stacktrace.0, meta, rules, platform, pac_mask);
 }
 
 /// Normalizes the stack traces in an event's exceptions, in `event.exceptions.stacktraces`.
 ///
 /// Note: the raw stack traces, in `event.exceptions.raw_stacktraces` is not normalized.
-fn normalize_exception_stacktraces(event: &mut Event) {
+fn normalize_exception_stacktraces(
+    event: &mut Event,
+    rules: Option<&StacktraceRules>,
+    platform: Option<&str>,
+    pac_mask: Option<u64>,
+) {
     let Some(event_exception) = event.exceptions.value_mut() else {
         return;
     };
@@ -715,7 +798,7 @@ fn normalize_exception_stacktraces(event: &mut Event) {
             continue;
         };
         if let Annotated(Some(stacktrace), meta) = &mut exception.stacktrace {
-            stacktrace::normalize_stacktrace(&mut stacktrace.0, meta);
+            stacktrace::normalize_stacktrace(&mut stacktrace.0, meta, rules, platform, pac_mask);
         }
     }
 }
@@ -723,7 +806,12 @@ fn normalize_exception_stacktraces(event: &mut Event) {
 /// Normalizes the stack traces in an event's threads, in `event.threads.stacktraces`.
 ///
 /// Note: the raw stack traces, in `event.threads.raw_stacktraces`, is not normalized.
-fn normalize_thread_stacktraces(event: &mut Event) {
+fn normalize_thread_stacktraces(
+    event: &mut Event,
+    rules: Option<&StacktraceRules>,
+    platform: Option<&str>,
+    pac_mask: Option<u64>,
+) {
     let Some(event_threads) = event.threads.value_mut() else {
         return;
     };
@@ -735,7 +823,7 @@ fn normalize_thread_stacktraces(event: &mut Event) {
             continue;
         };
         if let Annotated(Some(stacktrace), meta) = &mut thread.stacktrace {
-            stacktrace::normalize_stacktrace(&mut stacktrace.0, meta);
+            stacktrace::normalize_stacktrace(&mut stacktrace.0, meta, rules, platform, pac_mask);
         }
     }
 }
@@ -1420,7 +1508,7 @@ mod tests {
     use itertools::Itertools;
     use relay_common::glob2::LazyGlob;
     use relay_event_schema::protocol::{
-        Breadcrumb, Csp, DebugMeta, DeviceContext, MetricSummary, MetricsSummary, Span, Values,
+        Breadcrumb, Csp, DebugImage, DebugMeta, MetricSummary, MetricsSummary, Span, Values,
     };
     use relay_protocol::{get_value, SerializableAnnotated};
     use serde_json::json;
@@ -3815,4 +3903,71 @@ mod tests {
         );
         assert!(get_value!(event.spans[0].exclusive_time).is_some());
     }
+
+    #[test]
+    fn test_normalize_stacktrace_rules() {
+        let json = r#"{
+            "platform": "python",
+            "exception": {
+                "values": [
+                    {
+                        "type": "ValueError",
+                        "stacktrace": {
+                            "frames": [
+                                {"module": "django.core.handlers", "function": "get_response"},
+                                {"module": "myapp.views", "function": "index"}
+                            ]
+                        }
+                    }
+                ]
+            },
+            "threads": {
+                "values": [
+                    {
+                        "stacktrace": {
+                            "frames": [{"module": "myapp.worker", "function": "run"}]
+                        }
+                    }
+                ]
+            }
+        }"#;
+
+        let rules: StacktraceRules = "family:other module:myapp.* +app".parse().unwrap();
+
+        let mut event = Annotated::<Event>::from_json(json).unwrap();
+        normalize_event(
+            &mut event,
+            &NormalizationConfig {
+                stacktrace_rules: Some(&rules),
+                ..Default::default()
+            },
+        );
+
+        let frames = get_value!(event.exceptions.values[0].stacktrace.frames!);
+        assert_eq!(frames[0].value().unwrap().in_app.value(), None);
+        assert_eq!(frames[1].value().unwrap().in_app.value(), Some(&true));
+
+        let frame = get_value!(event.threads.values[0].stacktrace.frames[0]!);
+        assert_eq!(frame.in_app.value(), Some(&true));
+    }
+
+    #[test]
+    fn test_promote_context_tags() {
+        let json = r#"{
+            "tags": {"kubernetes.namespace": "explicit"},
+            "contexts": {
+                "kubernetes": {"cluster_name": "production", "namespace": "checkout"},
+                "otel": {"resource": {"container.image.name": "gcr.io/example/checkout"}}
+            }
+        }"#;
+
+        let mut event = Annotated::<Event>::from_json(json).unwrap();
+        normalize_event(&mut event, &NormalizationConfig::default());
+
+        let tags = get_value!(event.tags!);
+        assert_eq!(tags.get("kubernetes.cluster"), Some("production"));
+        assert_eq!(tags.get("kubernetes.namespace"), Some("explicit"));
+        assert_eq!(tags.get("container.image"), Some("gcr.io/example/checkout"));
+        assert_eq!(tags.get("kubernetes.deployment"), None);
+    }
 }
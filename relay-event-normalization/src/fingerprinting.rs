@@ -0,0 +1,446 @@
+//! Server-side fingerprinting of error events.
+//!
+//! Fingerprinting rules assign a custom fingerprint to errors that match a [`RuleCondition`].
+//! Relay computes a grouping hash from the fingerprint and stores it in the event, so that errors
+//! can be deduplicated and rate limited by issue before they are grouped downstream.
+//!
+//! Conditions are evaluated against every exception and stack frame of the event, and a rule
+//! matches if its condition matches any of them. The following fields are available:
+//!
+//!  - `error.type`: The type of the exception.
+//!  - `error.value`: The value of the exception.
+//!  - `error.module`: The module of the exception.
+//!  - `error.mechanism`: The type of the exception mechanism.
+//!  - `error.message`: The log message of the event.
+//!  - `frame.function`: The function of the stack frame.
+//!  - `frame.module`: The module of the stack frame.
+//!  - `frame.path`: The absolute path of the stack frame, falling back to the file name.
+//!  - `frame.package`: The package of the stack frame.
+//!  - `frame.in_app`: Whether the stack frame is in app.
+//!
+//! For example, the following rule groups all connection errors raised by the database driver into
+//! one issue:
+//!
+//! ```json
+//! {
+//!   "condition": {
+//!     "op": "and",
+//!     "inner": [
+//!       {"op": "glob", "name": "error.type", "value": ["*ConnectionError"]},
+//!       {"op": "glob", "name": "frame.module", "value": ["psycopg2.*"]}
+//!     ]
+//!   },
+//!   "fingerprint": ["database-connection-error"]
+//! }
+//! ```
+
+use relay_base_schema::events::EventType;
+use relay_event_schema::protocol::{Event, Exception, Frame};
+use relay_protocol::{Annotated, Getter, RuleCondition, Val};
+use serde::{Deserialize, Serialize};
+
+/// The fingerprint entry that stands for the default grouping.
+const DEFAULT_FINGERPRINT: &str = "{{default}}";
+
+/// Configuration for server-side fingerprinting.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct FingerprintingConfig {
+    /// The fingerprinting rules, in the order they are evaluated.
+    ///
+    /// The first matching rule determines the fingerprint of the event.
+    #[serde(default)]
+    pub rules: Vec<FingerprintingRule>,
+}
+
+/// A rule that assigns a custom fingerprint to matching errors.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FingerprintingRule {
+    /// The condition that an exception or stack frame of the error must match.
+    pub condition: RuleCondition,
+    /// The fingerprint assigned to matching errors.
+    ///
+    /// The entry `{{ default }}` stands for the default grouping of the event.
+    pub fingerprint: Vec<String>,
+}
+
+/// An exception and stack frame of an event that conditions are evaluated against.
+#[derive(Clone, Copy, Debug)]
+struct Candidate<'a> {
+    exception: Option<&'a Exception>,
+    frame: Option<&'a Frame>,
+    message: Option<&'a str>,
+}
+
+impl Getter for Candidate<'_> {
+    fn get_value(&self, path: &str) -> Option<Val<'_>> {
+        Some(match path {
+            "error.type" => self.exception?.ty.as_str()?.into(),
+            "error.value" => self.exception?.value.as_str()?.into(),
+            "error.module" => self.exception?.module.as_str()?.into(),
+            "error.mechanism" => self.exception?.mechanism.value()?.ty.as_str()?.into(),
+            "error.message" => self.message?.into(),
+            "frame.function" => self.frame?.function.as_str()?.into(),
+            "frame.module" => self.frame?.module.as_str()?.into(),
+            "frame.path" => frame_path(self.frame?)?.into(),
+            "frame.package" => self.frame?.package.as_str()?.into(),
+            "frame.in_app" => (*self.frame?.in_app.value()?).into(),
+            _ => return None,
+        })
+    }
+}
+
+/// Returns the absolute path of the frame, falling back to the file name.
+fn frame_path(frame: &Frame) -> Option<&str> {
+    frame
+        .abs_path
+        .value()
+        .or_else(|| frame.filename.value())
+        .map(|path| path.as_str())
+}
+
+/// Returns the exceptions of the event, starting with the most recent one.
+fn exceptions(event: &Event) -> impl Iterator<Item = &Exception> {
+    event
+        .exceptions
+        .value()
+        .and_then(|values| values.values.value())
+        .into_iter()
+        .flatten()
+        .rev()
+        .filter_map(|exception| exception.value())
+}
+
+/// Returns the stack frames of the exception, starting with the innermost one.
+fn frames(exception: &Exception) -> impl Iterator<Item = &Frame> {
+    exception
+        .stacktrace
+        .value()
+        .and_then(|stacktrace| stacktrace.frames.value())
+        .into_iter()
+        .flatten()
+        .rev()
+        .filter_map(|frame| frame.value())
+}
+
+/// Returns the log message of the event, preferring the unformatted message.
+fn message(event: &Event) -> Option<&str> {
+    let logentry = event.logentry.value()?;
+    let message = logentry.message.value().or(logentry.formatted.value())?;
+    Some(message.as_ref())
+}
+
+/// Returns `true` if the condition matches any exception or stack frame of the event.
+fn matches(condition: &RuleCondition, event: &Event) -> bool {
+    let message = message(event);
+    let mut has_exceptions = false;
+
+    for exception in exceptions(event) {
+        has_exceptions = true;
+
+        let mut has_frames = false;
+        for frame in frames(exception) {
+            has_frames = true;
+
+            let candidate = Candidate {
+                exception: Some(exception),
+                frame: Some(frame),
+                message,
+            };
+            if condition.matches(&candidate) {
+                return true;
+            }
+        }
+
+        let candidate = Candidate {
+            exception: Some(exception),
+            frame: None,
+            message,
+        };
+        if !has_frames && condition.matches(&candidate) {
+            return true;
+        }
+    }
+
+    let candidate = Candidate {
+        exception: None,
+        frame: None,
+        message,
+    };
+    !has_exceptions && message.is_some() && condition.matches(&candidate)
+}
+
+/// Returns `true` if the entry stands for the default grouping, ignoring whitespace.
+fn is_default(part: &str) -> bool {
+    part.chars()
+        .filter(|c| !c.is_whitespace())
+        .eq(DEFAULT_FINGERPRINT.chars())
+}
+
+/// Returns the components of the default grouping of the event.
+///
+/// Exceptions are grouped by the type of the most recent exception and the module and function of
+/// its in-app frames. If there are no in-app frames, all frames are used, and if there is no stack
+/// trace, the exception value is used instead. Other errors are grouped by their log message.
+fn default_components(event: &Event) -> Vec<&str> {
+    let Some(exception) = exceptions(event).next() else {
+        return message(event).into_iter().collect();
+    };
+
+    let mut components = vec![exception.ty.as_str().unwrap_or_default()];
+
+    let has_in_app = frames(exception).any(|frame| frame.in_app.value() == Some(&true));
+    let mut has_frames = false;
+    for frame in frames(exception) {
+        if has_in_app && frame.in_app.value() != Some(&true) {
+            continue;
+        }
+
+        has_frames = true;
+        let module = frame.module.as_str().or_else(|| frame_path(frame));
+        components.push(module.unwrap_or_default());
+        components.push(frame.function.as_str().unwrap_or_default());
+    }
+
+    if !has_frames {
+        components.push(exception.value.as_str().unwrap_or_default());
+    }
+
+    components
+}
+
+/// Returns `true` if the event is an error that can be fingerprinted.
+fn is_error(event: &Event) -> bool {
+    matches!(
+        event.ty.value(),
+        None | Some(EventType::Error | EventType::Default)
+    ) && (exceptions(event).next().is_some() || message(event).is_some())
+}
+
+/// Returns the first fingerprinting rule that matches the event.
+pub fn find_fingerprinting_rule<'a>(
+    config: &'a FingerprintingConfig,
+    event: &Event,
+) -> Option<&'a FingerprintingRule> {
+    config
+        .rules
+        .iter()
+        .find(|rule| matches(&rule.condition, event))
+}
+
+/// Computes the grouping hash of an error event.
+///
+/// The hash is computed from the fingerprint of the first matching rule. If no rule matches, the
+/// fingerprint sent with the event is used, and otherwise the default grouping. Returns `None` if
+/// the event is not an error.
+pub fn compute_grouping_hash(config: &FingerprintingConfig, event: &Event) -> Option<String> {
+    if !is_error(event) {
+        return None;
+    }
+
+    let fingerprint = match find_fingerprinting_rule(config, event) {
+        Some(rule) => Some(rule.fingerprint.as_slice()),
+        None => event.fingerprint.value().map(|f| f.as_slice()),
+    };
+
+    let mut context = md5::Context::new();
+    let mut consume = |part: &str| {
+        context.consume(part.as_bytes());
+        context.consume([0]);
+    };
+
+    match fingerprint.filter(|parts| !parts.is_empty()) {
+        Some(parts) => {
+            for part in parts {
+                if is_default(part) {
+                    default_components(event).into_iter().for_each(&mut consume);
+                } else {
+                    consume(part);
+                }
+            }
+        }
+        None => default_components(event).into_iter().for_each(consume),
+    }
+
+    Some(format!("{:x}", context.compute()))
+}
+
+/// Computes the grouping hash of the event and stores it in [`Event::grouping_hash`].
+///
+/// Without a fingerprinting config, the grouping hash is removed from the event.
+pub fn normalize_grouping_hash(event: &mut Event, config: Option<&FingerprintingConfig>) {
+    let grouping_hash = config.and_then(|config| compute_grouping_hash(config, event));
+    event.grouping_hash = Annotated::from(grouping_hash);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_event(json: &str) -> Event {
+        Annotated::<Event>::from_json(json)
+            .unwrap()
+            .into_value()
+            .unwrap()
+    }
+
+    fn get_config(json: &str) -> FingerprintingConfig {
+        serde_json::from_str(json).unwrap()
+    }
+
+    const EVENT: &str = r#"{
+        "exception": {
+            "values": [
+                {
+                    "type": "OperationalError",
+                    "value": "could not connect to server",
+                    "module": "psycopg2",
+                    "stacktrace": {
+                        "frames": [
+                            {"function": "handle", "module": "app.views", "in_app": true},
+                            {"function": "connect", "module": "psycopg2.pool", "in_app": false}
+                        ]
+                    }
+                }
+            ]
+        }
+    }"#;
+
+    #[test]
+    fn test_find_rule_frame() {
+        let config = get_config(
+            r#"{
+                "rules": [
+                    {
+                        "condition": {"op": "glob", "name": "frame.module", "value": ["django.*"]},
+                        "fingerprint": ["django"]
+                    },
+                    {
+                        "condition": {
+                            "op": "and",
+                            "inner": [
+                                {"op": "glob", "name": "error.type", "value": ["*Error"]},
+                                {"op": "glob", "name": "frame.module", "value": ["psycopg2.*"]}
+                            ]
+                        },
+                        "fingerprint": ["database-error"]
+                    }
+                ]
+            }"#,
+        );
+
+        let rule = find_fingerprinting_rule(&config, &get_event(EVENT)).unwrap();
+        assert_eq!(rule.fingerprint, ["database-error"]);
+    }
+
+    #[test]
+    fn test_find_rule_no_match() {
+        let config = get_config(
+            r#"{
+                "rules": [
+                    {
+                        "condition": {
+                            "op": "and",
+                            "inner": [
+                                {"op": "eq", "name": "frame.function", "value": "connect"},
+                                {"op": "eq", "name": "frame.in_app", "value": true}
+                            ]
+                        },
+                        "fingerprint": ["in-app-connect"]
+                    }
+                ]
+            }"#,
+        );
+
+        // The condition must match a single frame.
+        assert_eq!(find_fingerprinting_rule(&config, &get_event(EVENT)), None);
+    }
+
+    #[test]
+    fn test_find_rule_message() {
+        let config = get_config(
+            r#"{
+                "rules": [
+                    {
+                        "condition": {"op": "glob", "name": "error.message", "value": ["Timeout *"]},
+                        "fingerprint": ["timeout"]
+                    }
+                ]
+            }"#,
+        );
+
+        let event = get_event(r#"{"logentry": {"formatted": "Timeout after 30s"}}"#);
+        let rule = find_fingerprinting_rule(&config, &event).unwrap();
+        assert_eq!(rule.fingerprint, ["timeout"]);
+    }
+
+    #[test]
+    fn test_grouping_hash_rule() {
+        let config = get_config(
+            r#"{
+                "rules": [
+                    {
+                        "condition": {"op": "eq", "name": "error.module", "value": "psycopg2"},
+                        "fingerprint": ["database-error"]
+                    }
+                ]
+            }"#,
+        );
+
+        let event = get_event(EVENT);
+        let mut other = event.clone();
+        other.fingerprint = Annotated::new(vec!["something-else".to_owned()].into());
+
+        // The fingerprint of the rule takes precedence over the one sent with the event.
+        let hash = compute_grouping_hash(&config, &event).unwrap();
+        assert_eq!(hash.len(), 32);
+        assert_eq!(compute_grouping_hash(&config, &other), Some(hash));
+    }
+
+    #[test]
+    fn test_grouping_hash_default() {
+        let config = FingerprintingConfig::default();
+
+        let event = get_event(EVENT);
+        let mut explicit = event.clone();
+        explicit.fingerprint = Annotated::new(vec!["{{ default }}".to_owned()].into());
+        assert_eq!(
+            compute_grouping_hash(&config, &event),
+            compute_grouping_hash(&config, &explicit)
+        );
+
+        // Only in-app frames contribute to the default grouping.
+        let mut system_frame_changed = get_event(&EVENT.replace("connect", "reconnect"));
+        assert_eq!(
+            compute_grouping_hash(&config, &event),
+            compute_grouping_hash(&config, &system_frame_changed)
+        );
+
+        system_frame_changed.fingerprint = Annotated::new(vec!["custom".to_owned()].into());
+        assert_ne!(
+            compute_grouping_hash(&config, &event),
+            compute_grouping_hash(&config, &system_frame_changed)
+        );
+    }
+
+    #[test]
+    fn test_grouping_hash_not_error() {
+        let config = FingerprintingConfig::default();
+
+        let event = get_event(r#"{"type": "transaction", "transaction": "/"}"#);
+        assert_eq!(compute_grouping_hash(&config, &event), None);
+
+        let event = get_event(r#"{"type": "error"}"#);
+        assert_eq!(compute_grouping_hash(&config, &event), None);
+    }
+
+    #[test]
+    fn test_normalize_grouping_hash() {
+        let mut event = get_event(EVENT);
+
+        normalize_grouping_hash(&mut event, Some(&FingerprintingConfig::default()));
+        assert!(event.grouping_hash.value().is_some());
+
+        normalize_grouping_hash(&mut event, None);
+        assert_eq!(event.grouping_hash.value(), None);
+    }
+}
@@ -1,5 +1,8 @@
 use std::fmt;
-use std::path::Path;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+use std::time::SystemTime;
 
 use relay_event_schema::protocol::Geo;
 use relay_protocol::Annotated;
@@ -10,11 +13,124 @@ type ReaderType = maxminddb::Mmap;
 #[cfg(not(feature = "mmap"))]
 type ReaderType = Vec<u8>;
 
+type Reader = maxminddb::Reader<ReaderType>;
+
 /// An error in the `GeoIpLookup`.
 pub type GeoIpError = maxminddb::MaxMindDBError;
 
+fn open_reader(path: &Path) -> Result<Reader, GeoIpError> {
+    #[cfg(feature = "mmap")]
+    let reader = maxminddb::Reader::open_mmap(path)?;
+    #[cfg(not(feature = "mmap"))]
+    let reader = maxminddb::Reader::open_readfile(path)?;
+    Ok(reader)
+}
+
+/// Identifies the version of a file on disk.
+///
+/// A file that is replaced on disk gets a new inode, even if it has the same size and modification
+/// time as the previous file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct FileVersion {
+    #[cfg(unix)]
+    device: u64,
+    #[cfg(unix)]
+    inode: u64,
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl FileVersion {
+    fn of(path: &Path) -> Option<Self> {
+        #[cfg(unix)]
+        use std::os::unix::fs::MetadataExt;
+
+        let metadata = std::fs::metadata(path).ok()?;
+
+        Some(Self {
+            #[cfg(unix)]
+            device: metadata.dev(),
+            #[cfg(unix)]
+            inode: metadata.ino(),
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+        })
+    }
+}
+
+/// A maxminddb file that can be reloaded when it is replaced on disk.
+struct Database {
+    path: PathBuf,
+    reader: RwLock<Arc<Reader>>,
+    version: Mutex<Option<FileVersion>>,
+}
+
+impl Database {
+    fn open(path: &Path) -> Result<Self, GeoIpError> {
+        let version = FileVersion::of(path);
+        let reader = open_reader(path)?;
+
+        Ok(Self {
+            path: path.to_owned(),
+            reader: RwLock::new(Arc::new(reader)),
+            version: Mutex::new(version),
+        })
+    }
+
+    /// Returns the currently loaded reader.
+    ///
+    /// The reader remains valid for lookups even if the database is reloaded concurrently.
+    fn reader(&self) -> Arc<Reader> {
+        Arc::clone(&self.reader.read().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Reopens the file if it was replaced or modified since it was last opened.
+    ///
+    /// If the new file cannot be opened, the previous reader remains in use and the reload is
+    /// attempted again on the next call.
+    fn reload(&self) -> Result<bool, GeoIpError> {
+        let mut version = self.version.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let current = FileVersion::of(&self.path);
+        if current == *version {
+            return Ok(false);
+        }
+
+        let reader = open_reader(&self.path)?;
+        *self.reader.write().unwrap_or_else(PoisonError::into_inner) = Arc::new(reader);
+        *version = current;
+
+        Ok(true)
+    }
+
+    /// Reloads the file like [`reload`](Self::reload) and logs errors.
+    fn reload_or_log(&self) -> bool {
+        match self.reload() {
+            Ok(reloaded) => reloaded,
+            Err(error) => {
+                relay_log::error!(
+                    error = &error as &dyn std::error::Error,
+                    path = %self.path.display(),
+                    "failed to reload GeoIP database"
+                );
+                false
+            }
+        }
+    }
+}
+
 /// A geo ip lookup helper based on maxmind db files.
-pub struct GeoIpLookup(maxminddb::Reader<ReaderType>);
+///
+/// The lookup is based on a City or Enterprise database, and can optionally be enriched with
+/// network information from an ASN or ISP database. Enterprise databases contain network
+/// information themselves, which is used where the network database has no information. Both
+/// files can be replaced on disk while Relay is running, see [`reload`](Self::reload). Files must
+/// be replaced atomically, for example by moving the new file into place, since they may be memory
+/// mapped.
+pub struct GeoIpLookup {
+    city: Database,
+    network: Option<Database>,
+}
 
 impl GeoIpLookup {
     /// Opens a maxminddb file by path.
@@ -22,54 +138,136 @@ impl GeoIpLookup {
     where
         P: AsRef<Path>,
     {
-        #[cfg(feature = "mmap")]
-        let reader = maxminddb::Reader::open_mmap(path)?;
-        #[cfg(not(feature = "mmap"))]
-        let reader = maxminddb::Reader::open_readfile(path)?;
-        Ok(GeoIpLookup(reader))
+        Ok(GeoIpLookup {
+            city: Database::open(path.as_ref())?,
+            network: None,
+        })
+    }
+
+    /// Opens a maxminddb file with network information by path.
+    ///
+    /// This can be either a GeoLite2 or GeoIP2 ASN database, or a GeoIP2 ISP database. It is used
+    /// to populate the autonomous system number and the organization of the network. If the file
+    /// cannot be opened, the lookup remains unchanged.
+    pub fn open_network<P>(&mut self, path: P) -> Result<(), GeoIpError>
+    where
+        P: AsRef<Path>,
+    {
+        self.network = Some(Database::open(path.as_ref())?);
+        Ok(())
+    }
+
+    /// Reloads all database files that have been replaced or modified on disk since they were
+    /// opened.
+    ///
+    /// Every database is reloaded independently. If a database cannot be reloaded, the error is
+    /// logged and the previous version of that database remains in use. Returns `true` if at least
+    /// one of the databases was reloaded.
+    pub fn reload(&self) -> bool {
+        let mut reloaded = self.city.reload_or_log();
+        if let Some(ref network) = self.network {
+            reloaded |= network.reload_or_log();
+        }
+        reloaded
     }
 
     /// Looks up an IP address.
     pub fn lookup(&self, ip_address: &str) -> Result<Option<Geo>, GeoIpError> {
         // XXX: Why do we parse the IP again after deserializing?
-        let ip_address = match ip_address.parse() {
-            Ok(x) => x,
-            Err(_) => return Ok(None),
-        };
+        match ip_address.parse() {
+            Ok(ip_address) => self.lookup_ip(ip_address),
+            Err(_) => Ok(None),
+        }
+    }
 
-        let city: maxminddb::geoip2::City = match self.0.lookup(ip_address) {
-            Ok(x) => x,
-            Err(GeoIpError::AddressNotFoundError(_)) => return Ok(None),
+    /// Looks up a parsed IP address.
+    ///
+    /// Errors from the network database are logged and do not fail the lookup, so that the
+    /// location from the city database is still returned.
+    pub fn lookup_ip(&self, ip_address: IpAddr) -> Result<Option<Geo>, GeoIpError> {
+        let reader = self.city.reader();
+        let city: Option<maxminddb::geoip2::Enterprise> = match reader.lookup(ip_address) {
+            Ok(x) => Some(x),
+            Err(GeoIpError::AddressNotFoundError(_)) => None,
             Err(e) => return Err(e),
         };
 
-        Ok(Some(Geo {
-            country_code: Annotated::from(
+        let network_reader = self.network.as_ref().map(Database::reader);
+        let network: Option<maxminddb::geoip2::Isp> = match network_reader {
+            Some(ref reader) => match reader.lookup(ip_address) {
+                Ok(x) => Some(x),
+                Err(GeoIpError::AddressNotFoundError(_)) => None,
+                Err(error) => {
+                    relay_log::warn!(
+                        error = &error as &dyn std::error::Error,
+                        "failed to look up IP address in network database"
+                    );
+                    None
+                }
+            },
+            None => None,
+        };
+
+        if city.is_none() && network.is_none() {
+            return Ok(None);
+        }
+
+        let mut geo = Geo::default();
+
+        if let Some(city) = city {
+            geo.country_code = Annotated::from(
                 city.country
                     .as_ref()
                     .and_then(|country| Some(country.iso_code.as_ref()?.to_string())),
-            ),
-            city: Annotated::from(
+            );
+            geo.city = Annotated::from(
                 city.city
                     .as_ref()
                     .and_then(|city| Some(city.names.as_ref()?.get("en")?.to_string())),
-            ),
-            subdivision: Annotated::from(city.subdivisions.as_ref().and_then(|subdivisions| {
-                subdivisions.first().and_then(|subdivision| {
-                    subdivision.names.as_ref().and_then(|subdivision_names| {
-                        subdivision_names
-                            .get("en")
-                            .map(|subdivision_name| subdivision_name.to_string())
+            );
+            geo.subdivision =
+                Annotated::from(city.subdivisions.as_ref().and_then(|subdivisions| {
+                    subdivisions.first().and_then(|subdivision| {
+                        subdivision.names.as_ref().and_then(|subdivision_names| {
+                            subdivision_names
+                                .get("en")
+                                .map(|subdivision_name| subdivision_name.to_string())
+                        })
                     })
-                })
-            })),
-            region: Annotated::from(
+                }));
+            geo.region = Annotated::from(
                 city.country
                     .as_ref()
                     .and_then(|country| Some(country.names.as_ref()?.get("en")?.to_string())),
-            ),
-            ..Default::default()
-        }))
+            );
+
+            if let Some(traits) = city.traits {
+                geo.asn = Annotated::from(traits.autonomous_system_number.map(u64::from));
+                geo.organization = Annotated::from(
+                    traits
+                        .autonomous_system_organization
+                        .or(traits.organization)
+                        .or(traits.isp)
+                        .map(str::to_owned),
+                );
+            }
+        }
+
+        if let Some(network) = network {
+            if let Some(asn) = network.autonomous_system_number {
+                geo.asn = Annotated::new(asn.into());
+            }
+
+            let organization = network
+                .autonomous_system_organization
+                .or(network.organization)
+                .or(network.isp);
+            if let Some(organization) = organization {
+                geo.organization = Annotated::new(organization.to_owned());
+            }
+        }
+
+        Ok(Some(geo))
     }
 }
 
@@ -78,3 +276,153 @@ impl fmt::Debug for GeoIpLookup {
         f.debug_struct("GeoIpLookup").finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{self, File};
+    use std::time::Duration;
+
+    use super::*;
+
+    const FIXTURE: &str = "tests/fixtures/GeoIP2-Enterprise-Test.mmdb";
+    const NETWORK_FIXTURE: &str = "tests/fixtures/GeoIP2-ISP-Test.mmdb";
+
+    /// Atomically replaces the file at `path` with a file that has a newer modification time.
+    fn replace(path: &Path, contents: &[u8]) {
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, contents).unwrap();
+        File::options()
+            .write(true)
+            .open(&tmp)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        fs::rename(&tmp, path).unwrap();
+    }
+
+    #[test]
+    fn test_lookup_enterprise_network() {
+        let lookup = GeoIpLookup::open(FIXTURE).unwrap();
+
+        let geo = lookup.lookup("89.160.20.112").unwrap().unwrap();
+        assert_eq!(geo.country_code.as_str(), Some("SE"));
+        assert_eq!(geo.asn.value(), Some(&29518));
+        assert_eq!(geo.organization.as_str(), Some("Bredband2 AB"));
+
+        let geo = lookup.lookup("2.125.160.216").unwrap().unwrap();
+        assert_eq!(geo.asn.value(), None);
+        assert_eq!(geo.organization.value(), None);
+    }
+
+    #[test]
+    fn test_lookup_network() {
+        let mut lookup = GeoIpLookup::open(FIXTURE).unwrap();
+
+        let geo = lookup.lookup("2.125.160.216").unwrap().unwrap();
+        assert_eq!(geo.asn.value(), None);
+
+        lookup.open_network(NETWORK_FIXTURE).unwrap();
+
+        let geo = lookup.lookup("2.125.160.216").unwrap().unwrap();
+        assert_eq!(geo.country_code.as_str(), Some("GB"));
+        assert_eq!(geo.asn.value(), Some(&64496));
+        assert_eq!(geo.organization.as_str(), Some("Example Network"));
+    }
+
+    #[test]
+    fn test_lookup_network_without_asn() {
+        let mut lookup = GeoIpLookup::open(FIXTURE).unwrap();
+        // Records of the enterprise database have no top-level network fields.
+        lookup.open_network(FIXTURE).unwrap();
+
+        let geo = lookup.lookup("89.160.20.112").unwrap().unwrap();
+        assert_eq!(geo.asn.value(), Some(&29518));
+        assert_eq!(geo.organization.as_str(), Some("Bredband2 AB"));
+    }
+
+    #[test]
+    fn test_open_network_invalid_keeps_lookup() {
+        let mut lookup = GeoIpLookup::open(FIXTURE).unwrap();
+        assert!(lookup.open_network("tests/fixtures/missing.mmdb").is_err());
+
+        let geo = lookup.lookup("2.125.160.216").unwrap().unwrap();
+        assert_eq!(geo.country_code.as_str(), Some("GB"));
+    }
+
+    #[test]
+    fn test_reload_modified() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("GeoIP2-City.mmdb");
+        fs::copy(FIXTURE, &path).unwrap();
+
+        let lookup = GeoIpLookup::open(&path).unwrap();
+        assert!(!lookup.reload());
+
+        replace(&path, &fs::read(FIXTURE).unwrap());
+        assert!(lookup.reload());
+        assert!(!lookup.reload());
+
+        let geo = lookup.lookup("2.125.160.216").unwrap().unwrap();
+        assert_eq!(geo.country_code.as_str(), Some("GB"));
+    }
+
+    #[test]
+    fn test_reload_replaced_same_mtime() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("GeoIP2-City.mmdb");
+        fs::copy(FIXTURE, &path).unwrap();
+        let modified = fs::metadata(&path).unwrap().modified().unwrap();
+
+        let lookup = GeoIpLookup::open(&path).unwrap();
+
+        // Replace the file with a different database that keeps the modification time.
+        let tmp = path.with_extension("tmp");
+        fs::copy(NETWORK_FIXTURE, &tmp).unwrap();
+        File::options()
+            .write(true)
+            .open(&tmp)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        fs::rename(&tmp, &path).unwrap();
+
+        assert!(lookup.reload());
+        assert!(!lookup.reload());
+    }
+
+    #[test]
+    fn test_reload_network_independently() {
+        let dir = tempfile::tempdir().unwrap();
+        let city_path = dir.path().join("GeoIP2-City.mmdb");
+        let network_path = dir.path().join("GeoIP2-ISP.mmdb");
+        fs::copy(FIXTURE, &city_path).unwrap();
+        fs::copy(NETWORK_FIXTURE, &network_path).unwrap();
+
+        let mut lookup = GeoIpLookup::open(&city_path).unwrap();
+        lookup.open_network(&network_path).unwrap();
+
+        // A broken city database does not prevent reloading the network database.
+        replace(&city_path, b"not a database");
+        replace(&network_path, &fs::read(NETWORK_FIXTURE).unwrap());
+        assert!(lookup.reload());
+
+        let geo = lookup.lookup("2.125.160.216").unwrap().unwrap();
+        assert_eq!(geo.country_code.as_str(), Some("GB"));
+        assert_eq!(geo.asn.value(), Some(&64496));
+    }
+
+    #[test]
+    fn test_reload_invalid_keeps_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("GeoIP2-City.mmdb");
+        fs::copy(FIXTURE, &path).unwrap();
+
+        let lookup = GeoIpLookup::open(&path).unwrap();
+
+        replace(&path, b"not a database");
+        assert!(!lookup.reload());
+
+        let geo = lookup.lookup("2.125.160.216").unwrap().unwrap();
+        assert_eq!(geo.country_code.as_str(), Some("GB"));
+    }
+}
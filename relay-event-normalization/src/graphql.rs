@@ -0,0 +1,555 @@
+//! Parsing of GraphQL documents for span and transaction name normalization.
+//!
+//! GraphQL APIs are typically served from a single endpoint, such as `POST /graphql`, so the URL
+//! does not identify the executed operation. Instead, the operation is parsed from the GraphQL
+//! document into its type, its name, and the names of the top-level fields it selects. Arguments,
+//! variables, aliases, and nested selections are dropped, so that the result has low cardinality.
+//!
+//! This is not a validating parser. It only understands as much of the
+//! [GraphQL grammar](https://spec.graphql.org/October2021/#sec-Document) as is needed to extract
+//! operations and skips over everything else.
+use std::fmt;
+
+use relay_protocol::Value;
+
+/// The maximum nesting depth of selection sets, inline fragments, and arguments.
+///
+/// Documents that nest deeper are not parsed. This bounds the recursion of the parser.
+const MAX_DEPTH: usize = 64;
+
+/// The type of a GraphQL operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OperationType {
+    /// A read-only fetch.
+    Query,
+    /// A write followed by a fetch.
+    Mutation,
+    /// A long-lived request that fetches data in response to events.
+    Subscription,
+}
+
+impl OperationType {
+    fn parse(keyword: &str) -> Option<Self> {
+        Some(match keyword {
+            "query" => Self::Query,
+            "mutation" => Self::Mutation,
+            "subscription" => Self::Subscription,
+            _ => return None,
+        })
+    }
+
+    /// Returns the keyword of the operation type.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Query => "query",
+            Self::Mutation => "mutation",
+            Self::Subscription => "subscription",
+        }
+    }
+}
+
+/// A GraphQL operation parsed from a document.
+///
+/// The display format is `<type> <name> { <fields> }`, for example `query GetUser { user }`. The
+/// name and the fields are omitted if they are not known.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Operation {
+    /// The type of the operation.
+    pub ty: OperationType,
+    /// The name of the operation, if it is not anonymous.
+    pub name: Option<String>,
+    /// Sorted, unique names of the top-level fields selected by the operation.
+    pub fields: Vec<String>,
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.ty.as_str())?;
+        if let Some(ref name) = self.name {
+            write!(f, " {name}")?;
+        }
+        if !self.fields.is_empty() {
+            write!(f, " {{ {} }}", self.fields.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses the operation executed by a GraphQL document.
+///
+/// If the document contains multiple operations, `operation_name` selects the one that is
+/// executed. Otherwise, the first operation is returned. Returns `None` if the document cannot be
+/// parsed or contains no matching operation.
+pub fn parse_operation(document: &str, operation_name: Option<&str>) -> Option<Operation> {
+    let tokens = tokenize(document)?;
+    let operations = Parser { tokens, pos: 0 }.parse_document()?;
+
+    match operation_name {
+        Some(name) => operations
+            .into_iter()
+            .find(|op| op.name.as_deref() == Some(name)),
+        None => operations.into_iter().next(),
+    }
+}
+
+/// Parses the operation from the body of a GraphQL HTTP request.
+///
+/// The body is a JSON object with the document in `query` and an optional `operationName`, either
+/// as object or as serialized JSON string.
+pub fn parse_request_body(body: &Value) -> Option<Operation> {
+    let body = match body {
+        Value::String(string) => return parse_json_body(&serde_json::from_str(string).ok()?),
+        Value::Object(body) => body,
+        _ => return None,
+    };
+
+    let document = body.get("query")?.value()?.as_str()?;
+    let operation_name = body
+        .get("operationName")
+        .and_then(|name| name.value())
+        .and_then(Value::as_str);
+
+    parse_operation(document, operation_name)
+}
+
+fn parse_json_body(body: &serde_json::Value) -> Option<Operation> {
+    let document = body.get("query")?.as_str()?;
+    let operation_name = body.get("operationName").and_then(|name| name.as_str());
+    parse_operation(document, operation_name)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Token<'a> {
+    /// A name, including keywords.
+    Name(&'a str),
+    /// A punctuator, except for the spread operator.
+    Punct(char),
+    /// The spread operator `...`.
+    Spread,
+    /// A string or number literal.
+    Literal,
+}
+
+/// Splits a document into tokens, skipping whitespace, commas, and comments.
+fn tokenize(document: &str) -> Option<Vec<Token<'_>>> {
+    let bytes = document.as_bytes();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while pos < bytes.len() {
+        let c = bytes[pos];
+        match c {
+            b' ' | b'\t' | b'\n' | b'\r' | b',' => pos += 1,
+            b'#' => {
+                while pos < bytes.len() && bytes[pos] != b'\n' {
+                    pos += 1;
+                }
+            }
+            b'.' => {
+                if !document[pos..].starts_with("...") {
+                    return None;
+                }
+                tokens.push(Token::Spread);
+                pos += 3;
+            }
+            b'!' | b'$' | b'&' | b'(' | b')' | b':' | b'=' | b'@' | b'[' | b']' | b'{' | b'|'
+            | b'}' => {
+                tokens.push(Token::Punct(c as char));
+                pos += 1;
+            }
+            b'_' | b'a'..=b'z' | b'A'..=b'Z' => {
+                let start = pos;
+                while pos < bytes.len()
+                    && (bytes[pos] == b'_' || bytes[pos].is_ascii_alphanumeric())
+                {
+                    pos += 1;
+                }
+                tokens.push(Token::Name(&document[start..pos]));
+            }
+            b'-' | b'0'..=b'9' => {
+                pos += 1;
+                while pos < bytes.len()
+                    && (bytes[pos].is_ascii_alphanumeric()
+                        || matches!(bytes[pos], b'.' | b'+' | b'-'))
+                {
+                    pos += 1;
+                }
+                tokens.push(Token::Literal);
+            }
+            b'"' if document[pos..].starts_with("\"\"\"") => {
+                pos += 3;
+                loop {
+                    let rest = document.get(pos..)?;
+                    if rest.starts_with("\\\"\"\"") {
+                        pos += 4;
+                    } else if rest.starts_with("\"\"\"") {
+                        pos += 3;
+                        break;
+                    } else {
+                        pos += rest.chars().next()?.len_utf8();
+                    }
+                }
+                tokens.push(Token::Literal);
+            }
+            b'"' => {
+                pos += 1;
+                loop {
+                    match *bytes.get(pos)? {
+                        b'\\' => pos += 2,
+                        b'"' => break,
+                        b'\n' => return None,
+                        _ => pos += 1,
+                    }
+                }
+                pos += 1;
+                tokens.push(Token::Literal);
+            }
+            // Unicode byte order mark.
+            0xEF if document[pos..].starts_with('\u{feff}') => pos += 3,
+            _ => return None,
+        }
+    }
+
+    Some(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<Token<'a>> {
+        let token = self.peek()?;
+        self.pos += 1;
+        Some(token)
+    }
+
+    fn eat(&mut self, token: Token<'_>) -> bool {
+        let matches = self.peek() == Some(token);
+        if matches {
+            self.pos += 1;
+        }
+        matches
+    }
+
+    fn parse_document(mut self) -> Option<Vec<Operation>> {
+        let mut operations = Vec::new();
+
+        while let Some(token) = self.bump() {
+            match token {
+                Token::Punct('{') => {
+                    self.pos -= 1;
+                    let fields = self.parse_selection_set()?;
+                    operations.push(Operation {
+                        ty: OperationType::Query,
+                        name: None,
+                        fields,
+                    });
+                }
+                Token::Name("fragment") => {
+                    while !self.eat(Token::Punct('{')) {
+                        self.bump()?;
+                    }
+                    self.pos -= 1;
+                    self.skip_nested(Token::Punct('{'), Token::Punct('}'), 0)?;
+                }
+                Token::Name(keyword) => {
+                    let ty = OperationType::parse(keyword)?;
+                    let name = match self.peek() {
+                        Some(Token::Name(name)) => {
+                            self.pos += 1;
+                            Some(name.to_owned())
+                        }
+                        _ => None,
+                    };
+
+                    if self.peek() == Some(Token::Punct('(')) {
+                        self.skip_nested(Token::Punct('('), Token::Punct(')'), 0)?;
+                    }
+                    self.skip_directives(0)?;
+
+                    // Descriptions of SDKs sometimes only contain the operation type and name.
+                    let fields = match self.peek() {
+                        None => Vec::new(),
+                        Some(_) => self.parse_selection_set()?,
+                    };
+
+                    operations.push(Operation { ty, name, fields });
+                }
+                _ => return None,
+            }
+        }
+
+        Some(operations)
+    }
+
+    /// Parses a selection set and returns the sorted names of its fields.
+    fn parse_selection_set(&mut self) -> Option<Vec<String>> {
+        let mut fields = Vec::new();
+        self.collect_fields(&mut fields, 0)?;
+        fields.sort_unstable();
+        fields.dedup();
+        Some(fields)
+    }
+
+    /// Collects the field names of a selection set, including fields of inline fragments.
+    ///
+    /// `depth` is the nesting depth of the selection set. Returns `None` if the document nests
+    /// deeper than [`MAX_DEPTH`].
+    fn collect_fields(&mut self, fields: &mut Vec<String>, depth: usize) -> Option<()> {
+        if depth >= MAX_DEPTH || !self.eat(Token::Punct('{')) {
+            return None;
+        }
+
+        loop {
+            match self.bump()? {
+                Token::Punct('}') => return Some(()),
+                Token::Spread => match self.peek()? {
+                    // Fragment spread, the fields are defined elsewhere.
+                    Token::Name(name) if name != "on" => {
+                        self.pos += 1;
+                        self.skip_directives(depth)?;
+                    }
+                    // Inline fragment with an optional type condition.
+                    _ => {
+                        if self.eat(Token::Name("on")) {
+                            let Token::Name(_) = self.bump()? else {
+                                return None;
+                            };
+                        }
+                        self.skip_directives(depth)?;
+                        self.collect_fields(fields, depth + 1)?;
+                    }
+                },
+                Token::Name(name) => {
+                    // With an alias, the field name follows the colon.
+                    let name = if self.eat(Token::Punct(':')) {
+                        let Token::Name(name) = self.bump()? else {
+                            return None;
+                        };
+                        name
+                    } else {
+                        name
+                    };
+                    fields.push(name.to_owned());
+
+                    if self.peek() == Some(Token::Punct('(')) {
+                        self.skip_nested(Token::Punct('('), Token::Punct(')'), depth)?;
+                    }
+                    self.skip_directives(depth)?;
+                    if self.peek() == Some(Token::Punct('{')) {
+                        self.skip_nested(Token::Punct('{'), Token::Punct('}'), depth)?;
+                    }
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    /// Skips directives such as `@include(if: $flag)`.
+    fn skip_directives(&mut self, depth: usize) -> Option<()> {
+        while self.eat(Token::Punct('@')) {
+            let Token::Name(_) = self.bump()? else {
+                return None;
+            };
+            if self.peek() == Some(Token::Punct('(')) {
+                self.skip_nested(Token::Punct('('), Token::Punct(')'), depth)?;
+            }
+        }
+        Some(())
+    }
+
+    /// Skips a balanced group of tokens starting at the current `open` token.
+    ///
+    /// `depth` is the nesting depth at which the group starts. Returns `None` if the group nests
+    /// deeper than [`MAX_DEPTH`].
+    fn skip_nested(&mut self, open: Token<'_>, close: Token<'_>, mut depth: usize) -> Option<()> {
+        let start = depth;
+        loop {
+            let token = self.bump()?;
+            if token == open {
+                depth += 1;
+                if depth > MAX_DEPTH {
+                    return None;
+                }
+            } else if token == close {
+                depth -= 1;
+                if depth == start {
+                    return Some(());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use relay_protocol::Annotated;
+
+    use super::*;
+
+    fn display(document: &str) -> Option<String> {
+        parse_operation(document, None).map(|op| op.to_string())
+    }
+
+    #[test]
+    fn test_named_query() {
+        let document = r#"
+            query GetUser($id: ID!, $withPosts: Boolean = false) {
+                user(id: $id) {
+                    name
+                    posts @include(if: $withPosts) { title }
+                }
+                viewer { id }
+            }
+        "#;
+
+        assert_eq!(
+            parse_operation(document, None),
+            Some(Operation {
+                ty: OperationType::Query,
+                name: Some("GetUser".to_owned()),
+                fields: vec!["user".to_owned(), "viewer".to_owned()],
+            })
+        );
+        assert_eq!(
+            display(document).as_deref(),
+            Some("query GetUser { user, viewer }")
+        );
+    }
+
+    #[test]
+    fn test_anonymous_query() {
+        assert_eq!(
+            display(r#"{ search(term: "shoes", first: 10) { id } }"#).as_deref(),
+            Some("query { search }")
+        );
+    }
+
+    #[test]
+    fn test_mutation_with_aliases() {
+        let document = r#"
+            mutation {
+                first: addItem(input: {name: "a", tags: ["x", "y"]}) { id }
+                second: addItem(input: {name: "b"}) { id }
+            }
+        "#;
+
+        assert_eq!(display(document).as_deref(), Some("mutation { addItem }"));
+    }
+
+    #[test]
+    fn test_fragments() {
+        let document = r#"
+            fragment UserFields on User { id name }
+
+            query Feed {
+                ...RootFields
+                ... on Query { feed { ...UserFields } }
+                ... @skip(if: true) { ads }
+            }
+        "#;
+
+        assert_eq!(
+            display(document).as_deref(),
+            Some("query Feed { ads, feed }")
+        );
+    }
+
+    #[test]
+    fn test_select_operation_by_name() {
+        let document = "query A { a } mutation B { b }";
+
+        assert_eq!(display(document).as_deref(), Some("query A { a }"));
+        assert_eq!(
+            parse_operation(document, Some("B")).map(|op| op.to_string()),
+            Some("mutation B { b }".to_owned())
+        );
+        assert_eq!(parse_operation(document, Some("C")), None);
+    }
+
+    #[test]
+    fn test_operation_without_selection() {
+        assert_eq!(
+            display("subscription OnMessage").as_deref(),
+            Some("subscription OnMessage")
+        );
+    }
+
+    #[test]
+    fn test_strings_and_comments() {
+        let document = r#"
+            # A comment with { braces
+            query Search {
+                search(text: "a \" { quote", description: """ block " } string """) { id }
+            }
+        "#;
+
+        assert_eq!(
+            display(document).as_deref(),
+            Some("query Search { search }")
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(display("shoes"), None);
+        assert_eq!(display("query { unclosed"), None);
+        assert_eq!(display("type User { id: ID }"), None);
+        assert_eq!(display(""), None);
+    }
+
+    #[test]
+    fn test_nesting_depth() {
+        let nested = |depth: usize| {
+            format!(
+                "query {{ user {} id {} }}",
+                "{ friends ".repeat(depth),
+                "}".repeat(depth)
+            )
+        };
+
+        assert_eq!(display(&nested(10)).as_deref(), Some("query { user }"));
+        assert_eq!(display(&nested(1000)), None);
+    }
+
+    #[test]
+    fn test_inline_fragment_depth() {
+        let nested = |depth: usize| {
+            format!(
+                "query {{ {} id {} }}",
+                "... on User { ".repeat(depth),
+                "}".repeat(depth)
+            )
+        };
+
+        assert_eq!(display(&nested(10)).as_deref(), Some("query { id }"));
+        assert_eq!(display(&nested(1000)), None);
+    }
+
+    #[test]
+    fn test_parse_request_body() {
+        let body = Annotated::<Value>::from_json(
+            r#"{"query": "query A { a } query B { b }", "operationName": "B", "variables": {}}"#,
+        )
+        .unwrap()
+        .into_value()
+        .unwrap();
+
+        assert_eq!(
+            parse_request_body(&body).map(|op| op.to_string()),
+            Some("query B { b }".to_owned())
+        );
+
+        let body = Value::String(r#"{"query": "mutation { like }"}"#.to_owned());
+        assert_eq!(
+            parse_request_body(&body).map(|op| op.to_string()),
+            Some("mutation { like }".to_owned())
+        );
+    }
+}
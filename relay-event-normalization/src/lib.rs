@@ -7,9 +7,12 @@
 )]
 
 mod clock_drift;
+mod debug_images;
 mod event;
 mod event_error;
+mod fingerprinting;
 mod geo;
+mod graphql;
 mod legacy;
 mod logentry;
 mod mechanism;
@@ -18,6 +21,7 @@ mod regexes;
 mod remove_other;
 mod schema;
 mod stacktrace;
+mod stacktrace_rules;
 mod statsd;
 mod timestamp;
 mod transactions;
@@ -29,13 +33,16 @@ pub use validation::{
     TransactionValidationConfig,
 };
 pub mod replay;
+pub use debug_images::*;
 pub use event::{
     normalize_event, normalize_measurements, normalize_performance_score, NormalizationConfig,
 };
+pub use fingerprinting::*;
 pub use normalize::breakdowns::*;
 pub use normalize::*;
 pub use remove_other::RemoveOtherProcessor;
 pub use schema::SchemaProcessor;
+pub use stacktrace_rules::*;
 pub use timestamp::TimestampProcessor;
 pub use transactions::*;
 pub use trimming::TrimmingProcessor;
@@ -1,11 +1,14 @@
 //! Computation and normalization of contexts from event data.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use once_cell::sync::Lazy;
 use regex::Regex;
-use relay_event_schema::protocol::{Context, Cookies, OsContext, ResponseContext, RuntimeContext};
-use relay_protocol::{Annotated, Empty, Value};
+use relay_event_schema::protocol::{
+    ContainerContext, Context, Contexts, Cookies, DefaultContext, FlagsContext, KubernetesContext,
+    OsContext, OtelContext, ResponseContext, RuntimeContext,
+};
+use relay_protocol::{Annotated, Empty, FromValue, Object, Value};
 
 /// Environment.OSVersion (GetVersionEx) or RuntimeInformation.OSDescription on Windows
 static OS_WINDOWS_REGEX1: Lazy<Regex> = Lazy::new(|| {
@@ -274,6 +277,98 @@ fn normalize_response(response: &mut ResponseContext) {
     }
 }
 
+/// The maximum number of feature flag evaluations retained in the flags context.
+const MAX_FLAGS: usize = 100;
+
+/// Removes repeated evaluations of the same flag and caps the number of flags.
+///
+/// Only the most recent evaluation of each flag is kept, at the position of that evaluation. If
+/// there are more than [`MAX_FLAGS`] distinct flags, the oldest evaluations are dropped.
+fn normalize_flags_context(flags: &mut FlagsContext) {
+    let Some(values) = flags.values.value_mut() else {
+        return;
+    };
+
+    let original_length = values.len();
+
+    let mut seen = HashSet::new();
+    let mut deduped: Vec<_> = std::mem::take(values)
+        .into_iter()
+        .rev()
+        .filter(
+            |item| match item.value().and_then(|item| item.flag.as_str()) {
+                Some(flag) => seen.insert(flag.to_owned()),
+                None => false,
+            },
+        )
+        .take(MAX_FLAGS)
+        .collect();
+    deduped.reverse();
+    *values = deduped;
+
+    if values.len() != original_length {
+        flags
+            .values
+            .meta_mut()
+            .set_original_length(Some(original_length));
+    }
+}
+
+/// Builds a context from the OpenTelemetry resource attributes starting with `prefix`.
+///
+/// The attributes are mapped to the fields of the context through their aliases. Attributes
+/// without a matching field are retained in the additional properties of the context.
+fn resource_context<C>(resource: &Object<Value>, prefix: &str) -> Option<C>
+where
+    C: DefaultContext + FromValue + Empty,
+{
+    let attributes: Object<Value> = resource
+        .iter()
+        .filter(|(key, _)| key.starts_with(prefix))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+
+    if attributes.is_empty() {
+        return None;
+    }
+
+    C::from_value(Annotated::new(Value::Object(attributes)))
+        .into_value()
+        .filter(|context| !context.is_empty())
+}
+
+/// Adds the Kubernetes and container contexts from OpenTelemetry resource attributes.
+///
+/// SDKs bridging OpenTelemetry send the resource in the `otel` context. Attributes following the
+/// `k8s.*` and `container.*` semantic conventions are mapped to the respective contexts, unless a
+/// context already exists under their key.
+pub fn normalize_resource_contexts(contexts: &mut Contexts) {
+    let Some(resource) = contexts
+        .get::<OtelContext>()
+        .and_then(OtelContext::resource)
+    else {
+        return;
+    };
+
+    let kubernetes = if contexts.contains_key(KubernetesContext::default_key()) {
+        None
+    } else {
+        resource_context::<KubernetesContext>(resource, "k8s.")
+    };
+    let container = if contexts.contains_key(ContainerContext::default_key()) {
+        None
+    } else {
+        resource_context::<ContainerContext>(resource, "container.")
+    };
+
+    if let Some(kubernetes) = kubernetes {
+        contexts.add(kubernetes);
+    }
+    if let Some(container) = container {
+        contexts.add(container);
+    }
+}
+
 /// Normalizes the given context.
 pub fn normalize_context(context: &mut Context) {
     match context {
@@ -290,13 +385,14 @@ pub fn normalize_context(context: &mut Context) {
                 device.name.set_value(Some(product_name.to_string()))
             }
         }
+        Context::Flags(flags) => normalize_flags_context(flags),
         _ => {}
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use relay_event_schema::protocol::{Headers, LenientString, PairList};
+    use relay_event_schema::protocol::{FlagsContextItem, Headers, LenientString, PairList};
     use relay_protocol::Object;
     use similar_asserts::assert_eq;
 
@@ -732,4 +828,110 @@ mod tests {
         assert_eq!(response.inferred_content_type.value(), None);
         assert_eq!(response.data.as_str(), Some(r#"{"foo":"b"#));
     }
+
+    #[test]
+    fn test_flags_dedupe() {
+        let mut flags = Annotated::<FlagsContext>::from_json(
+            r#"{"values": [{"flag": "a", "result": false}, {"flag": "b", "result": true}, {"flag": "a", "result": true}]}"#,
+        )
+        .unwrap()
+        .into_value()
+        .unwrap();
+
+        normalize_flags_context(&mut flags);
+
+        let values = flags.values.value().unwrap();
+        let evaluations: Vec<_> = values
+            .iter()
+            .map(|item| {
+                let item = item.value().unwrap();
+                (item.flag.as_str().unwrap(), item.result.value().unwrap())
+            })
+            .collect();
+        assert_eq!(
+            evaluations,
+            vec![("b", &Value::Bool(true)), ("a", &Value::Bool(true))]
+        );
+        assert_eq!(flags.values.meta().original_length(), Some(3));
+    }
+
+    #[test]
+    fn test_flags_capped() {
+        let values = (0..MAX_FLAGS + 10)
+            .map(|i| {
+                Annotated::new(FlagsContextItem {
+                    flag: Annotated::new(format!("flag-{i}")),
+                    result: Annotated::new(Value::Bool(true)),
+                    ..Default::default()
+                })
+            })
+            .collect();
+        let mut flags = FlagsContext {
+            values: Annotated::new(values),
+            ..Default::default()
+        };
+
+        normalize_flags_context(&mut flags);
+
+        let values = flags.values.value().unwrap();
+        assert_eq!(values.len(), MAX_FLAGS);
+        // The oldest evaluations are dropped.
+        assert_eq!(values[0].value().unwrap().flag.as_str(), Some("flag-10"));
+        assert_eq!(flags.values.meta().original_length(), Some(MAX_FLAGS + 10));
+    }
+
+    #[test]
+    fn test_resource_contexts() {
+        let mut contexts = Annotated::<Contexts>::from_json(
+            r#"{
+                "otel": {
+                    "resource": {
+                        "service.name": "checkout",
+                        "k8s.namespace.name": "shop",
+                        "k8s.pod.name": "checkout-7d9f8b6c5-x2k4z",
+                        "k8s.replicaset.name": "checkout-7d9f8b6c5",
+                        "container.image.name": "gcr.io/example/checkout"
+                    }
+                }
+            }"#,
+        )
+        .unwrap()
+        .into_value()
+        .unwrap();
+
+        normalize_resource_contexts(&mut contexts);
+
+        let kubernetes = contexts.get::<KubernetesContext>().unwrap();
+        assert_eq!(kubernetes.namespace.as_str(), Some("shop"));
+        assert_eq!(
+            kubernetes.pod_name.as_str(),
+            Some("checkout-7d9f8b6c5-x2k4z")
+        );
+        assert!(kubernetes.other.contains_key("k8s.replicaset.name"));
+
+        let container = contexts.get::<ContainerContext>().unwrap();
+        assert_eq!(
+            container.image_name.as_str(),
+            Some("gcr.io/example/checkout")
+        );
+    }
+
+    #[test]
+    fn test_resource_contexts_keep_existing() {
+        let mut contexts = Annotated::<Contexts>::from_json(
+            r#"{
+                "kubernetes": {"namespace": "sdk"},
+                "otel": {"resource": {"k8s.namespace.name": "otel"}}
+            }"#,
+        )
+        .unwrap()
+        .into_value()
+        .unwrap();
+
+        normalize_resource_contexts(&mut contexts);
+
+        let kubernetes = contexts.get::<KubernetesContext>().unwrap();
+        assert_eq!(kubernetes.namespace.as_str(), Some("sdk"));
+        assert!(!contexts.contains::<ContainerContext>());
+    }
 }
@@ -0,0 +1,209 @@
+//! Logic for scrubbing and normalizing span descriptions of Elasticsearch and OpenSearch requests.
+//!
+//! Descriptions contain either the request line followed by the query DSL body, or only the body:
+//!
+//! ```text
+//! GET /products/_search {"query": {"match": {"title": "red shoes"}}}
+//! ```
+//!
+//! Index names and API endpoints are kept, while document IDs and all values in the body are
+//! replaced with placeholders.
+use std::collections::BTreeSet;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value;
+
+use super::json::scrub_value;
+use crate::span::tag_extraction::HTTP_METHOD_EXTRACTOR_REGEX;
+
+/// Matches sequences of digits in index names, such as dates in time-based indices.
+static INDEX_DIGITS_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+").unwrap());
+
+/// Endpoints that are followed by a document ID in the request path.
+const DOCUMENT_ENDPOINTS: &[&str] = &[
+    "_create",
+    "_doc",
+    "_explain",
+    "_source",
+    "_termvectors",
+    "_update",
+];
+
+/// Scrubs a request to Elasticsearch or OpenSearch.
+///
+/// Returns `None` if the description is neither a request line nor a JSON body.
+pub fn scrub_request(description: &str) -> Option<String> {
+    let description = description.trim();
+
+    if description.starts_with('{') {
+        return scrub_body(description);
+    }
+
+    let (method, rest) = description.split_once(' ').unwrap_or((description, ""));
+    if !HTTP_METHOD_EXTRACTOR_REGEX.is_match(method) {
+        return None;
+    }
+
+    let rest = rest.trim_start();
+    let (path, body) = match rest.find(char::is_whitespace) {
+        Some(index) => (&rest[..index], rest[index..].trim_start()),
+        None => (rest, ""),
+    };
+
+    if !path.starts_with('/') {
+        return None;
+    }
+
+    let mut scrubbed = format!("{} {}", method.to_uppercase(), scrub_path(path));
+    if !body.is_empty() {
+        scrubbed.push(' ');
+        scrubbed.push_str(&scrub_body(body)?);
+    }
+
+    Some(scrubbed)
+}
+
+/// Scrubs the request path, dropping the query string.
+fn scrub_path(path: &str) -> String {
+    let path = path.split_once('?').map_or(path, |(path, _query)| path);
+
+    let mut segments = Vec::new();
+    let mut is_document_id = false;
+    for segment in path.split('/').filter(|s| !s.is_empty()) {
+        if is_document_id {
+            segments.push("*".to_owned());
+            is_document_id = false;
+        } else if segment.starts_with('_') {
+            is_document_id = DOCUMENT_ENDPOINTS.contains(&segment);
+            segments.push(segment.to_owned());
+        } else {
+            segments.push(INDEX_DIGITS_REGEX.replace_all(segment, "*").into_owned());
+        }
+    }
+
+    format!("/{}", segments.join("/"))
+}
+
+/// Scrubs a query DSL body.
+///
+/// Bulk and multi-search requests contain multiple newline-delimited documents. Only the first
+/// occurrence of each scrubbed document is kept, so that the result does not depend on the number
+/// of operations in the request.
+fn scrub_body(body: &str) -> Option<String> {
+    let mut seen = BTreeSet::new();
+    let mut documents = Vec::new();
+
+    for document in serde_json::Deserializer::from_str(body).into_iter::<Value>() {
+        let mut document = document.ok()?;
+        scrub_value(&mut document);
+
+        let document = serde_json::to_string(&document).ok()?;
+        if seen.insert(document.clone()) {
+            documents.push(document);
+        }
+    }
+
+    Some(documents.join(" "))
+}
+
+/// Returns the API endpoint and the index of a scrubbed request.
+///
+/// The endpoint is returned without its leading underscore, for example `search`.
+pub fn endpoint_and_index(scrubbed: &str) -> (Option<&str>, Option<&str>) {
+    let Some(path) = scrubbed.split(' ').nth(1).filter(|p| p.starts_with('/')) else {
+        return (None, None);
+    };
+
+    let mut endpoint = None;
+    let mut index = None;
+    for segment in path.split('/').filter(|s| !s.is_empty()) {
+        match segment.strip_prefix('_') {
+            Some(name) => {
+                endpoint.get_or_insert(name);
+            }
+            None if endpoint.is_none() => {
+                index.get_or_insert(segment);
+            }
+            None => (),
+        }
+    }
+
+    (endpoint, index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scrub_search() {
+        let description = r#"GET /products/_search?typed_keys=true {"query": {"bool": {"must": [{"match": {"title": "red shoes"}}], "filter": [{"range": {"price": {"lte": 100}}}]}}, "size": 10}"#;
+
+        assert_eq!(
+            scrub_request(description).as_deref(),
+            Some(
+                r#"GET /products/_search {"query":{"bool":{"filter":[{"range":{"price":{"lte":"?"}}}],"must":[{"match":{"title":"?"}}]}},"size":"?"}"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_scrub_document_id() {
+        assert_eq!(
+            scrub_request("GET /users/_doc/8a7d2b1c").as_deref(),
+            Some("GET /users/_doc/*")
+        );
+        assert_eq!(
+            scrub_request("post /users/_update/42 {\"doc\": {\"name\": \"Jane\"}}").as_deref(),
+            Some(r#"POST /users/_update/* {"doc":{"name":"?"}}"#)
+        );
+    }
+
+    #[test]
+    fn test_scrub_time_based_index() {
+        assert_eq!(
+            scrub_request("GET /logs-2024.05.01,logs-2024.05.02/_count").as_deref(),
+            Some("GET /logs-*.*.*,logs-*.*.*/_count")
+        );
+    }
+
+    #[test]
+    fn test_scrub_bulk() {
+        let description = "POST /_bulk\n{\"index\": {\"_index\": \"users\"}}\n{\"name\": \"a\"}\n{\"index\": {\"_index\": \"users\"}}\n{\"name\": \"b\"}\n";
+
+        assert_eq!(
+            scrub_request(description).as_deref(),
+            Some(r#"POST /_bulk {"index":{"_index":"?"}} {"name":"?"}"#)
+        );
+    }
+
+    #[test]
+    fn test_scrub_body_only() {
+        assert_eq!(
+            scrub_request(r#"{"query": {"term": {"user.id": "kimchy"}}}"#).as_deref(),
+            Some(r#"{"query":{"term":{"user.id":"?"}}}"#)
+        );
+    }
+
+    #[test]
+    fn test_scrub_invalid() {
+        assert_eq!(scrub_request("search"), None);
+        assert_eq!(scrub_request("GET products"), None);
+        assert_eq!(scrub_request("GET /products/_search {invalid"), None);
+    }
+
+    #[test]
+    fn test_endpoint_and_index() {
+        assert_eq!(
+            endpoint_and_index("GET /products/_search {}"),
+            (Some("search"), Some("products"))
+        );
+        assert_eq!(endpoint_and_index("POST /_bulk {}"), (Some("bulk"), None));
+        assert_eq!(
+            endpoint_and_index("PUT /products"),
+            (None, Some("products"))
+        );
+        assert_eq!(endpoint_and_index(r#"{"query":{}}"#), (None, None));
+    }
+}
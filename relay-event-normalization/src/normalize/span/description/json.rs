@@ -0,0 +1,69 @@
+//! Scrubbing of values in JSON query documents, shared by MongoDB and Elasticsearch queries.
+use std::collections::HashSet;
+
+use serde_json::Value;
+
+/// The placeholder for scrubbed values.
+const PLACEHOLDER: &str = "?";
+
+/// Replaces all scalar values in a JSON document with `"?"`.
+///
+/// Object keys, which contain field names and query operators, are preserved. Arrays are
+/// scrubbed element-wise and repeated elements are collapsed into their first occurrence, so that
+/// the result does not depend on the number of values in a list.
+pub fn scrub_value(value: &mut Value) {
+    match value {
+        Value::Object(map) => map.values_mut().for_each(scrub_value),
+        Value::Array(items) => {
+            items.iter_mut().for_each(scrub_value);
+            let mut seen = HashSet::new();
+            items.retain(|item| seen.insert(item.to_string()));
+        }
+        _ => *value = Value::String(PLACEHOLDER.to_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_scrub_value() {
+        let mut value = json!({
+            "name": "John",
+            "age": {"$gt": 21},
+            "tags": ["a", "b", "c"],
+            "$or": [{"active": true}, {"active": false}, {"deleted": null}],
+        });
+
+        scrub_value(&mut value);
+
+        assert_eq!(
+            value,
+            json!({
+                "name": "?",
+                "age": {"$gt": "?"},
+                "tags": ["?"],
+                "$or": [{"active": "?"}, {"deleted": "?"}],
+            })
+        );
+    }
+
+    #[test]
+    fn test_scrub_value_non_adjacent_duplicates() {
+        let mut value = json!([
+            {"active": true},
+            {"deleted": null},
+            {"active": false},
+            [1, 2],
+            {"deleted": 1},
+            ["x"],
+        ]);
+
+        scrub_value(&mut value);
+
+        assert_eq!(value, json!([{"active": "?"}, {"deleted": "?"}, ["?"]]));
+    }
+}
@@ -1,5 +1,9 @@
 //! Span description scrubbing logic.
+mod elasticsearch;
+mod json;
+mod mongodb;
 mod resource;
+mod rpc;
 mod sql;
 use once_cell::sync::Lazy;
 use psl;
@@ -12,6 +16,7 @@ use std::net::{Ipv4Addr, Ipv6Addr};
 use std::path::Path;
 use url::{Host, Url};
 
+use crate::graphql;
 use crate::regexes::{
     DB_SQL_TRANSACTION_CORE_DATA_REGEX, DB_SUPABASE_REGEX, FUNCTION_NORMALIZER_REGEX,
     REDIS_COMMAND_REGEX, RESOURCE_NORMALIZER_REGEX,
@@ -48,6 +53,9 @@ pub(crate) fn scrub_span_description(
     let db_system = data
         .and_then(|data| data.db_system.value())
         .and_then(|system| system.as_str());
+    let db_operation = data
+        .and_then(|data| data.db_operation.value())
+        .and_then(|operation| operation.as_str());
     let span_origin = span.origin.as_str();
 
     let mut parsed_sql = None;
@@ -56,15 +64,19 @@ pub(crate) fn scrub_span_description(
         .as_str()
         .map(|op| op.split_once('.').unwrap_or((op, "")))
         .and_then(|(op, sub)| match (op, sub) {
-            ("http", _) => scrub_http(description),
+            ("http", _) => scrub_http_span(span, description, false),
+            ("graphql", _) => graphql_operation(span).map(|operation| operation.to_string()),
+            ("rpc" | "grpc", _) => rpc_call(span).map(|call| call.to_string()),
             ("cache", _) | ("db", "redis") => scrub_redis_keys(description),
             ("db", _) if db_system == Some("redis") => scrub_redis_keys(description),
             ("db", sub) => {
-                if sub.contains("clickhouse")
-                    || sub.contains("mongodb")
+                if is_elasticsearch(sub, db_system) {
+                    elasticsearch::scrub_request(description)
+                } else if sub.contains("mongodb") || is_sql_mongodb(description, db_system) {
+                    mongodb::scrub_command(description, db_operation)
+                } else if sub.contains("clickhouse")
                     || sub.contains("redis")
                     || is_legacy_activerecord(sub, db_system)
-                    || is_sql_mongodb(description, db_system)
                 {
                     None
                 // spans coming from CoreData need to be scrubbed differently.
@@ -141,6 +153,103 @@ pub(crate) fn scrub_span_description(
     (scrubbed_description, parsed_sql)
 }
 
+/// Returns the GraphQL operation executed by a span.
+///
+/// The document is read from the `graphql.document` span data attribute. For `graphql.*` spans, the
+/// span description is parsed instead if the attribute is missing.
+pub(crate) fn graphql_operation(span: &Span) -> Option<graphql::Operation> {
+    let data = span.data.value();
+    let operation_name = data
+        .and_then(|data| data.other.get("graphql.operation.name"))
+        .and_then(|value| value.as_str());
+
+    let document = data
+        .and_then(|data| data.other.get("graphql.document"))
+        .and_then(|value| value.as_str());
+
+    match document {
+        Some(document) => graphql::parse_operation(document, operation_name),
+        None if span.op.as_str().is_some_and(|op| op.starts_with("graphql")) => {
+            graphql::parse_operation(span.description.as_str()?, operation_name)
+        }
+        None => None,
+    }
+}
+
+/// Returns the service and method called by an `rpc.*` or `grpc.*` span.
+///
+/// The call is read from the `rpc.service` and `rpc.method` span data attributes, and otherwise
+/// parsed from the span description.
+pub(crate) fn rpc_call(span: &Span) -> Option<rpc::Call<'_>> {
+    let op = span.op.as_str()?;
+    let (op, _) = op.split_once('.').unwrap_or((op, ""));
+    if op != "rpc" && op != "grpc" {
+        return None;
+    }
+
+    let data = span.data.value();
+    let service = data
+        .and_then(|data| data.other.get("rpc.service"))
+        .and_then(|value| value.as_str());
+    let method = data
+        .and_then(|data| data.other.get("rpc.method"))
+        .and_then(|value| value.as_str());
+
+    rpc::Call::resolve(span.description.as_str(), service, method)
+}
+
+/// The action and domain of a scrubbed query to a document database.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct DocumentQuery {
+    /// The operation, such as the MongoDB command or the Elasticsearch API endpoint.
+    pub action: Option<String>,
+    /// The MongoDB collection or the Elasticsearch index.
+    pub domain: Option<String>,
+}
+
+/// Extracts the action and domain from the scrubbed description of a MongoDB or Elasticsearch span.
+///
+/// Returns `None` for all other spans.
+pub(crate) fn document_query(span: &Span, scrubbed_description: &str) -> Option<DocumentQuery> {
+    let op = span.op.as_str()?;
+    let (op, sub) = op.split_once('.').unwrap_or((op, ""));
+    if op != "db" {
+        return None;
+    }
+
+    let data = span.data.value();
+    let db_system = data
+        .and_then(|data| data.db_system.value())
+        .and_then(|system| system.as_str());
+    let db_operation = data
+        .and_then(|data| data.db_operation.value())
+        .and_then(|operation| operation.as_str());
+
+    if is_elasticsearch(sub, db_system) {
+        let (endpoint, index) = elasticsearch::endpoint_and_index(scrubbed_description);
+        Some(DocumentQuery {
+            action: endpoint.map(str::to_uppercase),
+            domain: index.map(str::to_owned),
+        })
+    } else if sub.contains("mongodb") || is_sql_mongodb(scrubbed_description, db_system) {
+        let (command, collection) =
+            mongodb::command_and_collection(scrubbed_description, db_operation)?;
+        Some(DocumentQuery {
+            action: Some(command.to_uppercase()),
+            domain: collection,
+        })
+    } else {
+        None
+    }
+}
+
+/// A span declares an Elasticsearch or OpenSearch operation.
+fn is_elasticsearch(sub_op: &str, db_system: Option<&str>) -> bool {
+    sub_op.contains("elasticsearch")
+        || sub_op.contains("opensearch")
+        || matches!(db_system, Some("elasticsearch" | "opensearch"))
+}
+
 /// A span declares `op: db.sql.query`, but contains mongodb.
 fn is_sql_mongodb(description: &str, db_system: Option<&str>) -> bool {
     description.contains("\"$")
@@ -166,7 +275,37 @@ fn scrub_supabase(string: &str) -> Option<String> {
     Some(DB_SUPABASE_REGEX.replace_all(string, "{%s}").into())
 }
 
-fn scrub_http(string: &str) -> Option<String> {
+/// Returns the description that user-defined span grouping rules are matched against.
+///
+/// This is the `scrubbed` description, except for HTTP spans, where the path of the URL is kept so
+/// that rules can group on it. The host is still scrubbed, and the query and fragment are removed.
+pub(crate) fn grouping_description<'a>(span: &Span, scrubbed: &'a str) -> Cow<'a, str> {
+    let is_http = span
+        .op
+        .as_str()
+        .is_some_and(|op| op.split_once('.').map_or(op, |(op, _)| op) == "http");
+
+    match span.description.as_str() {
+        Some(description) if is_http => {
+            scrub_http_span(span, description, true).map_or(Cow::Borrowed(scrubbed), Cow::Owned)
+        }
+        _ => Cow::Borrowed(scrubbed),
+    }
+}
+
+/// Scrubs the description of an HTTP span and appends the GraphQL operation, if any.
+fn scrub_http_span(span: &Span, description: &str, keep_path: bool) -> Option<String> {
+    let scrubbed = scrub_http(description, keep_path)?;
+    Some(match graphql_operation(span) {
+        Some(operation) => format!("{scrubbed} ({operation})"),
+        None => scrubbed,
+    })
+}
+
+/// Scrubs the URL of an HTTP span description down to its method, scheme and scrubbed host.
+///
+/// With `keep_path`, the path of the URL is retained as well.
+fn scrub_http(string: &str, keep_path: bool) -> Option<String> {
     let (method, url) = string.split_once(' ')?;
     if !HTTP_METHOD_EXTRACTOR_REGEX.is_match(method) {
         return None;
@@ -181,8 +320,9 @@ fn scrub_http(string: &str) -> Option<String> {
             let scheme = url.scheme();
             let scrubbed_host = url.host().map(scrub_host);
             let domain = concatenate_host_and_port(scrubbed_host.as_deref(), url.port());
+            let path = if keep_path { url.path() } else { "" };
 
-            format!("{method} {scheme}://{domain}")
+            format!("{method} {scheme}://{domain}{path}")
         }
         Err(_) => {
             format!("{method} *")
@@ -1005,6 +1145,34 @@ mod tests {
 
     span_description_test!(db_category_with_not_sql, "{someField:someValue}", "db", "");
 
+    span_description_test!(
+        db_mongodb_command,
+        r#"{"find": "documents", "filter": {"_id": "6a2f41a3c54c"}, "limit": 1}"#,
+        "db.mongodb.query",
+        r#"{"filter":{"_id":"?"},"find":"documents","limit":"?"}"#
+    );
+
+    span_description_test!(
+        db_sql_with_mongodb_command,
+        r#"{"delete": "documents", "deletes": [{"q": {"age": {"$lt": 18}}, "limit": 0}]}"#,
+        "db.sql.query",
+        r#"{"delete":"documents","deletes":[{"limit":"?","q":{"age":{"$lt":"?"}}}]}"#
+    );
+
+    span_description_test!(
+        db_elasticsearch_request,
+        r#"GET /logs-2024.06.01/_search {"query": {"term": {"level": "error"}}}"#,
+        "db.elasticsearch",
+        r#"GET /logs-*.*.*/_search {"query":{"term":{"level":"?"}}}"#
+    );
+
+    span_description_test!(
+        db_opensearch_document,
+        "PUT /users/_doc/1234",
+        "db.opensearch.query",
+        "PUT /users/_doc/*"
+    );
+
     span_description_test!(
         resource_img_semi_colon,
         "http://www.foo.com/path/to/resource;param1=test;param2=ing",
@@ -1209,6 +1377,68 @@ mod tests {
         );
     }
 
+    #[test]
+    fn graphql_operation_from_description() {
+        let json = r#"{
+            "description": "query GetUser($id: ID!) { user(id: $id) { name } }",
+            "op": "graphql.execute"
+        }"#;
+
+        let mut span = Annotated::<Span>::from_json(json).unwrap();
+
+        let scrubbed = scrub_span_description(span.value_mut().as_mut().unwrap());
+
+        assert_eq!(scrubbed.0.as_deref(), Some("query GetUser { user }"));
+    }
+
+    #[test]
+    fn http_with_graphql_document() {
+        let json = r#"{
+            "description": "POST https://api.example.com/graphql",
+            "op": "http.client",
+            "data": {
+                "graphql.document": "mutation { addItem(name: \"shoes\") { id } }"
+            }
+        }"#;
+
+        let mut span = Annotated::<Span>::from_json(json).unwrap();
+
+        let scrubbed = scrub_span_description(span.value_mut().as_mut().unwrap());
+
+        assert_eq!(
+            scrubbed.0.as_deref(),
+            Some("POST https://api.example.com (mutation { addItem })")
+        );
+    }
+
+    span_description_test!(
+        grpc_path,
+        "/helloworld.Greeter/SayHello",
+        "grpc.client",
+        "helloworld.Greeter/SayHello"
+    );
+
+    span_description_test!(rpc_invalid, "SayHello", "rpc.client", "");
+
+    #[test]
+    fn rpc_call_from_data() {
+        let json = r#"{
+            "description": "SayHello",
+            "op": "rpc.client",
+            "data": {
+                "rpc.system": "grpc",
+                "rpc.service": "helloworld.Greeter",
+                "rpc.method": "SayHello"
+            }
+        }"#;
+
+        let mut span = Annotated::<Span>::from_json(json).unwrap();
+
+        let scrubbed = scrub_span_description(span.value_mut().as_mut().unwrap());
+
+        assert_eq!(scrubbed.0.as_deref(), Some("helloworld.Greeter/SayHello"));
+    }
+
     #[test]
     fn ui_interaction_with_component_name() {
         let json = r#"{
@@ -0,0 +1,172 @@
+//! Logic for scrubbing and normalizing span descriptions that contain MongoDB commands.
+//!
+//! SDKs record MongoDB commands as JSON documents, for example:
+//!
+//! ```json
+//! {"find": "users", "filter": {"email": "jane@example.org"}, "limit": 1}
+//! ```
+//!
+//! The key of the command names the operation, and its value is the collection. The collection is
+//! kept, while all other values are replaced with `"?"`.
+use serde_json::{Map, Value};
+
+use super::json::scrub_value;
+
+/// Names of MongoDB database commands that operate on a collection.
+///
+/// This is used to identify the command key if the span does not declare `db.operation`.
+const COMMANDS: &[&str] = &[
+    "aggregate",
+    "count",
+    "create",
+    "createIndexes",
+    "delete",
+    "distinct",
+    "drop",
+    "dropIndexes",
+    "find",
+    "findAndModify",
+    "getMore",
+    "insert",
+    "listIndexes",
+    "mapReduce",
+    "update",
+];
+
+/// Returns the key of the command in a MongoDB command document.
+fn find_command<'a>(document: &'a Map<String, Value>, operation: Option<&str>) -> Option<&'a str> {
+    if let Some((key, _)) = operation.and_then(|op| document.get_key_value(op)) {
+        return Some(key.as_str());
+    }
+
+    COMMANDS
+        .iter()
+        .find_map(|command| Some(document.get_key_value(*command)?.0.as_str()))
+}
+
+/// Returns the key that holds the collection name for a command.
+///
+/// Most commands have the collection as value of the command key, except for `getMore`, which
+/// references a cursor.
+fn collection_key(command: &str) -> &str {
+    match command {
+        "getMore" => "collection",
+        command => command,
+    }
+}
+
+fn parse_command(description: &str) -> Option<Map<String, Value>> {
+    match serde_json::from_str(description) {
+        Ok(Value::Object(document)) => Some(document),
+        _ => None,
+    }
+}
+
+/// Scrubs a MongoDB command.
+///
+/// `operation` is the command name from the span's `db.operation`, if available. Returns `None` if
+/// the description is not a JSON document or does not contain a known command.
+pub fn scrub_command(description: &str, operation: Option<&str>) -> Option<String> {
+    let mut document = parse_command(description)?;
+    let command = find_command(&document, operation)?;
+    let collection_key = collection_key(command).to_owned();
+
+    for (key, value) in document.iter_mut() {
+        if *key != collection_key || !value.is_string() {
+            scrub_value(value);
+        }
+    }
+
+    serde_json::to_string(&document).ok()
+}
+
+/// Returns the command name and the collection of a MongoDB command.
+pub fn command_and_collection(
+    description: &str,
+    operation: Option<&str>,
+) -> Option<(String, Option<String>)> {
+    let document = parse_command(description)?;
+    let command = find_command(&document, operation)?;
+
+    let collection = document
+        .get(collection_key(command))
+        .and_then(Value::as_str)
+        .map(str::to_owned);
+
+    Some((command.to_owned(), collection))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scrub_find() {
+        let description = r#"{"find": "users", "filter": {"email": "jane@example.org", "age": {"$gt": 21}}, "limit": 1}"#;
+
+        assert_eq!(
+            scrub_command(description, None).as_deref(),
+            Some(r#"{"filter":{"age":{"$gt":"?"},"email":"?"},"find":"users","limit":"?"}"#)
+        );
+    }
+
+    #[test]
+    fn test_scrub_insert() {
+        let description =
+            r#"{"insert": "users", "documents": [{"name": "a"}, {"name": "b"}], "ordered": true}"#;
+
+        assert_eq!(
+            scrub_command(description, Some("insert")).as_deref(),
+            Some(r#"{"documents":[{"name":"?"}],"insert":"users","ordered":"?"}"#)
+        );
+    }
+
+    #[test]
+    fn test_scrub_operation_from_data() {
+        let description = r#"{"renameCollection": "db.users", "to": "db.people"}"#;
+
+        assert_eq!(scrub_command(description, None), None);
+        assert_eq!(
+            scrub_command(description, Some("renameCollection")).as_deref(),
+            Some(r#"{"renameCollection":"db.users","to":"?"}"#)
+        );
+    }
+
+    #[test]
+    fn test_scrub_get_more() {
+        let description = r#"{"getMore": 12345, "collection": "users"}"#;
+
+        assert_eq!(
+            scrub_command(description, None).as_deref(),
+            Some(r#"{"collection":"users","getMore":"?"}"#)
+        );
+    }
+
+    #[test]
+    fn test_scrub_invalid() {
+        assert_eq!(scrub_command("find({some_id:1234567890})", None), None);
+        assert_eq!(scrub_command(r#"["find", "users"]"#, None), None);
+        assert_eq!(scrub_command(r#"{"some": "document"}"#, None), None);
+    }
+
+    #[test]
+    fn test_command_and_collection() {
+        let scrubbed = r#"{"filter":{"email":"?"},"find":"users"}"#;
+        assert_eq!(
+            command_and_collection(scrubbed, None),
+            Some(("find".to_owned(), Some("users".to_owned())))
+        );
+
+        let scrubbed = r#"{"collection":"users","getMore":"?"}"#;
+        assert_eq!(
+            command_and_collection(scrubbed, None),
+            Some(("getMore".to_owned(), Some("users".to_owned())))
+        );
+
+        let scrubbed = r#"{"count":{"$literal":"?"}}"#;
+        assert_eq!(
+            command_and_collection(scrubbed, None),
+            Some(("count".to_owned(), None))
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! Logic for normalizing span descriptions of remote procedure calls, such as gRPC.
+//!
+//! Descriptions are normalized to `service/method`, for example:
+//!
+//! ```text
+//! helloworld.Greeter/SayHello
+//! ```
+//!
+//! The service and method are read from the `rpc.service` and `rpc.method` span data attributes.
+//! If they are missing, they are parsed from a description in the form of a gRPC request path,
+//! optionally preceded by the HTTP method, such as `POST /helloworld.Greeter/SayHello`.
+
+/// Returns `true` if the string is a valid service or method name.
+///
+/// Service names are fully qualified and may contain dots to separate the package, for example
+/// `grpc.health.v1.Health`.
+fn is_valid_name(name: &str, allow_dots: bool) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || (allow_dots && c == '.'))
+}
+
+/// Parses the service and method from a gRPC request path.
+///
+/// Returns `None` if the description does not contain exactly a service and a method.
+pub fn parse_path(description: &str) -> Option<(&str, &str)> {
+    let description = description.trim();
+    let path = match description.split_once(' ') {
+        Some((method, path)) if method.eq_ignore_ascii_case("POST") => path.trim_start(),
+        Some(_) => return None,
+        None => description,
+    };
+
+    let path = path.strip_prefix('/').unwrap_or(path);
+    let (service, method) = path.split_once('/')?;
+
+    (is_valid_name(service, true) && is_valid_name(method, false)).then_some((service, method))
+}
+
+/// The service and method of a remote procedure call.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Call<'a> {
+    /// The fully qualified name of the service, for example `helloworld.Greeter`.
+    pub service: &'a str,
+    /// The name of the method, for example `SayHello`.
+    pub method: &'a str,
+}
+
+impl<'a> Call<'a> {
+    /// Resolves the service and method of an RPC call.
+    ///
+    /// `service` and `method` are the values of the `rpc.service` and `rpc.method` span data
+    /// attributes. They take precedence over the service and method parsed from the description.
+    pub fn resolve(
+        description: Option<&'a str>,
+        service: Option<&'a str>,
+        method: Option<&'a str>,
+    ) -> Option<Self> {
+        let parsed = description.and_then(parse_path);
+
+        Some(Self {
+            service: service.or(parsed.map(|(service, _)| service))?,
+            method: method.or(parsed.map(|(_, method)| method))?,
+        })
+    }
+}
+
+impl std::fmt::Display for Call<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.service, self.method)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(
+            parse_path("/helloworld.Greeter/SayHello"),
+            Some(("helloworld.Greeter", "SayHello"))
+        );
+        assert_eq!(
+            parse_path("grpc.health.v1.Health/Check"),
+            Some(("grpc.health.v1.Health", "Check"))
+        );
+        assert_eq!(
+            parse_path("POST /helloworld.Greeter/SayHello"),
+            Some(("helloworld.Greeter", "SayHello"))
+        );
+    }
+
+    #[test]
+    fn test_parse_path_invalid() {
+        assert_eq!(parse_path("SayHello"), None);
+        assert_eq!(parse_path("GET /users/123"), None);
+        assert_eq!(parse_path("/helloworld.Greeter/Say.Hello"), None);
+        assert_eq!(parse_path("/helloworld.Greeter/SayHello/extra"), None);
+        assert_eq!(parse_path("/helloworld.Greeter/"), None);
+    }
+
+    #[test]
+    fn test_resolve_call() {
+        let call = Call::resolve(
+            Some("SayHello"),
+            Some("helloworld.Greeter"),
+            Some("SayHello"),
+        );
+        assert_eq!(
+            call.map(|call| call.to_string()).as_deref(),
+            Some("helloworld.Greeter/SayHello")
+        );
+
+        let call = Call::resolve(
+            Some("/helloworld.Greeter/SayHello"),
+            None,
+            Some("SayHelloAgain"),
+        );
+        assert_eq!(
+            call,
+            Some(Call {
+                service: "helloworld.Greeter",
+                method: "SayHelloAgain",
+            })
+        );
+
+        assert_eq!(
+            Call::resolve(Some("SayHello"), None, Some("SayHello")),
+            None
+        );
+        assert_eq!(Call::resolve(None, None, None), None);
+    }
+}
@@ -6,7 +6,7 @@ use std::time::Instant;
 
 use crate::statsd::Timers;
 use once_cell::sync::Lazy;
-use parser::normalize_parsed_queries;
+use parser::{normalize_parsed_queries, SqlDialect};
 use regex::Regex;
 
 /// Removes SQL comments starting with "--" or "#".
@@ -110,10 +110,12 @@ static ALREADY_NORMALIZED_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"/\?|\$1
 /// Normalizes the given SQL-query-like string.
 pub fn scrub_queries(db_system: Option<&str>, string: &str) -> (Option<String>, Mode) {
     let t = Instant::now();
-    let (res, mode) = scrub_queries_inner(db_system, string);
+    let dialect = SqlDialect::from_db_system(db_system);
+    let (res, mode) = scrub_queries_inner(db_system, &dialect, string);
     relay_statsd::metric!(
         timer(Timers::SpanDescriptionNormalizeSQL) = t.elapsed(),
         mode = mode.as_str(),
+        dialect = dialect.as_str(),
     );
     (res, mode)
 }
@@ -136,8 +138,12 @@ impl Mode {
     }
 }
 
-fn scrub_queries_inner(db_system: Option<&str>, string: &str) -> (Option<String>, Mode) {
-    if let Ok((queries, ast)) = normalize_parsed_queries(db_system, string) {
+fn scrub_queries_inner(
+    db_system: Option<&str>,
+    dialect: &SqlDialect,
+    string: &str,
+) -> (Option<String>, Mode) {
+    if let Ok((queries, ast)) = normalize_parsed_queries(db_system, dialect, string) {
         return (Some(queries), Mode::Parsed(ast));
     }
 
@@ -1022,4 +1028,60 @@ mod tests {
         "SELECT (toStartOfHour(finish_ts, 'Universal') AS _snuba_time), (uniqIf((nullIf(user, '') AS _snuba_user), greater(multiIf(equals(tupleElement(('duration', 300), 1), 'lcp'), (if(has(measurements.key, 'lcp'), arrayElement(measurements.value, indexOf(measurements.key, 'lcp')), NULL) AS `_snuba_measurements[lcp]`), (duration AS _snuba_duration)), multiply(tupleElement(('duration', 300), 2), 4))) AS _snuba_count_miserable_user), (ifNull(divide(plus(_snuba_count_miserable_user, 4.56), plus(nullIf(uniqIf(_snuba_user, greater(multiIf(equals(tupleElement(('duration', 300), 1), 'lcp'), `_snuba_measurements[lcp]`, _snuba_duration), 0)), 0), 113.45)), 0) AS _snuba_user_misery), _snuba_count_miserable_user, (divide(countIf(notEquals(transaction_status, 0) AND notEquals(transaction_status, 1) AND notEquals(transaction_status, 2)), count()) AS _snuba_failure_rate), (divide(count(), divide(3600.0, 60)) AS _snuba_tpm_3600) FROM transactions_dist WHERE equals(('transaction' AS _snuba_type), 'transaction') AND greaterOrEquals((finish_ts AS _snuba_finish_ts), toDateTime('2023-06-13T09:08:51', 'Universal')) AND less(_snuba_finish_ts, toDateTime('2023-07-11T09:08:51', 'Universal')) AND in((project_id AS _snuba_project_id), [123, 456, 789]) AND equals((environment AS _snuba_environment), 'production') GROUP BY _snuba_time ORDER BY _snuba_time ASC LIMIT 10000 OFFSET 0",
         "SELECT (toStartOfHour(finish_ts, %s) AS _snuba_time), (uniqIf((nullIf(user, %s) AS _snuba_user), greater(multiIf(equals(tupleElement((%s, %s), %s), %s), (if(has(key, %s), arrayElement(value, indexOf(key, %s)), NULL) AS `_snuba_measurements[lcp]`), (duration AS _snuba_duration)), multiply(tupleElement((%s, %s), %s), %s))) AS _snuba_count_miserable_user), (ifNull(divide(plus(_snuba_count_miserable_user, %s), plus(nullIf(uniqIf(_snuba_user, greater(multiIf(equals(tupleElement((%s, %s), %s), %s), `_snuba_measurements[lcp]`, _snuba_duration), %s)), %s), %s)), %s) AS _snuba_user_misery), _snuba_count_miserable_user, (divide(countIf(notEquals(transaction_status, %s) AND notEquals(transaction_status, %s) AND notEquals(transaction_status, %s)), count()) AS _snuba_failure_rate), (divide(count(), divide(%s, %s)) AS _snuba_tpm_3600) FROM transactions_dist WHERE equals((%s AS _snuba_type), %s) AND greaterOrEquals((finish_ts AS _snuba_finish_ts), toDateTime(%s, %s)) AND less(_snuba_finish_ts, toDateTime(%s, %s)) AND in((project_id AS _snuba_project_id), [%s, %s, %s]) AND equals((environment AS _snuba_environment), %s) GROUP BY _snuba_time ORDER BY _snuba_time ASC LIMIT %s OFFSET %s"
     );
+
+    scrub_sql_test_with_dialect!(
+        postgresql_returning,
+        "postgresql",
+        r#"INSERT INTO "users" ("name", "email") VALUES ($1, $2) RETURNING "users"."id""#,
+        "INSERT INTO users (..) VALUES (%s) RETURNING id"
+    );
+
+    scrub_sql_test_with_dialect!(
+        postgresql_cast,
+        "postgresql",
+        r#"SELECT "id" FROM "events" WHERE "created_at" > $1::date"#,
+        "SELECT id FROM events WHERE created_at > %s"
+    );
+
+    scrub_sql_test_with_dialect!(
+        mariadb_backticks,
+        "mariadb",
+        "SELECT `users`.`id` FROM `users` WHERE `users`.`email` = 'jane@example.org'",
+        "SELECT id FROM users WHERE email = %s"
+    );
+
+    scrub_sql_test_with_dialect!(
+        mssql_brackets,
+        "mssql",
+        "SELECT [u].[id] FROM [dbo].[users] AS [u] WHERE [u].[name] = N'jane'",
+        "SELECT id FROM users AS u WHERE name = %s"
+    );
+
+    scrub_sql_test_with_dialect!(
+        sqlite_placeholders,
+        "sqlite",
+        r#"SELECT "id" FROM "todos" WHERE "done" = ? LIMIT ?"#,
+        "SELECT id FROM todos WHERE done = %s LIMIT %s"
+    );
+
+    scrub_sql_test_with_dialect!(
+        bigquery_backticks,
+        "bigquery",
+        "SELECT `name` FROM `dataset`.`users` WHERE `id` = 1",
+        "SELECT name FROM users WHERE id = %s"
+    );
+
+    scrub_sql_test_with_dialect!(
+        snowflake_qualified_table,
+        "snowflake",
+        r#"SELECT "ID" FROM "DB"."PUBLIC"."ORDERS" WHERE "STATUS" = 'open'"#,
+        "SELECT ID FROM ORDERS WHERE STATUS = %s"
+    );
+
+    scrub_sql_test_with_dialect!(
+        clickhouse_functions,
+        "clickhouse",
+        "SELECT count() FROM events WHERE project_id = 1 AND created_at > toDateTime('2024-01-01 00:00:00')",
+        "SELECT count() FROM events WHERE project_id = %s AND created_at > toDateTime(%s)"
+    );
 }
@@ -1,6 +1,7 @@
 //! Logic for parsing SQL queries and manipulating the resulting Abstract Syntax Tree.
 use std::borrow::Cow;
 use std::ops::ControlFlow;
+use std::panic::AssertUnwindSafe;
 
 use itertools::Itertools;
 use once_cell::sync::Lazy;
@@ -13,6 +14,8 @@ use sqlparser::ast::{
 };
 use sqlparser::dialect::{Dialect, GenericDialect};
 
+use crate::statsd::Counters;
+
 /// Keeps track of the maximum depth of an SQL expression that the [`NormalizeVisitor`] encounters.
 ///
 /// This is used to prevent the serialization of the AST from crashing.
@@ -32,36 +35,91 @@ static TABLE_NAME_REGEX: Lazy<Regex> = Lazy::new(|| {
     .unwrap()
 });
 
-/// Derive the SQL dialect from `db_system` (the value obtained from `span.data.system`)
-/// and try to parse the query into an AST.
+/// Database systems that sqlparser does not recognize by name, and the dialect that parses them.
+///
+/// The names follow the `db.system` span attribute, see
+/// <https://github.com/open-telemetry/semantic-conventions/blob/main/docs/database/database-spans.md#notes-and-well-known-identifiers-for-dbsystem>.
+const DB_SYSTEM_ALIASES: &[(&str, &str)] = &[
+    ("cockroachdb", "postgresql"),
+    ("mariadb", "mysql"),
+    ("sqlserver", "mssql"),
+];
+
+/// The SQL dialect used to parse a query, derived from `db.system`.
+pub struct SqlDialect {
+    name: String,
+    dialect: Box<dyn Dialect>,
+}
+
+impl SqlDialect {
+    /// Returns the dialect for a `db.system` value.
+    ///
+    /// Known aliases are resolved first. All other values are passed to sqlparser's
+    /// [`dialect_from_str`](sqlparser::dialect::dialect_from_str). Unknown or missing database
+    /// systems fall back to the generic dialect.
+    pub fn from_db_system(db_system: Option<&str>) -> Self {
+        let dialect = db_system.and_then(|db_system| {
+            let mut name = db_system.to_ascii_lowercase();
+            let alias = DB_SYSTEM_ALIASES.iter().find(|(system, _)| *system == name);
+            if let Some((_, dialect_name)) = alias {
+                name = (*dialect_name).to_owned();
+            }
+
+            let dialect = sqlparser::dialect::dialect_from_str(&name)?;
+            Some(Self { name, dialect })
+        });
+
+        dialect.unwrap_or_else(|| Self {
+            name: "generic".to_owned(),
+            dialect: Box::new(GenericDialect {}),
+        })
+    }
+
+    /// Returns the name of the dialect, used as metric tag.
+    ///
+    /// The name is one of the dialects known to sqlparser, so it has bounded cardinality.
+    pub fn as_str(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Try to parse the query into an AST with the dialect derived from `db_system` (the value
+/// obtained from `span.data.system`).
 pub fn parse_query(
     db_system: Option<&str>,
+    dialect: &SqlDialect,
     query: &str,
 ) -> Result<Vec<Statement>, sqlparser::parser::ParserError> {
-    relay_log::with_scope(
+    let result = relay_log::with_scope(
         |scope| {
             scope.set_tag("db_system", db_system.unwrap_or_default());
             scope.set_extra("query", query.into());
         },
-        || match std::panic::catch_unwind(|| parse_query_inner(db_system, query)) {
-            Ok(res) => res,
-            Err(_) => Err(sqlparser::parser::ParserError::ParserError(
-                "panicked".to_string(),
-            )),
+        || {
+            let parse = AssertUnwindSafe(|| parse_query_inner(dialect, query));
+            match std::panic::catch_unwind(parse) {
+                Ok(res) => res,
+                Err(_) => Err(sqlparser::parser::ParserError::ParserError(
+                    "panicked".to_string(),
+                )),
+            }
         },
-    )
+    );
+
+    relay_statsd::metric!(
+        counter(Counters::SqlParsed) += 1,
+        dialect = dialect.as_str(),
+        success = if result.is_ok() { "true" } else { "false" },
+    );
+
+    result
 }
 
 fn parse_query_inner(
-    db_system: Option<&str>,
+    dialect: &SqlDialect,
     query: &str,
 ) -> Result<Vec<Statement>, sqlparser::parser::ParserError> {
-    // See https://github.com/open-telemetry/opentelemetry-specification/blob/main/specification/trace/semantic_conventions/database.md#notes-and-well-known-identifiers-for-dbsystem
-    //     https://docs.rs/sqlparser/latest/sqlparser/dialect/fn.dialect_from_str.html
-    let dialect = db_system
-        .and_then(sqlparser::dialect::dialect_from_str)
-        .unwrap_or_else(|| Box::new(GenericDialect {}));
-    let dialect = DialectWithParameters(dialect);
+    let dialect = DialectWithParameters(dialect.dialect.as_ref());
 
     sqlparser::parser::Parser::parse_sql(&dialect, query)
 }
@@ -69,9 +127,10 @@ fn parse_query_inner(
 /// Tries to parse a series of SQL queries into an AST and normalize it.
 pub fn normalize_parsed_queries(
     db_system: Option<&str>,
+    dialect: &SqlDialect,
     string: &str,
 ) -> Result<(String, Vec<Statement>), ()> {
-    let mut parsed = parse_query(db_system, string).map_err(|_| ())?;
+    let mut parsed = parse_query(db_system, dialect, string).map_err(|_| ())?;
     parsed.visit(&mut NormalizeVisitor);
     parsed.visit(&mut MaxDepthVisitor::new());
 
@@ -783,13 +842,13 @@ impl VisitorMut for MaxDepthVisitor {
 
 /// An extension of an SQL dialect that accepts `?`, `%s`, `:c0` as valid input.
 #[derive(Debug)]
-struct DialectWithParameters(Box<dyn Dialect>);
+struct DialectWithParameters<'a>(&'a dyn Dialect);
 
-impl DialectWithParameters {
+impl DialectWithParameters<'_> {
     const PARAMETERS: &'static str = "?%:";
 }
 
-impl Dialect for DialectWithParameters {
+impl Dialect for DialectWithParameters<'_> {
     fn dialect(&self) -> std::any::TypeId {
         self.0.dialect()
     }
@@ -867,11 +926,31 @@ mod tests {
     #[test]
     fn parse_deep_expression() {
         let query = "SELECT 1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1+1";
-        assert_eq!(normalize_parsed_queries(None, query).unwrap().0, "SELECT .. + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s");
+        let dialect = SqlDialect::from_db_system(None);
+        assert_eq!(normalize_parsed_queries(None, &dialect, query).unwrap().0, "SELECT .. + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s + %s");
+    }
+
+    #[test]
+    fn dialect_from_db_system() {
+        let name = |db_system| SqlDialect::from_db_system(db_system).as_str().to_owned();
+
+        assert_eq!(name(None), "generic");
+        assert_eq!(name(Some("postgresql")), "postgresql");
+        assert_eq!(name(Some("cockroachdb")), "postgresql");
+        assert_eq!(name(Some("MariaDB")), "mysql");
+        assert_eq!(name(Some("sqlserver")), "mssql");
+        assert_eq!(name(Some("oracle")), "generic");
+
+        // Dialects known to sqlparser without an alias.
+        assert_eq!(name(Some("duckdb")), "duckdb");
+        assert_eq!(name(Some("hive")), "hive");
+        assert_eq!(name(Some("ansi")), "ansi");
+        assert_eq!(name(Some("generic")), "generic");
     }
 
     #[test]
     fn parse_dont_panic() {
-        assert!(parse_query_inner(None, "REPLACE g;'341234c").is_err());
+        let dialect = SqlDialect::from_db_system(None);
+        assert!(parse_query_inner(&dialect, "REPLACE g;'341234c").is_err());
     }
 }
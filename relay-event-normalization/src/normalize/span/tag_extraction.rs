@@ -6,6 +6,7 @@ use std::fmt::Write;
 use std::net::IpAddr;
 use std::ops::ControlFlow;
 
+use chrono::{DateTime, Utc};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use relay_base_schema::metrics::{DurationUnit, InformationUnit, MetricUnit};
@@ -18,11 +19,13 @@ use sqlparser::ast::{ObjectName, Visitor};
 use url::Url;
 
 use crate::span::description::{
-    concatenate_host_and_port, scrub_domain_name, scrub_span_description,
+    concatenate_host_and_port, document_query, graphql_operation, grouping_description, rpc_call,
+    scrub_domain_name, scrub_span_description,
 };
 use crate::utils::{
     extract_transaction_op, http_status_code_from_span, MAIN_THREAD_NAME, MOBILE_SDKS,
 };
+use crate::{apply_span_grouping_rules, SpanGroupingRules, SpanGroupingTarget};
 
 /// A list of supported span tags for tag extraction.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -81,6 +84,8 @@ pub enum SpanTagKey {
     TraceStatus,
     MessagingDestinationName,
     MessagingMessageId,
+    /// The GraphQL operation executed by the span, for example `query GetUser { user }`.
+    GraphqlOperation,
 }
 
 impl SpanTagKey {
@@ -132,6 +137,7 @@ impl SpanTagKey {
             SpanTagKey::TraceStatus => "trace.status",
             SpanTagKey::MessagingDestinationName => "messaging.destination.name",
             SpanTagKey::MessagingMessageId => "messaging.message.id",
+            SpanTagKey::GraphqlOperation => "graphql.operation",
         }
     }
 }
@@ -169,21 +175,36 @@ impl std::fmt::Display for RenderBlockingStatus {
 /// Wrapper for [`extract_span_tags`].
 ///
 /// Tags longer than `max_tag_value_size` bytes will be truncated.
-pub(crate) fn extract_span_tags_from_event(event: &mut Event, max_tag_value_size: usize) {
+pub(crate) fn extract_span_tags_from_event(
+    event: &mut Event,
+    max_tag_value_size: usize,
+    span_grouping_rules: &SpanGroupingRules,
+) {
     // Temporarily take ownership to pass both an event reference and a mutable span reference to `extract_span_tags`.
     let mut spans = std::mem::take(&mut event.spans);
     let Some(spans_vec) = spans.value_mut() else {
         return;
     };
-    extract_span_tags(event, spans_vec.as_mut_slice(), max_tag_value_size);
+    extract_span_tags(
+        event,
+        spans_vec.as_mut_slice(),
+        max_tag_value_size,
+        span_grouping_rules,
+    );
 
     event.spans = spans;
 }
 
 /// Extracts tags and measurements from event and spans and materializes them into the spans.
 ///
-/// Tags longer than `max_tag_value_size` bytes will be truncated.
-pub fn extract_span_tags(event: &Event, spans: &mut [Annotated<Span>], max_tag_value_size: usize) {
+/// Tags longer than `max_tag_value_size` bytes will be truncated. The descriptions of spans are
+/// grouped with the first matching rule in `span_grouping_rules`.
+pub fn extract_span_tags(
+    event: &Event,
+    spans: &mut [Annotated<Span>],
+    max_tag_value_size: usize,
+    span_grouping_rules: &SpanGroupingRules,
+) {
     // TODO: To prevent differences between metrics and payloads, we should not extract tags here
     // when they have already been extracted by a downstream relay.
     let shared_tags = extract_shared_tags(event);
@@ -194,13 +215,23 @@ pub fn extract_span_tags(event: &Event, spans: &mut [Annotated<Span>], max_tag_v
 
     let ttid = timestamp_by_op(spans, "ui.load.initial_display");
     let ttfd = timestamp_by_op(spans, "ui.load.full_display");
+    let now = Utc::now();
 
     for span in spans {
         let Some(span) = span.value_mut() else {
             continue;
         };
 
-        let tags = extract_tags(span, max_tag_value_size, ttid, ttfd, is_mobile, start_type);
+        let tags = extract_tags(
+            span,
+            max_tag_value_size,
+            ttid,
+            ttfd,
+            is_mobile,
+            start_type,
+            span_grouping_rules,
+            now,
+        );
 
         span.sentry_tags = Annotated::new(
             shared_tags
@@ -414,7 +445,9 @@ fn extract_segment_tags(event: &Event) -> BTreeMap<SpanTagKey, String> {
 /// Generating new span data fields is based on a combination of looking at
 /// [span operations](https://develop.sentry.dev/sdk/performance/span-operations/) and
 /// existing [span data](https://develop.sentry.dev/sdk/performance/span-data-conventions/) fields,
-/// and rely on Sentry conventions and heuristics.
+/// and rely on Sentry conventions and heuristics. Span grouping rules that expired before `now`
+/// are not applied.
+#[allow(clippy::too_many_arguments)]
 pub fn extract_tags(
     span: &Span,
     max_tag_value_size: usize,
@@ -422,6 +455,8 @@ pub fn extract_tags(
     full_display: Option<Timestamp>,
     is_mobile: bool,
     start_type: Option<&str>,
+    span_grouping_rules: &SpanGroupingRules,
+    now: DateTime<Utc>,
 ) -> BTreeMap<SpanTagKey, String> {
     let mut span_tags: BTreeMap<SpanTagKey, String> = BTreeMap::new();
 
@@ -430,12 +465,22 @@ pub fn extract_tags(
         .value()
         .and_then(|data| data.db_system.value())
         .and_then(|system| system.as_str());
+    let system = system.or_else(|| {
+        span.data
+            .value()
+            .and_then(|data| data.other.get("rpc.system"))
+            .and_then(|system| system.as_str())
+    });
     if let Some(sys) = system {
         span_tags.insert(SpanTagKey::System, sys.to_lowercase());
     }
 
     if let Some(status) = span.status.value() {
         span_tags.insert(SpanTagKey::SpanStatus, status.as_str().to_owned());
+    } else if let Some(status) =
+        grpc_status_code_from_span(span).and_then(SpanStatus::from_grpc_code)
+    {
+        span_tags.insert(SpanTagKey::SpanStatus, status.as_str().to_owned());
     }
 
     if let Some(unsanitized_span_op) = span.op.value() {
@@ -448,7 +493,27 @@ pub fn extract_tags(
             span_tags.insert(SpanTagKey::Category, category.to_owned());
         }
 
-        let (scrubbed_description, parsed_sql) = scrub_span_description(span);
+        let (mut scrubbed_description, parsed_sql) = scrub_span_description(span);
+        // User-defined grouping rules replace the built-in scrubbing when they match. They never
+        // see the raw description, so they cannot expose values that scrubbing removes.
+        let grouped = match scrubbed_description.as_deref() {
+            Some(scrubbed) if !span_grouping_rules.is_empty() => apply_span_grouping_rules(
+                span_grouping_rules,
+                SpanGroupingTarget::SpanDescription,
+                Some(&span_op),
+                &grouping_description(span, scrubbed),
+                now,
+            ),
+            _ => None,
+        };
+        if let Some((_, grouped)) = grouped {
+            scrubbed_description = Some(grouped);
+        }
+        let document_query = scrubbed_description
+            .as_deref()
+            .and_then(|scrubbed| document_query(span, scrubbed));
+        let graphql_operation = graphql_operation(span);
+        let rpc = rpc_call(span);
 
         let action = match (category, span_op.as_str(), &scrubbed_description) {
             (Some("http"), _, _) => span
@@ -466,6 +531,10 @@ pub fn extract_tags(
                     Some(command)
                 }
             }
+            (Some("graphql"), _, _) => graphql_operation
+                .as_ref()
+                .map(|operation| operation.ty.as_str().to_uppercase()),
+            (Some("rpc" | "grpc"), _, _) => rpc.map(|call| call.method.to_owned()),
             (Some("db"), _, _) => {
                 let action_from_data = span
                     .data
@@ -473,12 +542,15 @@ pub fn extract_tags(
                     .and_then(|data| data.db_operation.value())
                     .and_then(|db_op| db_op.as_str())
                     .map(|s| s.to_uppercase());
-                action_from_data.or_else(|| {
-                    span.description
-                        .value()
-                        .and_then(|d| sql_action_from_query(d))
-                        .map(|a| a.to_uppercase())
-                })
+                match document_query {
+                    Some(ref query) => action_from_data.or_else(|| query.action.clone()),
+                    None => action_from_data.or_else(|| {
+                        span.description
+                            .value()
+                            .and_then(|d| sql_action_from_query(d))
+                            .map(|a| a.to_uppercase())
+                    }),
+                }
             }
             _ => None,
         };
@@ -491,7 +563,8 @@ pub fn extract_tags(
             // HACK: Parse the normalized description to get the normalized domain.
             if let Some(scrubbed) = scrubbed_description.as_deref() {
                 let url = if let Some((_, url)) = scrubbed.split_once(' ') {
-                    url
+                    // Skip a trailing GraphQL operation after the URL.
+                    url.split_once(' ').map_or(url, |(url, _)| url)
                 } else {
                     scrubbed
                 };
@@ -551,6 +624,10 @@ pub fn extract_tags(
                 .and_then(|s| s.strip_prefix("from("))
                 .and_then(|s| s.strip_suffix(')'))
                 .map(String::from)
+        } else if let Some(query) = document_query {
+            query.domain
+        } else if let Some(call) = rpc {
+            Some(call.service.to_owned())
         } else if span_op.starts_with("db") {
             span.description
                 .value()
@@ -565,6 +642,10 @@ pub fn extract_tags(
             }
         }
 
+        if let Some(operation) = graphql_operation {
+            span_tags.insert(SpanTagKey::GraphqlOperation, operation.to_string());
+        }
+
         if span_op.starts_with("cache.") {
             if let Some(Value::Bool(cache_hit)) =
                 span.data.value().and_then(|data| data.cache_hit.value())
@@ -707,6 +788,8 @@ pub fn extract_tags(
 
     if let Some(status_code) = http_status_code_from_span(span) {
         span_tags.insert(SpanTagKey::StatusCode, status_code);
+    } else if let Some(status_code) = grpc_status_code_from_span(span) {
+        span_tags.insert(SpanTagKey::StatusCode, status_code.to_string());
     }
 
     if is_mobile {
@@ -1040,6 +1123,22 @@ fn extract_captured_substring<'a>(string: &'a str, pattern: &'a Lazy<Regex>) ->
     None
 }
 
+/// Returns the gRPC status code from the `rpc.grpc.status_code` span data attribute.
+fn grpc_status_code_from_span(span: &Span) -> Option<i64> {
+    match span
+        .data
+        .value()?
+        .other
+        .get("rpc.grpc.status_code")?
+        .value()?
+    {
+        Value::I64(code) => Some(*code),
+        Value::U64(code) => i64::try_from(*code).ok(),
+        Value::String(code) => code.parse().ok(),
+        _ => None,
+    }
+}
+
 /// Returns the category of a span from its operation. The mapping is available in:
 /// <https://develop.sentry.dev/sdk/performance/span-operations/>
 fn span_op_to_category(op: &str) -> Option<&str> {
@@ -1388,7 +1487,7 @@ LIMIT 1
             .into_value()
             .unwrap();
 
-        extract_span_tags_from_event(&mut event, 200);
+        extract_span_tags_from_event(&mut event, 200, &Default::default());
 
         let spans = event.spans.value().unwrap();
 
@@ -1450,7 +1549,7 @@ LIMIT 1
             .into_value()
             .unwrap();
 
-        extract_span_tags_from_event(&mut event, 200);
+        extract_span_tags_from_event(&mut event, 200, &Default::default());
 
         let span = &event.spans.value().unwrap()[0];
 
@@ -1568,7 +1667,7 @@ LIMIT 1
             .into_value()
             .unwrap();
 
-        extract_span_tags_from_event(&mut event, 200);
+        extract_span_tags_from_event(&mut event, 200, &Default::default());
 
         let span_1 = &event.spans.value().unwrap()[0];
         let span_2 = &event.spans.value().unwrap()[1];
@@ -1621,7 +1720,7 @@ LIMIT 1
             .into_value()
             .unwrap();
 
-        extract_span_tags_from_event(&mut event, 200);
+        extract_span_tags_from_event(&mut event, 200, &Default::default());
 
         let span = &event
             .spans
@@ -1729,7 +1828,7 @@ LIMIT 1
             .into_value()
             .unwrap();
 
-        extract_span_tags_from_event(&mut event, 200);
+        extract_span_tags_from_event(&mut event, 200, &Default::default());
 
         let span_1 = &event.spans.value().unwrap()[0];
         let span_2 = &event.spans.value().unwrap()[1];
@@ -1852,7 +1951,7 @@ LIMIT 1
             .into_value()
             .unwrap();
 
-        extract_span_tags_from_event(&mut event, 200);
+        extract_span_tags_from_event(&mut event, 200, &Default::default());
 
         let span_1 = &event.spans.value().unwrap()[0];
         let span_2 = &event.spans.value().unwrap()[1];
@@ -1960,7 +2059,7 @@ LIMIT 1
             .into_value()
             .unwrap();
 
-        extract_span_tags_from_event(&mut event, 200);
+        extract_span_tags_from_event(&mut event, 200, &Default::default());
 
         let span = &event.spans.value().unwrap()[0];
 
@@ -2017,7 +2116,7 @@ LIMIT 1
             .into_value()
             .unwrap();
 
-        extract_span_tags_from_event(&mut event, 200);
+        extract_span_tags_from_event(&mut event, 200, &Default::default());
 
         let span = &event.spans.value().unwrap()[0];
         let tags = span.value().unwrap().sentry_tags.value().unwrap();
@@ -2045,7 +2144,16 @@ LIMIT 1
             .unwrap()
             .into_value()
             .unwrap();
-        let tags = extract_tags(&span, 200, None, None, false, None);
+        let tags = extract_tags(
+            &span,
+            200,
+            None,
+            None,
+            false,
+            None,
+            &Default::default(),
+            Utc::now(),
+        );
 
         assert_eq!(
             tags.get(&SpanTagKey::BrowserName),
@@ -2085,7 +2193,7 @@ LIMIT 1
             .into_value()
             .unwrap();
 
-        extract_span_tags_from_event(&mut event, 200);
+        extract_span_tags_from_event(&mut event, 200, &Default::default());
 
         let span = &event.spans.value().unwrap()[0];
         let tags = span.value().unwrap().sentry_tags.value().unwrap();
@@ -2116,7 +2224,16 @@ LIMIT 1
             .unwrap()
             .into_value()
             .unwrap();
-        let tags = extract_tags(&span, 200, None, None, false, None);
+        let tags = extract_tags(
+            &span,
+            200,
+            None,
+            None,
+            false,
+            None,
+            &Default::default(),
+            Utc::now(),
+        );
 
         assert_eq!(
             tags.get(&SpanTagKey::MessagingDestinationName),
@@ -2241,7 +2358,7 @@ LIMIT 1
             .into_value()
             .unwrap();
 
-        extract_span_tags_from_event(&mut event, 200);
+        extract_span_tags_from_event(&mut event, 200, &Default::default());
 
         let span = &event.spans.value().unwrap()[0];
         let tags = span.value().unwrap().sentry_tags.value().unwrap();
@@ -2339,7 +2456,16 @@ LIMIT 1
             .unwrap();
         span.description.set_value(Some(description.into()));
 
-        extract_tags(&span, 200, None, None, false, None)
+        extract_tags(
+            &span,
+            200,
+            None,
+            None,
+            false,
+            None,
+            &Default::default(),
+            Utc::now(),
+        )
     }
 
     #[test]
@@ -2377,6 +2503,255 @@ LIMIT 1
         assert_eq!(tags.get(&SpanTagKey::Domain), None);
     }
 
+    #[test]
+    fn graphql_http_client() {
+        let json = r#"{
+            "description": "POST https://api.example.com/graphql",
+            "op": "http.client",
+            "data": {
+                "graphql.document": "query A { a } query B { b }",
+                "graphql.operation.name": "B"
+            }
+        }"#;
+
+        let span = Annotated::<Span>::from_json(json)
+            .unwrap()
+            .into_value()
+            .unwrap();
+        let tags = extract_tags(
+            &span,
+            200,
+            None,
+            None,
+            false,
+            None,
+            &Default::default(),
+            Utc::now(),
+        );
+
+        assert_eq!(
+            tags.get(&SpanTagKey::Description).map(String::as_str),
+            Some("POST https://api.example.com (query B { b })")
+        );
+        assert_eq!(
+            tags.get(&SpanTagKey::Domain).map(String::as_str),
+            Some("api.example.com")
+        );
+        assert_eq!(
+            tags.get(&SpanTagKey::GraphqlOperation).map(String::as_str),
+            Some("query B { b }")
+        );
+    }
+
+    #[test]
+    fn graphql_span() {
+        let json = r#"{
+            "description": "mutation AddItem { addItem { id } }",
+            "op": "graphql.mutation"
+        }"#;
+
+        let span = Annotated::<Span>::from_json(json)
+            .unwrap()
+            .into_value()
+            .unwrap();
+        let tags = extract_tags(
+            &span,
+            200,
+            None,
+            None,
+            false,
+            None,
+            &Default::default(),
+            Utc::now(),
+        );
+
+        assert_eq!(
+            tags.get(&SpanTagKey::Action).map(String::as_str),
+            Some("MUTATION")
+        );
+        assert_eq!(
+            tags.get(&SpanTagKey::GraphqlOperation).map(String::as_str),
+            Some("mutation AddItem { addItem }")
+        );
+    }
+
+    #[test]
+    fn mongodb() {
+        let json = r#"{
+            "description": "{\"find\": \"users\", \"filter\": {\"email\": \"jane@example.org\"}}",
+            "op": "db",
+            "data": {
+                "db.system": "mongodb"
+            }
+        }"#;
+
+        let span = Annotated::<Span>::from_json(json)
+            .unwrap()
+            .into_value()
+            .unwrap();
+        let tags = extract_tags(
+            &span,
+            200,
+            None,
+            None,
+            false,
+            None,
+            &Default::default(),
+            Utc::now(),
+        );
+
+        assert_eq!(
+            tags.get(&SpanTagKey::Description).map(String::as_str),
+            Some(r#"{"filter":{"email":"?"},"find":"users"}"#)
+        );
+        assert_eq!(
+            tags.get(&SpanTagKey::Action).map(String::as_str),
+            Some("FIND")
+        );
+        assert_eq!(
+            tags.get(&SpanTagKey::Domain).map(String::as_str),
+            Some("users")
+        );
+    }
+
+    #[test]
+    fn elasticsearch() {
+        let json = r#"{
+            "description": "GET /products/_search {\"query\": {\"match\": {\"title\": \"shoes\"}}}",
+            "op": "db",
+            "data": {
+                "db.system": "elasticsearch"
+            }
+        }"#;
+
+        let span = Annotated::<Span>::from_json(json)
+            .unwrap()
+            .into_value()
+            .unwrap();
+        let tags = extract_tags(
+            &span,
+            200,
+            None,
+            None,
+            false,
+            None,
+            &Default::default(),
+            Utc::now(),
+        );
+
+        assert_eq!(
+            tags.get(&SpanTagKey::Description).map(String::as_str),
+            Some(r#"GET /products/_search {"query":{"match":{"title":"?"}}}"#)
+        );
+        assert_eq!(
+            tags.get(&SpanTagKey::Action).map(String::as_str),
+            Some("SEARCH")
+        );
+        assert_eq!(
+            tags.get(&SpanTagKey::Domain).map(String::as_str),
+            Some("products")
+        );
+    }
+
+    #[test]
+    fn grpc() {
+        let json = r#"{
+            "description": "/helloworld.Greeter/SayHello",
+            "op": "grpc.client",
+            "data": {
+                "rpc.system": "grpc",
+                "rpc.grpc.status_code": 5
+            }
+        }"#;
+
+        let span = Annotated::<Span>::from_json(json)
+            .unwrap()
+            .into_value()
+            .unwrap();
+        let tags = extract_tags(
+            &span,
+            200,
+            None,
+            None,
+            false,
+            None,
+            &Default::default(),
+            Utc::now(),
+        );
+
+        assert_eq!(
+            tags.get(&SpanTagKey::Description).map(String::as_str),
+            Some("helloworld.Greeter/SayHello")
+        );
+        assert_eq!(
+            tags.get(&SpanTagKey::Category).map(String::as_str),
+            Some("grpc")
+        );
+        assert_eq!(
+            tags.get(&SpanTagKey::Action).map(String::as_str),
+            Some("SayHello")
+        );
+        assert_eq!(
+            tags.get(&SpanTagKey::Domain).map(String::as_str),
+            Some("helloworld.Greeter")
+        );
+        assert_eq!(
+            tags.get(&SpanTagKey::System).map(String::as_str),
+            Some("grpc")
+        );
+        assert_eq!(
+            tags.get(&SpanTagKey::SpanStatus).map(String::as_str),
+            Some("not_found")
+        );
+        assert_eq!(
+            tags.get(&SpanTagKey::StatusCode).map(String::as_str),
+            Some("5")
+        );
+    }
+
+    #[test]
+    fn rpc_status_from_span() {
+        let json = r#"{
+            "description": "SayHello",
+            "op": "rpc.client",
+            "status": "ok",
+            "data": {
+                "rpc.service": "helloworld.Greeter",
+                "rpc.method": "SayHello",
+                "rpc.grpc.status_code": "14"
+            }
+        }"#;
+
+        let span = Annotated::<Span>::from_json(json)
+            .unwrap()
+            .into_value()
+            .unwrap();
+        let tags = extract_tags(
+            &span,
+            200,
+            None,
+            None,
+            false,
+            None,
+            &Default::default(),
+            Utc::now(),
+        );
+
+        assert_eq!(
+            tags.get(&SpanTagKey::Description).map(String::as_str),
+            Some("helloworld.Greeter/SayHello")
+        );
+        // The span status takes precedence over the gRPC status code.
+        assert_eq!(
+            tags.get(&SpanTagKey::SpanStatus).map(String::as_str),
+            Some("ok")
+        );
+        assert_eq!(
+            tags.get(&SpanTagKey::StatusCode).map(String::as_str),
+            Some("14")
+        );
+    }
+
     #[test]
     fn extract_user_into_sentry_tags() {
         let json = r#"
@@ -2430,4 +2805,85 @@ LIMIT 1
             "admin@sentry.io"
         );
     }
+
+    #[test]
+    fn span_grouping_rules() {
+        let rules: SpanGroupingRules = serde_json::from_str(
+            r#"[
+                {
+                    "pattern": "/orgs/[^/]+/projects/[^/]+$",
+                    "substitution": "/orgs/{id}/projects/{id}",
+                    "scope": {"op": "http.*"}
+                },
+                {
+                    "pattern": "^job-(?P<queue>[a-z]+)-\\w+$",
+                    "substitution": "job-${queue}-*"
+                }
+            ]"#,
+        )
+        .unwrap();
+
+        let span = Annotated::<Span>::from_json(
+            r#"{
+                "op": "http.client",
+                "description": "GET http://example.com/api/orgs/acme/projects/backend"
+            }"#,
+        )
+        .unwrap()
+        .into_value()
+        .unwrap();
+
+        let tags = extract_tags(&span, 200, None, None, false, None, &rules, Utc::now());
+        assert_eq!(
+            tags.get(&SpanTagKey::Description).map(String::as_str),
+            Some("GET http://example.com/api/orgs/{id}/projects/{id}")
+        );
+        assert_eq!(
+            tags.get(&SpanTagKey::Domain).map(String::as_str),
+            Some("example.com")
+        );
+
+        // Rules match the path of HTTP spans, but never the query string.
+        let span = Annotated::<Span>::from_json(
+            r#"{
+                "op": "http.client",
+                "description": "GET http://example.com/api/orgs/acme/projects/backend?token=secret"
+            }"#,
+        )
+        .unwrap()
+        .into_value()
+        .unwrap();
+
+        let tags = extract_tags(&span, 200, None, None, false, None, &rules, Utc::now());
+        assert_eq!(
+            tags.get(&SpanTagKey::Description).map(String::as_str),
+            Some("GET http://example.com/api/orgs/{id}/projects/{id}")
+        );
+
+        // Without a matching rule, the built-in scrubbing applies.
+        let span = Annotated::<Span>::from_json(
+            r#"{"op": "http.client", "description": "GET http://example.com/api/users/123"}"#,
+        )
+        .unwrap()
+        .into_value()
+        .unwrap();
+
+        let tags = extract_tags(&span, 200, None, None, false, None, &rules, Utc::now());
+        assert_eq!(
+            tags.get(&SpanTagKey::Description).map(String::as_str),
+            Some("GET http://example.com")
+        );
+
+        // Rules never apply to raw descriptions that are not scrubbed.
+        let span = Annotated::<Span>::from_json(
+            r#"{"op": "custom.job", "description": "job-emails-4f2a9c"}"#,
+        )
+        .unwrap()
+        .into_value()
+        .unwrap();
+
+        let tags = extract_tags(&span, 200, None, None, false, None, &rules, Utc::now());
+        assert_eq!(tags.get(&SpanTagKey::Description), None);
+        assert!(!tags.contains_key(&SpanTagKey::Group));
+    }
 }
@@ -5,6 +5,8 @@ use relay_event_schema::protocol::{Frame, RawStacktrace};
 use relay_protocol::{Annotated, Empty, Meta};
 use url::Url;
 
+use crate::StacktraceRules;
+
 fn is_url(filename: &str) -> bool {
     filename.starts_with("file:")
         || filename.starts_with("http:")
@@ -12,15 +14,54 @@ fn is_url(filename: &str) -> bool {
         || filename.starts_with("applewebdata:")
 }
 
-pub fn normalize_stacktrace(stacktrace: &mut RawStacktrace, _meta: &mut Meta) {
+/// Normalizes the frames of a stack trace and applies the project's stack trace rules to them.
+///
+/// `platform` is the platform of the event, which the rules fall back to for frames that do not
+/// declare their own platform. If `pac_mask` is set, pointer authentication codes are stripped
+/// from instruction addresses, see [`crate::pointer_authentication_mask`].
+pub fn normalize_stacktrace(
+    stacktrace: &mut RawStacktrace,
+    _meta: &mut Meta,
+    rules: Option<&StacktraceRules>,
+    platform: Option<&str>,
+    pac_mask: Option<u64>,
+) {
     // This processing is only done for non raw frames (i.e. not for exception.raw_stacktrace).
     if let Some(frames) = stacktrace.frames.value_mut() {
         for frame in frames.iter_mut() {
             normalize_non_raw_frame(frame);
+
+            if let Some(pac_mask) = pac_mask {
+                strip_frame_pointer_authentication_code(frame, pac_mask);
+            }
+        }
+
+        if let Some(rules) = rules {
+            rules.apply(frames, platform);
         }
     }
 }
 
+/// Strips the pointer authentication code from the absolute instruction address of a frame.
+fn strip_frame_pointer_authentication_code(frame: &mut Annotated<Frame>, pac_mask: u64) {
+    let Some(frame) = frame.value_mut() else {
+        return;
+    };
+
+    // Relative addresses are offsets into an image and never carry authentication codes.
+    if frame
+        .addr_mode
+        .as_str()
+        .is_some_and(|addr_mode| addr_mode != "abs")
+    {
+        return;
+    }
+
+    if let Some(addr) = frame.instruction_addr.value_mut() {
+        addr.0 = crate::strip_pointer_authentication_code(addr.0, pac_mask);
+    }
+}
+
 pub fn normalize_non_raw_frame(frame: &mut Annotated<Frame>) {
     let _ = processor::apply(frame, |frame, _meta| {
         if frame.abs_path.value().is_empty() {
@@ -75,6 +116,7 @@ pub fn normalize_non_raw_frame(frame: &mut Annotated<Frame>) {
 
 #[cfg(test)]
 mod tests {
+    use relay_event_schema::protocol::Addr;
     use similar_asserts::assert_eq;
 
     use super::*;
@@ -184,4 +226,39 @@ mod tests {
         assert!(!is_url("data:,"));
         assert!(!is_url("blob:\x00"));
     }
+
+    #[test]
+    fn test_strip_pointer_authentication_code() {
+        let mut stacktrace = RawStacktrace {
+            frames: Annotated::new(vec![
+                Annotated::new(Frame {
+                    instruction_addr: Annotated::new(Addr(0x8a5c_0001_9a6e_2c4c)),
+                    ..Default::default()
+                }),
+                Annotated::new(Frame {
+                    instruction_addr: Annotated::new(Addr(0x8a5c_0001_9a6e_2c4c)),
+                    addr_mode: Annotated::new("rel:0".to_owned()),
+                    ..Default::default()
+                }),
+            ]),
+            ..Default::default()
+        };
+
+        normalize_stacktrace(
+            &mut stacktrace,
+            &mut Meta::default(),
+            None,
+            Some("cocoa"),
+            crate::pointer_authentication_mask("cocoa", Some("arm64e")),
+        );
+
+        let addrs: Vec<_> = stacktrace
+            .frames
+            .value()
+            .unwrap()
+            .iter()
+            .map(|frame| frame.value().unwrap().instruction_addr.value().unwrap().0)
+            .collect();
+        assert_eq!(addrs, [0x0001_9a6e_2c4c, 0x8a5c_0001_9a6e_2c4c]);
+    }
 }
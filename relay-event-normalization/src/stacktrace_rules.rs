@@ -0,0 +1,638 @@
+//! Project-configurable rules for stack frames.
+//!
+//! Stack trace rules mark frames as in-app or system frames and assign categories to them, so that
+//! frames are classified consistently across SDKs. Rules use the syntax of Sentry's stack trace
+//! rules, with one rule per line:
+//!
+//! ```text
+//! # Mark all frames of our own packages as in-app
+//! stack.module:myapp.* +app
+//!
+//! # Vendored dependencies are never in-app
+//! path:**/node_modules/** -app
+//!
+//! # Frames calling into the panic handler are not in-app either
+//! family:native function:std::panicking::* ^-app
+//! function:sentry::* category=telemetry
+//! ```
+//!
+//! A rule consists of one or more matchers followed by one or more actions. A frame matches a rule
+//! if it matches all matchers. Matchers have the form `key:value`, where the value is a glob
+//! pattern that can be quoted if it contains spaces. Prefixing a matcher with `!` negates it. The
+//! following keys are supported:
+//!
+//!  - `stack.module` or `module`: The module of the frame.
+//!  - `stack.function` or `function`: The function of the frame.
+//!  - `stack.abs_path` or `path`: The absolute path or file name of the frame, case-insensitive.
+//!  - `stack.package` or `package`: The package of the frame or its basename, case-insensitive.
+//!  - `family`: A comma-separated list of platform families: `native`, `javascript`, `other`, or
+//!    `all`.
+//!  - `app`: Whether the frame is in-app, either `yes` or `no`.
+//!  - `category`: The category previously assigned to the frame.
+//!
+//! The following actions are supported:
+//!
+//!  - `+app` and `-app`: Mark the frame as in-app or not in-app.
+//!  - `category=<name>`: Assign a category to the frame, stored in `data.category`.
+//!
+//! Flag actions can be prefixed with `^` to apply them to all frames above the matching frame,
+//! towards the crash, or with `v` to apply them to all frames below. Grouping actions such as
+//! `+group` and other variables such as `max-frames=<n>` are accepted, but they are ignored by Relay
+//! since grouping is done downstream.
+//!
+//! Rules are applied in order, so later rules override earlier ones. When the in-app flag of a frame
+//! changes, its original value is preserved in `data.orig_in_app`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use relay_common::glob2::Glob;
+use relay_event_schema::protocol::{Frame, FrameData};
+use relay_protocol::Annotated;
+use serde::{Deserialize, Serialize};
+
+/// Platforms that belong to the `native` family.
+const NATIVE_PLATFORMS: &[&str] = &["c", "cocoa", "native", "objc", "swift"];
+
+/// Platforms that belong to the `javascript` family.
+const JAVASCRIPT_PLATFORMS: &[&str] = &["javascript", "node"];
+
+/// An error returned when parsing [`StacktraceRules`].
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+pub enum ParseStacktraceRulesError {
+    /// A matcher has an unknown key or an invalid value.
+    #[error("line {0}: invalid matcher `{1}`")]
+    InvalidMatcher(usize, String),
+    /// An action is not supported.
+    #[error("line {0}: invalid action `{1}`")]
+    InvalidAction(usize, String),
+    /// A quoted value is not terminated.
+    #[error("line {0}: unterminated quote")]
+    UnterminatedQuote(usize),
+    /// A rule has no matchers.
+    #[error("line {0}: rule has no matchers")]
+    MissingMatchers(usize),
+    /// A rule has no actions.
+    #[error("line {0}: rule has no actions")]
+    MissingActions(usize),
+}
+
+/// The platform family of a stack frame.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Family {
+    Native,
+    JavaScript,
+    Other,
+}
+
+impl Family {
+    /// Returns the family of the frame, based on its platform or the platform of the event.
+    fn of(frame: &Frame, platform: Option<&str>) -> Self {
+        match frame.platform.as_str().or(platform) {
+            Some(platform) if NATIVE_PLATFORMS.contains(&platform) => Self::Native,
+            Some(platform) if JAVASCRIPT_PLATFORMS.contains(&platform) => Self::JavaScript,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// The condition of a [`Matcher`].
+#[derive(Clone, Debug)]
+enum MatcherKind {
+    Module(Glob),
+    Function(Glob),
+    Path(Glob),
+    Package(Glob),
+    Family(Vec<Family>),
+    App(bool),
+    Category(Glob),
+}
+
+/// Matches a single attribute of a stack frame.
+#[derive(Clone, Debug)]
+struct Matcher {
+    kind: MatcherKind,
+    negated: bool,
+}
+
+/// Returns the path with forward slashes in lowercase, for case-insensitive matching.
+fn normalize_path(path: &str) -> String {
+    path.replace('\\', "/").to_lowercase()
+}
+
+impl Matcher {
+    fn parse(token: &str) -> Option<Self> {
+        let (negated, token) = match token.strip_prefix('!') {
+            Some(token) => (true, token),
+            None => (false, token),
+        };
+
+        let (key, value) = token.split_once(':')?;
+        if value.is_empty() {
+            return None;
+        }
+
+        let kind = match key {
+            "stack.module" | "module" => MatcherKind::Module(Glob::new(value)),
+            "stack.function" | "function" => MatcherKind::Function(Glob::new(value)),
+            "stack.abs_path" | "path" => MatcherKind::Path(Glob::new(&normalize_path(value))),
+            "stack.package" | "package" => MatcherKind::Package(Glob::new(&normalize_path(value))),
+            "family" => MatcherKind::Family(
+                value
+                    .split(',')
+                    .map(|family| match family {
+                        "native" => Some(vec![Family::Native]),
+                        "javascript" => Some(vec![Family::JavaScript]),
+                        "other" => Some(vec![Family::Other]),
+                        "all" => Some(vec![Family::Native, Family::JavaScript, Family::Other]),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>()?
+                    .concat(),
+            ),
+            "app" => MatcherKind::App(match value {
+                "yes" | "true" | "1" => true,
+                "no" | "false" | "0" => false,
+                _ => return None,
+            }),
+            "category" => MatcherKind::Category(Glob::new(value)),
+            _ => return None,
+        };
+
+        Some(Self { kind, negated })
+    }
+
+    fn matches(&self, frame: &Frame, platform: Option<&str>) -> bool {
+        let matches = match &self.kind {
+            MatcherKind::Module(glob) => frame.module.as_str().is_some_and(|m| glob.is_match(m)),
+            MatcherKind::Function(glob) => {
+                frame.function.as_str().is_some_and(|f| glob.is_match(f))
+            }
+            MatcherKind::Path(glob) => [&frame.abs_path, &frame.filename]
+                .into_iter()
+                .filter_map(|path| path.value())
+                .any(|path| glob.is_match(&normalize_path(path.as_str()))),
+            MatcherKind::Package(glob) => frame.package.as_str().is_some_and(|package| {
+                let package = normalize_path(package);
+                let basename = package.rsplit('/').next().unwrap_or_default();
+                glob.is_match(&package) || glob.is_match(basename)
+            }),
+            MatcherKind::Family(families) => families.contains(&Family::of(frame, platform)),
+            MatcherKind::App(in_app) => frame.in_app.value() == Some(in_app),
+            MatcherKind::Category(glob) => category(frame).is_some_and(|c| glob.is_match(c)),
+        };
+
+        matches != self.negated
+    }
+}
+
+/// The frames an [`Action`] applies to, relative to the matching frame.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Range {
+    /// Only the matching frame.
+    Matching,
+    /// All frames above the matching frame, towards the crash.
+    Above,
+    /// All frames below the matching frame, away from the crash.
+    Below,
+}
+
+/// Modifies stack frames matching a rule.
+#[derive(Clone, Debug, PartialEq)]
+enum Action {
+    /// Sets the in-app flag of frames.
+    App(Range, bool),
+    /// Sets the category of the matching frame.
+    Category(String),
+    /// An action that is only relevant for grouping and ignored by Relay.
+    Ignored,
+}
+
+impl Action {
+    fn parse(token: &str) -> Option<Self> {
+        if let Some((name, value)) = token.split_once('=') {
+            return match name {
+                "category" if !value.is_empty() => Some(Self::Category(value.to_owned())),
+                "max-frames" | "min-frames" | "invert-stacktrace" => Some(Self::Ignored),
+                _ => None,
+            };
+        }
+
+        let (range, token) = match token.strip_prefix('^') {
+            Some(token) => (Range::Above, token),
+            None => match token.strip_prefix('v') {
+                Some(token) => (Range::Below, token),
+                None => (Range::Matching, token),
+            },
+        };
+
+        match token {
+            "+app" => Some(Self::App(range, true)),
+            "-app" => Some(Self::App(range, false)),
+            "+group" | "-group" => Some(Self::Ignored),
+            _ => None,
+        }
+    }
+
+    fn apply(&self, frames: &mut [Annotated<Frame>], index: usize) {
+        match self {
+            Self::App(range, in_app) => {
+                let targets = match range {
+                    Range::Matching => &mut frames[index..=index],
+                    Range::Above => &mut frames[index + 1..],
+                    Range::Below => &mut frames[..index],
+                };
+
+                for frame in targets.iter_mut().filter_map(Annotated::value_mut) {
+                    set_in_app(frame, *in_app);
+                }
+            }
+            Self::Category(category) => {
+                if let Some(frame) = frames[index].value_mut() {
+                    frame
+                        .data
+                        .get_or_insert_with(FrameData::default)
+                        .category
+                        .set_value(Some(category.clone()));
+                }
+            }
+            Self::Ignored => (),
+        }
+    }
+}
+
+/// Returns the category assigned to the frame.
+fn category(frame: &Frame) -> Option<&str> {
+    frame.data.value()?.category.as_str()
+}
+
+/// Sets the in-app flag of the frame and preserves its original value.
+fn set_in_app(frame: &mut Frame, in_app: bool) {
+    let current = frame.in_app.value().copied();
+    if current == Some(in_app) {
+        return;
+    }
+
+    let data = frame.data.get_or_insert_with(FrameData::default);
+    if data.orig_in_app.value().is_none() {
+        data.orig_in_app.set_value(Some(match current {
+            None => -1,
+            Some(false) => 0,
+            Some(true) => 1,
+        }));
+    }
+
+    frame.in_app.set_value(Some(in_app));
+}
+
+/// A single stack trace rule, consisting of matchers and actions.
+#[derive(Clone, Debug)]
+struct StacktraceRule {
+    matchers: Vec<Matcher>,
+    actions: Vec<Action>,
+}
+
+impl StacktraceRule {
+    fn matches(&self, frame: &Frame, platform: Option<&str>) -> bool {
+        self.matchers
+            .iter()
+            .all(|matcher| matcher.matches(frame, platform))
+    }
+}
+
+/// Splits a line into tokens separated by whitespace, keeping quoted values together.
+fn tokenize(line: &str, lineno: usize) -> Result<Vec<String>, ParseStacktraceRulesError> {
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut quoted = false;
+
+    for c in line.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !token.is_empty() {
+                    tokens.push(std::mem::take(&mut token));
+                }
+            }
+            c => token.push(c),
+        }
+    }
+
+    if quoted {
+        return Err(ParseStacktraceRulesError::UnterminatedQuote(lineno));
+    }
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+/// A list of stack trace rules parsed from their text representation.
+///
+/// See the [module documentation](self) for the syntax.
+#[derive(Clone, Default)]
+pub struct StacktraceRules {
+    raw: String,
+    rules: Vec<StacktraceRule>,
+}
+
+impl StacktraceRules {
+    /// Returns `true` if there are no rules.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Returns the text representation of the rules.
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+
+    /// Applies the rules to the frames of a stack trace.
+    ///
+    /// `platform` is the platform of the event, which is used to determine the family of frames
+    /// that do not declare their own platform.
+    pub fn apply(&self, frames: &mut [Annotated<Frame>], platform: Option<&str>) {
+        for rule in &self.rules {
+            for index in 0..frames.len() {
+                let Some(frame) = frames[index].value() else {
+                    continue;
+                };
+
+                if rule.matches(frame, platform) {
+                    for action in &rule.actions {
+                        action.apply(frames, index);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for StacktraceRules {
+    type Err = ParseStacktraceRulesError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rules = Vec::new();
+
+        for (index, line) in s.lines().enumerate() {
+            let lineno = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut matchers = Vec::new();
+            let mut actions = Vec::new();
+
+            for token in tokenize(line, lineno)? {
+                if actions.is_empty() && token.contains(':') {
+                    let matcher = Matcher::parse(&token)
+                        .ok_or(ParseStacktraceRulesError::InvalidMatcher(lineno, token))?;
+                    matchers.push(matcher);
+                } else {
+                    let action = Action::parse(&token)
+                        .ok_or(ParseStacktraceRulesError::InvalidAction(lineno, token))?;
+                    actions.push(action);
+                }
+            }
+
+            if matchers.is_empty() {
+                return Err(ParseStacktraceRulesError::MissingMatchers(lineno));
+            }
+            if actions.is_empty() {
+                return Err(ParseStacktraceRulesError::MissingActions(lineno));
+            }
+
+            rules.push(StacktraceRule { matchers, actions });
+        }
+
+        Ok(Self {
+            raw: s.to_owned(),
+            rules,
+        })
+    }
+}
+
+impl fmt::Debug for StacktraceRules {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "StacktraceRules({:?})", self.raw)
+    }
+}
+
+impl PartialEq for StacktraceRules {
+    fn eq(&self, other: &Self) -> bool {
+        self.raw == other.raw
+    }
+}
+
+impl Serialize for StacktraceRules {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.raw)
+    }
+}
+
+impl<'de> Deserialize<'de> for StacktraceRules {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let raw = String::deserialize(deserializer)?;
+        raw.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use relay_event_schema::protocol::RawStacktrace;
+
+    use super::*;
+
+    fn get_frames(json: &str) -> Vec<Annotated<Frame>> {
+        Annotated::<RawStacktrace>::from_json(json)
+            .unwrap()
+            .into_value()
+            .unwrap()
+            .frames
+            .into_value()
+            .unwrap()
+    }
+
+    fn in_app(frames: &[Annotated<Frame>]) -> Vec<Option<bool>> {
+        frames
+            .iter()
+            .map(|frame| frame.value().unwrap().in_app.value().copied())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let rules: StacktraceRules = r#"
+            # comment
+            stack.module:myapp.* +app
+
+            !family:native path:"C:\Program Files\*" -app ^-group category=system
+        "#
+        .parse()
+        .unwrap();
+
+        assert_eq!(rules.rules.len(), 2);
+        assert_eq!(rules.rules[1].matchers.len(), 2);
+        assert!(rules.rules[1].matchers[0].negated);
+        assert_eq!(
+            rules.rules[1].actions,
+            [
+                Action::App(Range::Matching, false),
+                Action::Ignored,
+                Action::Category("system".to_owned())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let parse = |s: &str| s.parse::<StacktraceRules>().unwrap_err();
+
+        assert_eq!(
+            parse("module:foo\nlineno:1 +app"),
+            ParseStacktraceRulesError::InvalidMatcher(2, "lineno:1".to_owned())
+        );
+        assert_eq!(
+            parse("family:rust +app"),
+            ParseStacktraceRulesError::InvalidMatcher(1, "family:rust".to_owned())
+        );
+        assert_eq!(
+            parse("module:foo +app module:bar"),
+            ParseStacktraceRulesError::InvalidAction(1, "module:bar".to_owned())
+        );
+        assert_eq!(
+            parse("module:foo +ap"),
+            ParseStacktraceRulesError::InvalidAction(1, "+ap".to_owned())
+        );
+        assert_eq!(
+            parse("module:foo"),
+            ParseStacktraceRulesError::MissingActions(1)
+        );
+        assert_eq!(parse("+app"), ParseStacktraceRulesError::MissingMatchers(1));
+        assert_eq!(
+            parse(r#"path:"foo +app"#),
+            ParseStacktraceRulesError::UnterminatedQuote(1)
+        );
+    }
+
+    #[test]
+    fn test_serde_roundtrip() {
+        let json = r#""module:foo* +app""#;
+        let rules: StacktraceRules = serde_json::from_str(json).unwrap();
+        assert_eq!(rules.rules.len(), 1);
+        assert_eq!(serde_json::to_string(&rules).unwrap(), json);
+
+        assert!(serde_json::from_str::<StacktraceRules>(r#""module:foo""#).is_err());
+    }
+
+    #[test]
+    fn test_apply_in_app() {
+        let rules: StacktraceRules = "
+            module:myapp.* +app
+            path:**/node_modules/** -app
+            app:yes function:*_test -app
+        "
+        .parse()
+        .unwrap();
+
+        let mut frames = get_frames(
+            r#"{
+                "frames": [
+                    {"module": "myapp.views", "function": "index"},
+                    {"module": "myapp.tests", "function": "index_test"},
+                    {"module": "lodash", "abs_path": "/APP/NODE_MODULES/lodash/index.js", "in_app": true},
+                    {"module": "django.core", "in_app": false}
+                ]
+            }"#,
+        );
+
+        rules.apply(&mut frames, Some("python"));
+        assert_eq!(
+            in_app(&frames),
+            [Some(true), Some(false), Some(false), Some(false)]
+        );
+
+        // The original value is preserved for changed frames only.
+        let orig_in_app = |index: usize| {
+            frames[index]
+                .value()
+                .unwrap()
+                .data
+                .value()
+                .and_then(|data| data.orig_in_app.value().copied())
+        };
+        assert_eq!(orig_in_app(0), Some(-1));
+        assert_eq!(orig_in_app(1), Some(-1));
+        assert_eq!(orig_in_app(2), Some(1));
+        assert_eq!(orig_in_app(3), None);
+    }
+
+    #[test]
+    fn test_apply_range() {
+        let rules: StacktraceRules = "
+            function:panic ^-app
+            function:main v+app
+        "
+        .parse()
+        .unwrap();
+
+        let mut frames = get_frames(
+            r#"{
+                "frames": [
+                    {"function": "start"},
+                    {"function": "main"},
+                    {"function": "panic", "in_app": true},
+                    {"function": "unwind", "in_app": true},
+                    {"function": "abort", "in_app": true}
+                ]
+            }"#,
+        );
+
+        rules.apply(&mut frames, None);
+        assert_eq!(
+            in_app(&frames),
+            [Some(true), None, Some(true), Some(false), Some(false)]
+        );
+    }
+
+    #[test]
+    fn test_apply_family_and_category() {
+        let rules: StacktraceRules = "
+            family:native package:libsystem* -app category=system
+            family:javascript,other category=runtime
+            category:system !function:malloc +app
+        "
+        .parse()
+        .unwrap();
+
+        let mut frames = get_frames(
+            r#"{
+                "frames": [
+                    {"function": "malloc", "package": "/usr/lib/system/libsystem_malloc.dylib"},
+                    {"function": "pthread_start", "package": "/usr/lib/system/libsystem_pthread.dylib"},
+                    {"function": "render", "platform": "javascript"}
+                ]
+            }"#,
+        );
+
+        rules.apply(&mut frames, Some("cocoa"));
+        assert_eq!(in_app(&frames), [Some(false), Some(true), None]);
+
+        let categories = frames
+            .iter()
+            .map(|frame| category(frame.value().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            categories,
+            [Some("system"), Some("system"), Some("runtime")]
+        );
+    }
+}
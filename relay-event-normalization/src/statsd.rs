@@ -1,10 +1,11 @@
-use relay_statsd::TimerMetric;
+use relay_statsd::{CounterMetric, TimerMetric};
 
 pub enum Timers {
     /// Measures how log normalization of SQL queries in span description take.
     ///
     /// This metric is tagged with:
     ///  - `mode`: The method used for normalization (either `parser` or `regex`).
+    ///  - `dialect`: The SQL dialect derived from `db.system`, for example `postgresql`.
     SpanDescriptionNormalizeSQL,
 }
 
@@ -15,3 +16,26 @@ impl TimerMetric for Timers {
         }
     }
 }
+
+pub enum Counters {
+    /// Incremented every time a user-defined span grouping rule is applied.
+    ///
+    /// This metric is tagged with:
+    ///  - `target`: Either `span_description` or `transaction_name`.
+    SpanGroupingRuleApplied,
+    /// Incremented every time an SQL query in a span description is parsed.
+    ///
+    /// This metric is tagged with:
+    ///  - `dialect`: The SQL dialect derived from `db.system`, for example `postgresql`.
+    ///  - `success`: Whether the query could be parsed.
+    SqlParsed,
+}
+
+impl CounterMetric for Counters {
+    fn name(&self) -> &'static str {
+        match *self {
+            Self::SpanGroupingRuleApplied => "normalize.span_grouping_rule.applied",
+            Self::SqlParsed => "normalize.span.description.sql.parsed",
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use std::borrow::Cow;
 
+use chrono::Utc;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use relay_base_schema::events::EventType;
@@ -9,14 +10,22 @@ use relay_event_schema::processor::{
 use relay_event_schema::protocol::{Event, Span, SpanStatus, TraceContext, TransactionSource};
 use relay_protocol::{Annotated, Meta, Remark, RemarkType};
 
+use crate::graphql;
 use crate::regexes::TRANSACTION_NAME_NORMALIZER_REGEX;
-use crate::TransactionNameRule;
+use crate::{
+    apply_span_grouping_rules, SpanGroupingRules, SpanGroupingTarget, TransactionNameRule,
+};
 
 /// Configuration for sanitizing unparameterized transaction names.
 #[derive(Clone, Debug, Default)]
 pub struct TransactionNameConfig<'r> {
     /// Rules for identifier replacement that were discovered by Sentry's transaction clusterer.
     pub rules: &'r [TransactionNameRule],
+    /// User-defined rules for grouping transaction names, configured in the project.
+    pub grouping_rules: &'r SpanGroupingRules,
+    /// Appends the executed GraphQL operation to the names of transactions serving GraphQL
+    /// requests.
+    pub graphql_operations: bool,
 }
 
 /// Apply parametrization to transaction.
@@ -72,6 +81,38 @@ pub fn apply_transaction_rename_rules(
     });
 }
 
+/// Applies the first matching user-defined span grouping rule to the transaction name.
+///
+/// Only rules targeting transaction names are applied. `op` is the operation of the trace context,
+/// which is matched against the scope of the rules.
+pub fn apply_transaction_grouping_rules(
+    transaction: &mut Annotated<String>,
+    rules: &SpanGroupingRules,
+    op: Option<&str>,
+) {
+    let _ = processor::apply(transaction, |transaction, meta| {
+        let Some((rule, result)) = apply_span_grouping_rules(
+            rules,
+            SpanGroupingTarget::TransactionName,
+            op,
+            transaction,
+            Utc::now(),
+        ) else {
+            return Ok(());
+        };
+
+        if *transaction != result {
+            if meta.original_value().is_none() {
+                meta.set_original_value(Some(transaction.clone()));
+            }
+            meta.add_remark(Remark::new(RemarkType::Substituted, rule.pattern.as_str()));
+            *transaction = result;
+        }
+
+        Ok(())
+    });
+}
+
 /// Rejects transactions based on required fields.
 #[derive(Debug, Default)]
 pub struct TransactionsProcessor<'r> {
@@ -122,6 +163,18 @@ impl<'r> TransactionsProcessor<'r> {
                 .source
                 .set_value(Some(TransactionSource::Sanitized));
         }
+
+        if !self.name_config.grouping_rules.is_empty() {
+            let op = event
+                .context::<TraceContext>()
+                .and_then(|context| context.op.value().cloned());
+
+            apply_transaction_grouping_rules(
+                &mut event.transaction,
+                self.name_config.grouping_rules,
+                op.as_deref(),
+            );
+        }
     }
 }
 
@@ -149,6 +202,9 @@ impl Processor for TransactionsProcessor<'_> {
 
         set_default_transaction_source(event);
         self.normalize_transaction_name(event);
+        if self.name_config.graphql_operations {
+            normalize_graphql_transaction_name(event);
+        }
         if let Some(trace_context) = event.context_mut::<TraceContext>() {
             trace_context.op.get_or_insert_with(|| "default".to_owned());
         }
@@ -171,6 +227,61 @@ impl Processor for TransactionsProcessor<'_> {
     }
 }
 
+/// The rule id of the remark added to transaction names that contain a GraphQL operation.
+const GRAPHQL_OPERATION_REMARK: &str = "graphql.operation";
+
+/// Appends the executed GraphQL operation to the names of transactions serving GraphQL requests.
+///
+/// GraphQL APIs serve all operations from a single route, such as `POST /graphql`. The operation
+/// is parsed from the request body, and the transaction is renamed to, for example,
+/// `POST /graphql (query GetUser { user })`. Only URL and route transaction names are changed.
+///
+/// Renamed transactions are marked with a remark, so that the operation is appended only once
+/// when an event is normalized repeatedly.
+fn normalize_graphql_transaction_name(event: &mut Event) {
+    let source = event
+        .transaction_info
+        .value()
+        .and_then(|info| info.source.value());
+
+    if !matches!(
+        source,
+        None | Some(
+            &TransactionSource::Url | &TransactionSource::Route | &TransactionSource::Sanitized
+        )
+    ) {
+        return;
+    }
+
+    let Some(operation) = event
+        .request
+        .value()
+        .and_then(|request| request.data.value())
+        .and_then(graphql::parse_request_body)
+    else {
+        return;
+    };
+
+    let _ = processor::apply(&mut event.transaction, |transaction, meta| {
+        let renamed = meta
+            .iter_remarks()
+            .any(|remark| remark.rule_id() == GRAPHQL_OPERATION_REMARK);
+        if renamed {
+            return Ok(());
+        }
+
+        if meta.original_value().is_none() {
+            meta.set_original_value(Some(transaction.clone()));
+        }
+        meta.add_remark(Remark::new(
+            RemarkType::Substituted,
+            GRAPHQL_OPERATION_REMARK,
+        ));
+        *transaction = format!("{transaction} ({operation})");
+        Ok(())
+    });
+}
+
 /// Span status codes for the Ruby Rack integration that indicate raw URLs being sent as
 /// transaction names. These cases are considered as high-cardinality.
 ///
@@ -791,6 +902,127 @@ mod tests {
 ]"#);
     }
 
+    fn graphql_event(source: &str) -> Annotated<Event> {
+        let json = format!(
+            r#"
+        {{
+            "type": "transaction",
+            "transaction": "POST /graphql",
+            "transaction_info": {{
+              "source": "{source}"
+            }},
+            "timestamp": "2021-04-26T08:00:00+0100",
+            "start_timestamp": "2021-04-26T07:59:01+0100",
+            "contexts": {{
+                "trace": {{
+                    "trace_id": "4c79f60c11214eb38604f4ae0781bfb2",
+                    "span_id": "fa90fdead5f74053",
+                    "op": "http.server"
+                }}
+            }},
+            "request": {{
+                "method": "POST",
+                "data": {{
+                    "query": "query GetUser($id: ID!) {{ user(id: $id) {{ name }} }}",
+                    "operationName": "GetUser",
+                    "variables": {{"id": "42"}}
+                }}
+            }}
+        }}
+        "#
+        );
+        Annotated::<Event>::from_json(&json).unwrap()
+    }
+
+    fn graphql_processor() -> TransactionsProcessor<'static> {
+        TransactionsProcessor::new(TransactionNameConfig {
+            graphql_operations: true,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_transaction_name_graphql() {
+        let mut event = graphql_event("route");
+
+        for _ in 0..2 {
+            process_value(
+                &mut event,
+                &mut graphql_processor(),
+                ProcessingState::root(),
+            )
+            .unwrap();
+        }
+
+        assert_eq!(
+            get_value!(event.transaction!),
+            "POST /graphql (query GetUser { user })"
+        );
+        assert_eq!(
+            event
+                .value()
+                .unwrap()
+                .transaction
+                .meta()
+                .original_value()
+                .and_then(|value| value.as_str()),
+            Some("POST /graphql")
+        );
+    }
+
+    #[test]
+    fn test_transaction_name_graphql_renormalize() {
+        let mut event = graphql_event("route");
+        process_value(
+            &mut event,
+            &mut graphql_processor(),
+            ProcessingState::root(),
+        )
+        .unwrap();
+
+        // The marker survives serialization, for example when forwarding to the next Relay.
+        let mut event = Annotated::<Event>::from_json(&event.to_json().unwrap()).unwrap();
+        process_value(
+            &mut event,
+            &mut graphql_processor(),
+            ProcessingState::root(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            get_value!(event.transaction!),
+            "POST /graphql (query GetUser { user })"
+        );
+    }
+
+    #[test]
+    fn test_transaction_name_graphql_disabled() {
+        let mut event = graphql_event("route");
+
+        process_value(
+            &mut event,
+            &mut TransactionsProcessor::default(),
+            ProcessingState::root(),
+        )
+        .unwrap();
+
+        assert_eq!(get_value!(event.transaction!), "POST /graphql");
+    }
+
+    #[test]
+    fn test_transaction_name_graphql_custom() {
+        let mut event = graphql_event("custom");
+
+        process_value(
+            &mut event,
+            &mut graphql_processor(),
+            ProcessingState::root(),
+        )
+        .unwrap();
+
+        assert_eq!(get_value!(event.transaction!), "POST /graphql");
+    }
+
     /// When no identifiers are scrubbed, we should not set an original value in _meta.
     #[test]
     fn test_transaction_name_skip_original_value() {
@@ -912,6 +1144,7 @@ mod tests {
             &mut event,
             &mut TransactionsProcessor::new(TransactionNameConfig {
                 rules: &[rule1, rule2, rule3],
+                ..Default::default()
             }),
             ProcessingState::root(),
         )
@@ -993,6 +1226,7 @@ mod tests {
             &mut event,
             &mut TransactionsProcessor::new(TransactionNameConfig {
                 rules: &[rule1, rule2, rule3],
+                ..Default::default()
             }),
             ProcessingState::root(),
         )
@@ -1060,6 +1294,7 @@ mod tests {
 
         let mut processor = TransactionsProcessor::new(TransactionNameConfig {
             rules: rules.as_ref(),
+            ..Default::default()
         });
         process_value(&mut event, &mut processor, ProcessingState::root()).unwrap();
 
@@ -1175,6 +1410,7 @@ mod tests {
             &mut event,
             &mut TransactionsProcessor::new(TransactionNameConfig {
                 rules: rules.as_ref(),
+                ..Default::default()
             }),
             ProcessingState::root(),
         )
@@ -1232,6 +1468,7 @@ mod tests {
             &mut event,
             &mut TransactionsProcessor::new(TransactionNameConfig {
                 rules: rules.as_ref(),
+                ..Default::default()
             }),
             ProcessingState::root(),
         )
@@ -1315,7 +1552,10 @@ mod tests {
 
         process_value(
             &mut event,
-            &mut TransactionsProcessor::new(TransactionNameConfig { rules: &[rule] }),
+            &mut TransactionsProcessor::new(TransactionNameConfig {
+                rules: &[rule],
+                ..Default::default()
+            }),
             ProcessingState::root(),
         )
         .unwrap();
@@ -1526,6 +1766,7 @@ mod tests {
                     expiry: Utc.with_ymd_and_hms(3000, 1, 1, 1, 1, 1).unwrap(),
                     redaction: RedactionRule::default(),
                 }],
+                ..Default::default()
             }),
             ProcessingState::root(),
         )
@@ -1592,6 +1833,7 @@ mod tests {
                     expiry: Utc.with_ymd_and_hms(3000, 1, 1, 1, 1, 1).unwrap(),
                     redaction: RedactionRule::default(),
                 }],
+                ..Default::default()
             }),
             ProcessingState::root(),
         )
@@ -1626,4 +1868,63 @@ mod tests {
     },
 ]"#);
     }
+
+    #[test]
+    fn test_transaction_name_grouping_rules() {
+        let json = r#"
+        {
+            "type": "transaction",
+            "transaction": "/api/v1/orgs/acme/projects/backend",
+            "transaction_info": {
+                "source": "route"
+            },
+            "timestamp": "2021-04-26T08:00:00+0100",
+            "start_timestamp": "2021-04-26T07:59:01+0100",
+            "contexts": {
+                "trace": {
+                    "trace_id": "4c79f60c11214eb38604f4ae0781bfb2",
+                    "span_id": "fa90fdead5f74053",
+                    "op": "http.server"
+                }
+            }
+        }
+        "#;
+
+        let rules: SpanGroupingRules = serde_json::from_str(
+            r#"[
+                {
+                    "pattern": "^/api/v1/orgs/[^/]+/projects/[^/]+",
+                    "substitution": "/api/v1/orgs/{id}/projects/{id}",
+                    "scope": {"target": "transaction_name", "op": "http.*"}
+                }
+            ]"#,
+        )
+        .unwrap();
+
+        let mut event = Annotated::<Event>::from_json(json).unwrap();
+
+        process_value(
+            &mut event,
+            &mut TransactionsProcessor::new(TransactionNameConfig {
+                grouping_rules: &rules,
+                ..Default::default()
+            }),
+            ProcessingState::root(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            get_value!(event.transaction!),
+            "/api/v1/orgs/{id}/projects/{id}"
+        );
+        assert_eq!(get_value!(event.transaction_info.source!).as_str(), "route");
+
+        let remarks = get_value!(event!)
+            .transaction
+            .meta()
+            .iter_remarks()
+            .map(Remark::rule_id)
+            .collect_vec();
+        assert_eq!(remarks, ["^/api/v1/orgs/[^/]+/projects/[^/]+"]);
+    }
 }
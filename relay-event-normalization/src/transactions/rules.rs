@@ -1,10 +1,20 @@
 use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::OnceLock;
 
 use chrono::{DateTime, Utc};
+use regex::{Regex, RegexBuilder};
 use relay_common::glob2::LazyGlob;
 use relay_event_schema::protocol::OperationType;
 use serde::{Deserialize, Serialize};
 
+use crate::statsd::Counters;
+
+/// Maximum size of the compiled regex of a [`SpanGroupingRule`].
+const COMPILED_PATTERN_MAX_SIZE: usize = 262_144;
+
 /// Object containing transaction attributes the rules must only be applied to.
 ///
 /// This is part of [`SpanDescriptionRule`].
@@ -167,6 +177,277 @@ impl TransactionNameRule {
     }
 }
 
+/// Wrapper type around the raw string pattern and the compiled [`Regex`].
+///
+/// The regex is only compiled when it is used for the first time. Patterns that fail to compile
+/// never match.
+#[derive(Clone)]
+pub struct LazyRegex {
+    raw: String,
+    regex: OnceLock<Option<Regex>>,
+}
+
+impl LazyRegex {
+    /// Create a new [`LazyRegex`] from the raw string.
+    pub fn new(raw: impl Into<String>) -> Self {
+        Self {
+            raw: raw.into(),
+            regex: OnceLock::new(),
+        }
+    }
+
+    /// Returns the compiled version of the [`Regex`], or `None` if the pattern is invalid.
+    pub fn compiled(&self) -> Option<&Regex> {
+        self.regex
+            .get_or_init(|| {
+                match RegexBuilder::new(&self.raw)
+                    .size_limit(COMPILED_PATTERN_MAX_SIZE)
+                    .build()
+                {
+                    Ok(regex) => Some(regex),
+                    Err(error) => {
+                        relay_log::debug!(
+                            error = &error as &dyn std::error::Error,
+                            pattern = self.raw,
+                            "unable to compile span grouping rule pattern"
+                        );
+                        None
+                    }
+                }
+            })
+            .as_ref()
+    }
+
+    /// Returns the regex pattern as string.
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+}
+
+impl PartialEq for LazyRegex {
+    fn eq(&self, other: &Self) -> bool {
+        self.raw == other.raw
+    }
+}
+
+impl Eq for LazyRegex {}
+
+impl fmt::Debug for LazyRegex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "LazyRegex({:?})", self.raw)
+    }
+}
+
+impl Serialize for LazyRegex {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.raw)
+    }
+}
+
+impl<'de> Deserialize<'de> for LazyRegex {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        String::deserialize(deserializer).map(LazyRegex::new)
+    }
+}
+
+/// The names a [`SpanGroupingRule`] applies to.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SpanGroupingTarget {
+    /// Both span descriptions and transaction names.
+    #[default]
+    All,
+    /// Span descriptions only.
+    SpanDescription,
+    /// Transaction names only.
+    TransactionName,
+    /// Unsupported target for forward compatibility.
+    ///
+    /// Rules with an unknown target are never applied.
+    #[serde(other)]
+    Unknown,
+}
+
+impl SpanGroupingTarget {
+    /// Returns the name of the target used in metric tags.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::SpanDescription => "span_description",
+            Self::TransactionName => "transaction_name",
+            Self::Unknown => "unknown",
+        }
+    }
+
+    /// Returns `true` if a rule with this target applies to names of the given target.
+    fn includes(self, target: Self) -> bool {
+        match self {
+            Self::All => target != Self::Unknown,
+            Self::Unknown => false,
+            _ => self == target,
+        }
+    }
+}
+
+/// Object containing the attributes a [`SpanGroupingRule`] must only be applied to.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct SpanGroupingRuleScope {
+    /// Whether the rule applies to span descriptions, transaction names, or both.
+    #[serde(default)]
+    pub target: SpanGroupingTarget,
+    /// Glob pattern for the operation to match on, for example `http.*`.
+    ///
+    /// For span descriptions, this is matched against the span operation. For transaction names,
+    /// it is matched against the operation of the trace context. If missing, the rule applies to
+    /// all operations.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub op: Option<LazyGlob>,
+}
+
+/// A user-defined rule that groups span descriptions and transaction names with a regex.
+///
+/// The part of the name matched by the pattern is replaced with the substitution. Named capture
+/// groups of the pattern can be referenced in the substitution as `$name` or `${name}`, while
+/// braces without a dollar sign are inserted literally:
+///
+/// ```json
+/// {
+///   "pattern": "^/api/v1/orgs/[^/]+/projects/[^/]+(?P<rest>/.*)?$",
+///   "substitution": "/api/v1/orgs/{id}/projects/{id}${rest}",
+///   "priority": 10,
+///   "scope": {"target": "all", "op": "http.*"}
+/// }
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct SpanGroupingRule {
+    /// The regex which will be applied to the span description or transaction name.
+    pub pattern: LazyRegex,
+    /// The string to substitute the matched part of the name with.
+    pub substitution: String,
+    /// Rules with a higher priority are tried first.
+    ///
+    /// Rules with the same priority are tried in the order they are configured. Defaults to `0`.
+    #[serde(default)]
+    pub priority: i32,
+    /// Object containing the attributes the rule must only be applied to.
+    #[serde(default)]
+    pub scope: SpanGroupingRuleScope,
+    /// Date time when the rule expires and it should not be applied anymore.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiry: Option<DateTime<Utc>>,
+}
+
+impl SpanGroupingRule {
+    /// Returns `true` if the rule isn't expired at `now` and applies to the given target and
+    /// operation.
+    fn is_applicable(
+        &self,
+        target: SpanGroupingTarget,
+        op: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> bool {
+        if self.expiry.is_some_and(|expiry| expiry <= now) {
+            return false;
+        }
+
+        if !self.scope.target.includes(target) {
+            return false;
+        }
+
+        match self.scope.op {
+            Some(ref pattern) => op.is_some_and(|op| pattern.compiled().is_match(op)),
+            None => true,
+        }
+    }
+
+    /// Applies the rule to the given name, if it matches the pattern.
+    ///
+    /// Only the first match of the pattern is replaced.
+    pub fn match_and_apply(&self, name: &str) -> Option<String> {
+        let regex = self.pattern.compiled()?;
+        if !regex.is_match(name) {
+            return None;
+        }
+
+        Some(regex.replace(name, self.substitution.as_str()).into_owned())
+    }
+}
+
+/// A list of [`SpanGroupingRule`]s, sorted by descending priority.
+///
+/// Rules are sorted once when the list is created or deserialized. Rules with the same priority
+/// keep the order they are configured in.
+#[derive(Clone, Debug, Default, Serialize, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct SpanGroupingRules(Vec<SpanGroupingRule>);
+
+impl SpanGroupingRules {
+    /// Creates a new list of rules, sorted by priority.
+    pub fn new(mut rules: Vec<SpanGroupingRule>) -> Self {
+        rules.sort_by_key(|rule| Reverse(rule.priority));
+        Self(rules)
+    }
+
+    /// Returns `true` if there are no rules.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Default for &SpanGroupingRules {
+    fn default() -> Self {
+        static EMPTY: SpanGroupingRules = SpanGroupingRules(Vec::new());
+        &EMPTY
+    }
+}
+
+impl Deref for SpanGroupingRules {
+    type Target = [SpanGroupingRule];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'de> Deserialize<'de> for SpanGroupingRules {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Vec::deserialize(deserializer).map(Self::new)
+    }
+}
+
+/// Applies the first matching [`SpanGroupingRule`] to a span description or transaction name.
+///
+/// Rules are tried in the order of their priority. Rules that expired before `now` are skipped.
+/// Returns the applied rule along with the rewritten name, or `None` if no rule matches.
+pub fn apply_span_grouping_rules<'r>(
+    rules: &'r SpanGroupingRules,
+    target: SpanGroupingTarget,
+    op: Option<&str>,
+    name: &str,
+    now: DateTime<Utc>,
+) -> Option<(&'r SpanGroupingRule, String)> {
+    let (rule, result) = rules
+        .iter()
+        .filter(|rule| rule.is_applicable(target, op, now))
+        .find_map(|rule| rule.match_and_apply(name).map(|result| (rule, result)))?;
+
+    relay_statsd::metric!(
+        counter(Counters::SpanGroupingRuleApplied) += 1,
+        target = target.as_str(),
+    );
+
+    Some((rule, result))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -261,4 +542,160 @@ mod tests {
         // Make sure that we can  serialize into the same format we receive from the wire.
         assert_eq!(json, rule_json);
     }
+
+    #[test]
+    fn test_span_grouping_rule_format() {
+        let json = r#"
+        {
+          "pattern": "^/orgs/[^/]+",
+          "substitution": "/orgs/{id}",
+          "priority": 5,
+          "scope": {
+            "target": "span_description",
+            "op": "http.*"
+          },
+          "expiry": "2022-11-30T00:00:00.000000Z"
+        }
+        "#;
+
+        let rule: SpanGroupingRule = serde_json::from_str(json).unwrap();
+
+        let parsed_time = DateTime::parse_from_rfc3339("2022-11-30T00:00:00Z").unwrap();
+        let result = SpanGroupingRule {
+            pattern: LazyRegex::new("^/orgs/[^/]+"),
+            substitution: "/orgs/{id}".to_owned(),
+            priority: 5,
+            scope: SpanGroupingRuleScope {
+                target: SpanGroupingTarget::SpanDescription,
+                op: Some(LazyGlob::new("http.*")),
+            },
+            expiry: Some(DateTime::from_naive_utc_and_offset(
+                parsed_time.naive_utc(),
+                Utc,
+            )),
+        };
+
+        assert_eq!(rule, result);
+    }
+
+    #[test]
+    fn test_span_grouping_rule_format_roundtrip() {
+        let json = r#"{
+  "pattern": "^/orgs/[^/]+",
+  "substitution": "/orgs/{id}",
+  "priority": 0,
+  "scope": {
+    "target": "all"
+  }
+}"#;
+
+        let rule: SpanGroupingRule =
+            serde_json::from_str(r#"{"pattern": "^/orgs/[^/]+", "substitution": "/orgs/{id}"}"#)
+                .unwrap();
+        assert_eq!(json, serde_json::to_string_pretty(&rule).unwrap());
+    }
+
+    #[test]
+    fn test_span_grouping_rule_named_captures() {
+        let rule: SpanGroupingRule = serde_json::from_str(
+            r#"{
+                "pattern": "^/api/(?P<version>v\\d+)/orgs/[^/]+/projects/[^/]+",
+                "substitution": "/api/${version}/orgs/{id}/projects/{id}"
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            rule.match_and_apply("/api/v1/orgs/acme/projects/backend/issues")
+                .as_deref(),
+            Some("/api/v1/orgs/{id}/projects/{id}/issues")
+        );
+        assert_eq!(rule.match_and_apply("/api/v1/users/123"), None);
+    }
+
+    #[test]
+    fn test_span_grouping_rule_invalid_pattern() {
+        let rule = SpanGroupingRule {
+            pattern: LazyRegex::new("/orgs/(unclosed"),
+            substitution: "/orgs/{id}".to_owned(),
+            priority: 0,
+            scope: Default::default(),
+            expiry: None,
+        };
+
+        assert_eq!(rule.match_and_apply("/orgs/(unclosed"), None);
+    }
+
+    #[test]
+    fn test_apply_span_grouping_rules_priority() {
+        let rules: SpanGroupingRules = serde_json::from_str(
+            r#"[
+                {"pattern": "^/orgs/[^/]+", "substitution": "/orgs/{org}"},
+                {"pattern": "^/orgs/[^/]+", "substitution": "/orgs/{first}", "priority": 1},
+                {"pattern": "^/orgs/[^/]+", "substitution": "/orgs/{second}", "priority": 1}
+            ]"#,
+        )
+        .unwrap();
+
+        let (rule, result) = apply_span_grouping_rules(
+            &rules,
+            SpanGroupingTarget::SpanDescription,
+            None,
+            "/orgs/acme/",
+            Utc::now(),
+        )
+        .unwrap();
+
+        assert_eq!(rule, &rules[0]);
+        assert_eq!(rule.substitution, "/orgs/{first}");
+        assert_eq!(result, "/orgs/{first}/");
+    }
+
+    #[test]
+    fn test_apply_span_grouping_rules_scope() {
+        let rules: SpanGroupingRules = serde_json::from_str(
+            r#"[
+                {
+                    "pattern": "^/orgs/[^/]+",
+                    "substitution": "/orgs/{span}",
+                    "priority": 1,
+                    "scope": {"target": "span_description", "op": "http.*"}
+                },
+                {
+                    "pattern": "^/orgs/[^/]+",
+                    "substitution": "/orgs/{transaction}",
+                    "scope": {"target": "transaction_name"}
+                },
+                {
+                    "pattern": "^/orgs/[^/]+",
+                    "substitution": "/orgs/{future}",
+                    "priority": 2,
+                    "scope": {"target": "something_else"}
+                },
+                {
+                    "pattern": "^/orgs/[^/]+",
+                    "substitution": "/orgs/{expired}",
+                    "priority": 2,
+                    "expiry": "2022-11-30T00:00:00Z"
+                }
+            ]"#,
+        )
+        .unwrap();
+
+        let apply = |target, op| {
+            apply_span_grouping_rules(&rules, target, op, "/orgs/acme", Utc::now())
+                .map(|(_, name)| name)
+        };
+
+        assert_eq!(
+            apply(SpanGroupingTarget::SpanDescription, Some("http.client")).as_deref(),
+            Some("/orgs/{span}")
+        );
+        assert_eq!(apply(SpanGroupingTarget::SpanDescription, Some("db")), None);
+        assert_eq!(apply(SpanGroupingTarget::SpanDescription, None), None);
+        assert_eq!(
+            apply(SpanGroupingTarget::TransactionName, Some("http.server")).as_deref(),
+            Some("/orgs/{transaction}")
+        );
+    }
 }
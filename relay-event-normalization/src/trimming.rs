@@ -424,8 +424,8 @@ mod tests {
 
     use chrono::DateTime;
     use relay_event_schema::protocol::{
-        Breadcrumb, Context, Contexts, Event, Exception, ExtraValue, Span, SpanId, TagEntry, Tags,
-        Timestamp, TraceId, Values,
+        Breadcrumb, Context, Contexts, Event, Exception, ExtraValue, FlagsContext,
+        FlagsContextItem, Span, SpanId, TagEntry, Tags, Timestamp, TraceId, Values,
     };
     use relay_protocol::{get_value, Map, Remark, SerializableAnnotated};
     use similar_asserts::assert_eq;
@@ -727,6 +727,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_flags_context_trimming() {
+        let mut contexts = Contexts::new();
+        contexts.add(FlagsContext {
+            values: Annotated::new(vec![Annotated::new(FlagsContextItem {
+                flag: Annotated::new("x".repeat(300)),
+                result: Annotated::new(Value::String("a".repeat(1000))),
+                ..Default::default()
+            })]),
+            ..Default::default()
+        });
+
+        let mut contexts = Annotated::new(contexts);
+        let mut processor = TrimmingProcessor::new();
+        processor::process_value(&mut contexts, &mut processor, ProcessingState::root()).unwrap();
+
+        let flags = contexts.value().unwrap().get::<FlagsContext>().unwrap();
+        let item = flags.values.value().unwrap()[0].value().unwrap();
+
+        let mut expected = Annotated::new("x".repeat(300));
+        processor::apply(&mut expected, |v, m| {
+            trim_string(v, m, 256, 20);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(item.flag, expected);
+
+        let result = item.result.as_str().unwrap();
+        assert!(result.len() < 512);
+    }
+
     #[test]
     fn test_extra_trimming_long_arrays() {
         let mut extra = Object::new();
@@ -0,0 +1,136 @@
+#[cfg(feature = "jsonschema")]
+use relay_jsonschema_derive::JsonSchema;
+use relay_protocol::{Annotated, Empty, FromValue, IntoValue, Object, Value};
+
+use crate::processor::ProcessValue;
+
+/// Container Context.
+///
+/// This context describes the container the event originated from. The `container.*` resource
+/// attributes of the OpenTelemetry semantic conventions are accepted as aliases of the fields.
+///
+/// Example:
+///
+/// ```json
+/// "container": {
+///     "id": "a3bf90e006b2",
+///     "name": "checkout",
+///     "image_name": "gcr.io/example/checkout",
+///     "image_tag": "1.4.2",
+///     "runtime": "containerd"
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Empty, FromValue, IntoValue, ProcessValue)]
+#[cfg_attr(feature = "jsonschema", derive(JsonSchema))]
+pub struct ContainerContext {
+    /// The container ID.
+    #[metastructure(pii = "maybe", legacy_alias = "container.id")]
+    pub id: Annotated<String>,
+
+    /// The name of the container.
+    #[metastructure(pii = "maybe", legacy_alias = "container.name")]
+    pub name: Annotated<String>,
+
+    /// The name of the image the container was created from.
+    #[metastructure(legacy_alias = "container.image.name")]
+    pub image_name: Annotated<String>,
+
+    /// The tag of the container image.
+    #[metastructure(legacy_alias = "container.image.tag")]
+    pub image_tag: Annotated<String>,
+
+    /// The runtime managing the container, such as `docker` or `containerd`.
+    #[metastructure(legacy_alias = "container.runtime")]
+    pub runtime: Annotated<String>,
+
+    /// Additional arbitrary fields for forwards compatibility.
+    #[metastructure(additional_properties, retain = "true", pii = "maybe")]
+    pub other: Object<Value>,
+}
+
+impl super::DefaultContext for ContainerContext {
+    fn default_key() -> &'static str {
+        "container"
+    }
+
+    fn from_context(context: super::Context) -> Option<Self> {
+        match context {
+            super::Context::Container(c) => Some(*c),
+            _ => None,
+        }
+    }
+
+    fn cast(context: &super::Context) -> Option<&Self> {
+        match context {
+            super::Context::Container(c) => Some(c),
+            _ => None,
+        }
+    }
+
+    fn cast_mut(context: &mut super::Context) -> Option<&mut Self> {
+        match context {
+            super::Context::Container(c) => Some(c),
+            _ => None,
+        }
+    }
+
+    fn into_context(self) -> super::Context {
+        super::Context::Container(Box::new(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Context;
+
+    #[test]
+    fn test_container_context_roundtrip() {
+        let json = r#"{
+  "id": "a3bf90e006b2",
+  "name": "checkout",
+  "image_name": "gcr.io/example/checkout",
+  "image_tag": "1.4.2",
+  "runtime": "containerd",
+  "other": "value",
+  "type": "container"
+}"#;
+        let context = Annotated::new(Context::Container(Box::new(ContainerContext {
+            id: Annotated::new("a3bf90e006b2".into()),
+            name: Annotated::new("checkout".into()),
+            image_name: Annotated::new("gcr.io/example/checkout".into()),
+            image_tag: Annotated::new("1.4.2".into()),
+            runtime: Annotated::new("containerd".into()),
+            other: {
+                let mut map = Object::new();
+                map.insert(
+                    "other".to_string(),
+                    Annotated::new(Value::String("value".to_string())),
+                );
+                map
+            },
+        })));
+
+        assert_eq!(context, Annotated::from_json(json).unwrap());
+        assert_eq!(json, context.to_json_pretty().unwrap());
+    }
+
+    #[test]
+    fn test_container_context_otel_aliases() {
+        let json = r#"{
+  "container.id": "a3bf90e006b2",
+  "container.image.name": "gcr.io/example/checkout",
+  "type": "container"
+}"#;
+        let context = Annotated::<Context>::from_json(json).unwrap();
+        let Some(Context::Container(container)) = context.value() else {
+            panic!("expected a container context");
+        };
+
+        assert_eq!(container.id.as_str(), Some("a3bf90e006b2"));
+        assert_eq!(
+            container.image_name.as_str(),
+            Some("gcr.io/example/checkout")
+        );
+    }
+}
@@ -0,0 +1,151 @@
+#[cfg(feature = "jsonschema")]
+use relay_jsonschema_derive::JsonSchema;
+use relay_protocol::{Annotated, Array, Empty, FromValue, IntoValue, Object, Value};
+
+use crate::processor::ProcessValue;
+
+/// Feature flag context.
+///
+/// Contains the feature flags that were evaluated before the event occurred, in the order of their
+/// evaluation. SDKs record the most recent evaluations of a bounded number of flags.
+///
+/// ```json
+/// {
+///   "contexts": {
+///     "flags": {
+///       "values": [
+///         {"flag": "new-checkout", "result": true},
+///         {"flag": "theme", "result": "dark"}
+///       ]
+///     }
+///   }
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Empty, FromValue, IntoValue, ProcessValue)]
+#[cfg_attr(feature = "jsonschema", derive(JsonSchema))]
+pub struct FlagsContext {
+    /// The evaluated feature flags, ordered from the oldest to the most recent evaluation.
+    pub values: Annotated<Array<FlagsContextItem>>,
+
+    /// Additional arbitrary fields for forwards compatibility.
+    #[metastructure(additional_properties, retain = "true")]
+    pub other: Object<Value>,
+}
+
+impl FlagsContext {
+    /// Returns the result of the most recent evaluation of the given flag.
+    pub fn get(&self, flag: &str) -> Option<&Value> {
+        self.values
+            .value()?
+            .iter()
+            .rev()
+            .filter_map(Annotated::value)
+            .find(|item| item.flag.as_str() == Some(flag))?
+            .result
+            .value()
+    }
+}
+
+/// A single evaluation of a feature flag.
+#[derive(Clone, Debug, Default, PartialEq, Empty, FromValue, IntoValue, ProcessValue)]
+#[cfg_attr(feature = "jsonschema", derive(JsonSchema))]
+pub struct FlagsContextItem {
+    /// The name of the feature flag.
+    #[metastructure(required = "true", max_chars = 256, max_chars_allowance = 20)]
+    pub flag: Annotated<String>,
+
+    /// The evaluated value of the flag, usually a boolean or a string.
+    #[metastructure(max_depth = 2, max_bytes = 512)]
+    pub result: Annotated<Value>,
+
+    /// Additional arbitrary fields for forwards compatibility.
+    #[metastructure(additional_properties, retain = "true")]
+    pub other: Object<Value>,
+}
+
+impl super::DefaultContext for FlagsContext {
+    fn default_key() -> &'static str {
+        "flags"
+    }
+
+    fn from_context(context: super::Context) -> Option<Self> {
+        match context {
+            super::Context::Flags(c) => Some(*c),
+            _ => None,
+        }
+    }
+
+    fn cast(context: &super::Context) -> Option<&Self> {
+        match context {
+            super::Context::Flags(c) => Some(c),
+            _ => None,
+        }
+    }
+
+    fn cast_mut(context: &mut super::Context) -> Option<&mut Self> {
+        match context {
+            super::Context::Flags(c) => Some(c),
+            _ => None,
+        }
+    }
+
+    fn into_context(self) -> super::Context {
+        super::Context::Flags(Box::new(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Context;
+
+    #[test]
+    fn test_flags_context_roundtrip() {
+        let json = r#"{
+  "values": [
+    {
+      "flag": "new-checkout",
+      "result": true
+    },
+    {
+      "flag": "theme",
+      "result": "dark"
+    }
+  ],
+  "type": "flags"
+}"#;
+
+        let context = Annotated::new(Context::Flags(Box::new(FlagsContext {
+            values: Annotated::new(vec![
+                Annotated::new(FlagsContextItem {
+                    flag: Annotated::new("new-checkout".to_owned()),
+                    result: Annotated::new(Value::Bool(true)),
+                    ..Default::default()
+                }),
+                Annotated::new(FlagsContextItem {
+                    flag: Annotated::new("theme".to_owned()),
+                    result: Annotated::new(Value::String("dark".to_owned())),
+                    ..Default::default()
+                }),
+            ]),
+            ..Default::default()
+        })));
+
+        assert_eq!(context, Annotated::from_json(json).unwrap());
+        assert_eq!(json, context.to_json_pretty().unwrap());
+    }
+
+    #[test]
+    fn test_flags_context_get() {
+        let context = Annotated::<FlagsContext>::from_json(
+            r#"{"values": [{"flag": "a", "result": false}, {"flag": "b", "result": 1}, {"flag": "a", "result": true}]}"#,
+        )
+        .unwrap()
+        .into_value()
+        .unwrap();
+
+        assert_eq!(context.get("a"), Some(&Value::Bool(true)));
+        assert_eq!(context.get("b"), Some(&Value::I64(1)));
+        assert_eq!(context.get("c"), None);
+    }
+}
@@ -0,0 +1,147 @@
+#[cfg(feature = "jsonschema")]
+use relay_jsonschema_derive::JsonSchema;
+use relay_protocol::{Annotated, Empty, FromValue, IntoValue, Object, Value};
+
+use crate::processor::ProcessValue;
+
+/// Kubernetes Context.
+///
+/// This context describes the Kubernetes workload the event originated from. The `k8s.*` resource
+/// attributes of the OpenTelemetry semantic conventions are accepted as aliases of the fields.
+///
+/// Example:
+///
+/// ```json
+/// "kubernetes": {
+///     "cluster_name": "production",
+///     "namespace": "checkout",
+///     "node_name": "gke-production-pool-1-2b3c",
+///     "pod_name": "checkout-7d9f8b6c5-x2k4z",
+///     "pod_uid": "275ecb36-5aa8-4c2a-9c47-d8bb681b9aff",
+///     "deployment_name": "checkout"
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Empty, FromValue, IntoValue, ProcessValue)]
+#[cfg_attr(feature = "jsonschema", derive(JsonSchema))]
+pub struct KubernetesContext {
+    /// The name of the cluster.
+    #[metastructure(legacy_alias = "k8s.cluster.name")]
+    pub cluster_name: Annotated<String>,
+
+    /// The namespace the pod runs in.
+    #[metastructure(legacy_alias = "k8s.namespace.name")]
+    pub namespace: Annotated<String>,
+
+    /// The name of the node the pod is scheduled on.
+    #[metastructure(pii = "maybe", legacy_alias = "k8s.node.name")]
+    pub node_name: Annotated<String>,
+
+    /// The name of the pod.
+    #[metastructure(pii = "maybe", legacy_alias = "k8s.pod.name")]
+    pub pod_name: Annotated<String>,
+
+    /// The unique identifier of the pod.
+    #[metastructure(pii = "maybe", legacy_alias = "k8s.pod.uid")]
+    pub pod_uid: Annotated<String>,
+
+    /// The name of the deployment that manages the pod.
+    #[metastructure(legacy_alias = "k8s.deployment.name")]
+    pub deployment_name: Annotated<String>,
+
+    /// Additional arbitrary fields for forwards compatibility.
+    #[metastructure(additional_properties, retain = "true", pii = "maybe")]
+    pub other: Object<Value>,
+}
+
+impl super::DefaultContext for KubernetesContext {
+    fn default_key() -> &'static str {
+        "kubernetes"
+    }
+
+    fn from_context(context: super::Context) -> Option<Self> {
+        match context {
+            super::Context::Kubernetes(c) => Some(*c),
+            _ => None,
+        }
+    }
+
+    fn cast(context: &super::Context) -> Option<&Self> {
+        match context {
+            super::Context::Kubernetes(c) => Some(c),
+            _ => None,
+        }
+    }
+
+    fn cast_mut(context: &mut super::Context) -> Option<&mut Self> {
+        match context {
+            super::Context::Kubernetes(c) => Some(c),
+            _ => None,
+        }
+    }
+
+    fn into_context(self) -> super::Context {
+        super::Context::Kubernetes(Box::new(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Context;
+
+    #[test]
+    fn test_kubernetes_context_roundtrip() {
+        let json = r#"{
+  "cluster_name": "production",
+  "namespace": "checkout",
+  "node_name": "gke-production-pool-1-2b3c",
+  "pod_name": "checkout-7d9f8b6c5-x2k4z",
+  "pod_uid": "275ecb36-5aa8-4c2a-9c47-d8bb681b9aff",
+  "deployment_name": "checkout",
+  "other": "value",
+  "type": "kubernetes"
+}"#;
+        let context = Annotated::new(Context::Kubernetes(Box::new(KubernetesContext {
+            cluster_name: Annotated::new("production".into()),
+            namespace: Annotated::new("checkout".into()),
+            node_name: Annotated::new("gke-production-pool-1-2b3c".into()),
+            pod_name: Annotated::new("checkout-7d9f8b6c5-x2k4z".into()),
+            pod_uid: Annotated::new("275ecb36-5aa8-4c2a-9c47-d8bb681b9aff".into()),
+            deployment_name: Annotated::new("checkout".into()),
+            other: {
+                let mut map = Object::new();
+                map.insert(
+                    "other".to_string(),
+                    Annotated::new(Value::String("value".to_string())),
+                );
+                map
+            },
+        })));
+
+        assert_eq!(context, Annotated::from_json(json).unwrap());
+        assert_eq!(json, context.to_json_pretty().unwrap());
+    }
+
+    #[test]
+    fn test_kubernetes_context_otel_aliases() {
+        let json = r#"{
+  "k8s.cluster.name": "production",
+  "k8s.namespace.name": "checkout",
+  "k8s.pod.name": "checkout-7d9f8b6c5-x2k4z",
+  "k8s.replicaset.name": "checkout-7d9f8b6c5",
+  "type": "kubernetes"
+}"#;
+        let context = Annotated::<Context>::from_json(json).unwrap();
+        let Some(Context::Kubernetes(kubernetes)) = context.value() else {
+            panic!("expected a kubernetes context");
+        };
+
+        assert_eq!(kubernetes.cluster_name.as_str(), Some("production"));
+        assert_eq!(kubernetes.namespace.as_str(), Some("checkout"));
+        assert_eq!(
+            kubernetes.pod_name.as_str(),
+            Some("checkout-7d9f8b6c5-x2k4z")
+        );
+        assert!(kubernetes.other.contains_key("k8s.replicaset.name"));
+    }
+}
@@ -1,8 +1,11 @@
 mod app;
 mod browser;
 mod cloud_resource;
+mod container;
 mod device;
+mod flags;
 mod gpu;
+mod kubernetes;
 mod monitor;
 mod nel;
 mod os;
@@ -18,8 +21,11 @@ mod user_report_v2;
 pub use app::*;
 pub use browser::*;
 pub use cloud_resource::*;
+pub use container::*;
 pub use device::*;
+pub use flags::*;
 pub use gpu::*;
+pub use kubernetes::*;
 pub use monitor::*;
 pub use nel::*;
 pub use os::*;
@@ -90,6 +96,12 @@ pub enum Context {
     Nel(Box<NelContext>),
     /// Performance score information.
     PerformanceScore(Box<PerformanceScoreContext>),
+    /// Feature flag evaluations.
+    Flags(Box<FlagsContext>),
+    /// Kubernetes workload information.
+    Kubernetes(Box<KubernetesContext>),
+    /// Container information.
+    Container(Box<ContainerContext>),
     /// Additional arbitrary fields for forwards compatibility.
     #[metastructure(fallback_variant)]
     Other(#[metastructure(pii = "true")] Object<Value>),
@@ -27,6 +27,13 @@ pub struct OtelContext {
     pub other: Object<Value>,
 }
 
+impl OtelContext {
+    /// Returns the attributes of the OpenTelemetry resource.
+    pub fn resource(&self) -> Option<&Object<Value>> {
+        self.resource.value()
+    }
+}
+
 impl super::DefaultContext for OtelContext {
     fn default_key() -> &'static str {
         "otel"
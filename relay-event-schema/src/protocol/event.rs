@@ -13,9 +13,9 @@ use uuid::Uuid;
 use crate::processor::ProcessValue;
 use crate::protocol::{
     AppContext, Breadcrumb, Breakdowns, BrowserContext, ClientSdkInfo, Contexts, Csp, DebugMeta,
-    DefaultContext, DeviceContext, EventType, Exception, ExpectCt, ExpectStaple, Fingerprint, Hpkp,
-    LenientString, Level, LogEntry, Measurements, Metrics, MetricsSummary, OsContext,
-    ProfileContext, RelayInfo, Request, ResponseContext, Span, SpanId, Stacktrace, Tags,
+    DefaultContext, DeviceContext, EventType, Exception, ExpectCt, ExpectStaple, Fingerprint,
+    FlagsContext, Hpkp, LenientString, Level, LogEntry, Measurements, Metrics, MetricsSummary,
+    OsContext, ProfileContext, RelayInfo, Request, ResponseContext, Span, SpanId, Stacktrace, Tags,
     TemplateInfo, Thread, Timestamp, TraceContext, TransactionInfo, User, Values,
 };
 
@@ -453,6 +453,12 @@ pub struct Event {
     #[metastructure(omit_from_schema)] // deprecated
     pub checksum: Annotated<String>,
 
+    /// Grouping hash computed by Relay from the project's fingerprinting rules.
+    ///
+    /// This value should not be ingested and will be overwritten by the store normalizer.
+    #[metastructure(omit_from_schema)] // not part of external schema
+    pub grouping_hash: Annotated<String>,
+
     /// CSP (security) reports.
     #[metastructure(legacy_alias = "sentry.interfaces.Csp")]
     #[metastructure(omit_from_schema)] // we only document error events for now
@@ -669,6 +675,15 @@ impl Getter for Event {
                 .into(),
             "user.geo.region" => self.user.value()?.geo.value()?.region.as_str()?.into(),
             "user.geo.subdivision" => self.user.value()?.geo.value()?.subdivision.as_str()?.into(),
+            "user.geo.asn" => (*self.user.value()?.geo.value()?.asn.value()?).into(),
+            "user.geo.organization" => self
+                .user
+                .value()?
+                .geo
+                .value()?
+                .organization
+                .as_str()?
+                .into(),
             "request.method" => self.request.value()?.method.as_str()?.into(),
             "request.url" => self.request.value()?.url.as_str()?.into(),
             "transaction.source" => self
@@ -788,6 +803,8 @@ impl Getter for Event {
                 } else if let Some(rest) = path.strip_prefix("breakdowns.") {
                     let (breakdown, measurement) = rest.split_once('.')?;
                     self.breakdown(breakdown, measurement)?.into()
+                } else if let Some(flag) = path.strip_prefix("contexts.flags.") {
+                    self.context::<FlagsContext>()?.get(flag)?.into()
                 } else if let Some(rest) = path.strip_prefix("extra.") {
                     self.extra_at(rest)?.into()
                 } else if let Some(rest) = path.strip_prefix("tags.") {
@@ -816,7 +833,7 @@ mod tests {
 
     use super::*;
     use crate::protocol::{
-        Headers, IpAddr, JsonLenientString, PairList, TagEntry, TransactionSource,
+        FlagsContextItem, Headers, IpAddr, JsonLenientString, PairList, TagEntry, TransactionSource,
     };
 
     #[test]
@@ -1157,6 +1174,14 @@ mod tests {
                         "abadcade-feed-dead-beef-8addadfeedaa"
                     ))),
                 });
+                contexts.add(FlagsContext {
+                    values: Annotated::new(vec![Annotated::new(FlagsContextItem {
+                        flag: Annotated::new("new-checkout".to_string()),
+                        result: Annotated::new(Value::Bool(true)),
+                        ..Default::default()
+                    })]),
+                    ..Default::default()
+                });
                 contexts
             }),
             ..Default::default()
@@ -1254,6 +1279,11 @@ mod tests {
             Some(Val::String("route")),
             event.get_value("event.transaction.source")
         );
+        assert_eq!(
+            Some(Val::Bool(true)),
+            event.get_value("event.contexts.flags.new-checkout")
+        );
+        assert_eq!(None, event.get_value("event.contexts.flags.doesntexist"));
     }
 
     #[test]
@@ -275,6 +275,15 @@ impl Getter for Replay {
                 .into(),
             "user.geo.region" => self.user.value()?.geo.value()?.region.as_str()?.into(),
             "user.geo.subdivision" => self.user.value()?.geo.value()?.subdivision.as_str()?.into(),
+            "user.geo.asn" => (*self.user.value()?.geo.value()?.asn.value()?).into(),
+            "user.geo.organization" => self
+                .user
+                .value()?
+                .geo
+                .value()?
+                .organization
+                .as_str()?
+                .into(),
             "request.method" => self.request.value()?.method.as_str()?.into(),
             "request.url" => self.request.value()?.url.as_str()?.into(),
             "sdk.name" => self.sdk.value()?.name.as_str()?.into(),
@@ -2,6 +2,7 @@ use std::fmt::{self, Display};
 use std::time::SystemTime;
 
 use chrono::{DateTime, Utc};
+use relay_protocol::{Getter, Val};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -145,6 +146,19 @@ pub struct SessionAttributes {
     pub user_agent: Option<String>,
 }
 
+impl SessionAttributes {
+    /// Returns the value of an attribute for the given path, without the `event.` prefix.
+    fn get_value(&self, path: &str) -> Option<Val<'_>> {
+        Some(match path {
+            "release" => self.release.as_str().into(),
+            "environment" => self.environment.as_deref()?.into(),
+            "user.ip_address" => self.ip_address.as_ref()?.as_str().into(),
+            "user_agent" => self.user_agent.as_deref()?.into(),
+            _ => return None,
+        })
+    }
+}
+
 fn default_sequence() -> u64 {
     SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)
@@ -341,6 +355,22 @@ pub struct SessionAggregates {
     pub attributes: SessionAttributes,
 }
 
+impl Getter for SessionUpdate {
+    fn get_value(&self, path: &str) -> Option<Val<'_>> {
+        let path = path.strip_prefix("event.")?;
+        match path {
+            "status" => Some(self.status.as_str().into()),
+            _ => self.attributes.get_value(path),
+        }
+    }
+}
+
+impl Getter for SessionAggregates {
+    fn get_value(&self, path: &str) -> Option<Val<'_>> {
+        self.attributes.get_value(path.strip_prefix("event.")?)
+    }
+}
+
 impl SessionAggregates {
     /// Parses a session batch from JSON.
     pub fn parse(payload: &[u8]) -> Result<Self, serde_json::Error> {
@@ -544,4 +574,37 @@ mod tests {
         let update = SessionUpdate::parse(json.as_bytes()).unwrap();
         assert_eq!(update.abnormal_mechanism, AbnormalMechanism::None);
     }
+
+    #[test]
+    fn test_session_getter() {
+        let json = r#"{
+  "sid": "8333339f-5675-4f89-a9a0-1c935255ab58",
+  "started": "2020-02-07T14:16:00Z",
+  "status": "exited",
+  "attrs": {
+    "release": "sentry-test@1.0.0",
+    "environment": "production",
+    "ip_address": "::1"
+  }
+}"#;
+
+        let update = SessionUpdate::parse(json.as_bytes()).unwrap();
+        assert_eq!(
+            update.get_value("event.release"),
+            Some(Val::String("sentry-test@1.0.0"))
+        );
+        assert_eq!(
+            update.get_value("event.environment"),
+            Some(Val::String("production"))
+        );
+        assert_eq!(
+            update.get_value("event.user.ip_address"),
+            Some(Val::String("::1"))
+        );
+        assert_eq!(
+            update.get_value("event.status"),
+            Some(Val::String("exited"))
+        );
+        assert_eq!(update.get_value("event.user_agent"), None);
+    }
 }
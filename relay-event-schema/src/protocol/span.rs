@@ -123,6 +123,14 @@ impl Span {
             None => self.tags.value()?.get(key)?.as_str()?.into(),
         })
     }
+
+    /// Returns the evaluated value of a feature flag on the span.
+    ///
+    /// SDKs record flag evaluations as `flag.evaluation.<name>` attributes in the `data` object.
+    fn flag(&self, name: &str) -> Option<Val<'_>> {
+        let key = format!("flag.evaluation.{name}");
+        Some(self.data.value()?.other.get(&key)?.value()?.into())
+    }
 }
 
 impl Getter for Span {
@@ -150,6 +158,8 @@ impl Getter for Span {
                         self.tags.value()?.get(key)?.as_str()?.into()
                     } else if let Some(key) = path.strip_prefix("data.") {
                         self.attribute(key)?
+                    } else if let Some(flag) = path.strip_prefix("flags.") {
+                        self.flag(flag)?
                     } else if let Some(key) = path.strip_prefix("sentry_tags.") {
                         self.sentry_tags.value()?.get(key)?.as_str()?.into()
                     } else if let Some(rest) = path.strip_prefix("measurements.") {
@@ -538,6 +548,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_getter_flags() {
+        let span = Annotated::<Span>::from_json(
+            r#"{
+                "data": {
+                    "flag.evaluation.new-checkout": true,
+                    "flag.evaluation.theme": "dark"
+                }
+            }"#,
+        )
+        .unwrap()
+        .into_value()
+        .unwrap();
+
+        assert_eq!(
+            span.get_value("span.flags.new-checkout"),
+            Some(Val::Bool(true))
+        );
+        assert_eq!(
+            span.get_value("span.flags.theme"),
+            Some(Val::String("dark"))
+        );
+        assert_eq!(span.get_value("span.flags.other"), None);
+    }
+
     #[test]
     fn test_getter_was_transaction() {
         let mut span = Span::default();
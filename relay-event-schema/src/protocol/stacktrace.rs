@@ -229,7 +229,10 @@ pub struct FrameData {
     /// - `-1`: in_app was set to `null`
     /// - `0`: in_app was set to `false`
     /// - `1`: in_app was set to `true`
-    orig_in_app: Annotated<i64>,
+    pub orig_in_app: Annotated<i64>,
+    /// The category of the frame assigned by stack trace rules, such as `telemetry`.
+    #[metastructure(max_chars = 128)]
+    pub category: Annotated<String>,
     /// Additional keys not handled by this protocol.
     #[metastructure(additional_properties)]
     pub other: Object<Value>,
@@ -26,6 +26,14 @@ pub struct Geo {
     #[metastructure(pii = "true", max_chars = 1024, max_chars_allowance = 100)]
     pub region: Annotated<String>,
 
+    /// Number of the autonomous system (ASN) of the network.
+    #[metastructure(pii = "true")]
+    pub asn: Annotated<u64>,
+
+    /// Name of the organization that operates the network.
+    #[metastructure(pii = "true", max_chars = 1024, max_chars_allowance = 100)]
+    pub organization: Annotated<String>,
+
     /// Additional arbitrary fields for forwards compatibility.
     #[metastructure(additional_properties)]
     pub other: Object<Value>,
@@ -108,6 +116,8 @@ mod tests {
   "city": "San Francisco",
   "subdivision": "California",
   "region": "CA",
+  "asn": 64496,
+  "organization": "Example Networks",
   "other": "value"
 }"#;
         let geo = Annotated::new(Geo {
@@ -115,6 +125,8 @@ mod tests {
             city: Annotated::new("San Francisco".to_string()),
             subdivision: Annotated::new("California".to_string()),
             region: Annotated::new("CA".to_string()),
+            asn: Annotated::new(64496),
+            organization: Annotated::new("Example Networks".to_string()),
             other: {
                 let mut map = Map::new();
                 map.insert(
@@ -137,6 +149,8 @@ mod tests {
             city: Annotated::empty(),
             subdivision: Annotated::empty(),
             region: Annotated::empty(),
+            asn: Annotated::empty(),
+            organization: Annotated::empty(),
             other: Object::default(),
         });
 
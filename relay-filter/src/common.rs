@@ -12,6 +12,9 @@ pub enum FilterStatKey {
     /// Filtered by ip address.
     IpAddress,
 
+    /// Filtered by the geographic location or network of the client.
+    GeoLocation,
+
     /// Filtered by release name (version).
     ReleaseVersion,
 
@@ -42,6 +45,9 @@ pub enum FilterStatKey {
     /// Filtered due to the namespace being disabled.
     DisabledNamespace,
 
+    /// Filtered because the same error was seen too often within a short time.
+    RepeatedErrors,
+
     /// Filtered due to a generic filter.
     GenericFilter(String),
 }
@@ -63,6 +69,7 @@ impl FilterStatKey {
     pub fn name(self) -> Cow<'static, str> {
         Cow::Borrowed(match self {
             FilterStatKey::IpAddress => "ip-address",
+            FilterStatKey::GeoLocation => "geo-location",
             FilterStatKey::ReleaseVersion => "release-version",
             FilterStatKey::ErrorMessage => "error-message",
             FilterStatKey::BrowserExtensions => "browser-extensions",
@@ -73,6 +80,7 @@ impl FilterStatKey {
             FilterStatKey::FilteredTransactions => "filtered-transaction",
             FilterStatKey::DeniedName => "denied-name",
             FilterStatKey::DisabledNamespace => "disabled-namespace",
+            FilterStatKey::RepeatedErrors => "repeated-errors",
             FilterStatKey::GenericFilter(filter_identifier) => {
                 return Cow::Owned(filter_identifier);
             }
@@ -92,6 +100,7 @@ impl<'a> TryFrom<&'a str> for FilterStatKey {
     fn try_from(value: &'a str) -> Result<Self, Self::Error> {
         Ok(match value {
             "ip-address" => FilterStatKey::IpAddress,
+            "geo-location" => FilterStatKey::GeoLocation,
             "release-version" => FilterStatKey::ReleaseVersion,
             "error-message" => FilterStatKey::ErrorMessage,
             "browser-extensions" => FilterStatKey::BrowserExtensions,
@@ -100,6 +109,7 @@ impl<'a> TryFrom<&'a str> for FilterStatKey {
             "web-crawlers" => FilterStatKey::WebCrawlers,
             "invalid-csp" => FilterStatKey::InvalidCsp,
             "filtered-transaction" => FilterStatKey::FilteredTransactions,
+            "repeated-errors" => FilterStatKey::RepeatedErrors,
             other => FilterStatKey::GenericFilter(other.to_string()),
         })
     }
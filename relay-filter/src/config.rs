@@ -6,8 +6,10 @@ use std::convert::Infallible;
 use std::fmt;
 use std::ops::Deref;
 use std::str::FromStr;
+use std::sync::OnceLock;
 
 use indexmap::IndexMap;
+use regex::{Regex, RegexBuilder};
 use relay_common::glob3::GlobPatterns;
 use relay_protocol::RuleCondition;
 use serde::ser::SerializeSeq;
@@ -154,6 +156,154 @@ impl ClientIpsFilterConfig {
     }
 }
 
+/// A list of regular expressions matching user agents.
+///
+/// Expressions are matched case-insensitively and compiled on first use. Invalid expressions are
+/// ignored, but still serialized to downstream Relays.
+#[derive(Clone, Default)]
+pub struct UserAgentPatterns {
+    patterns: Vec<String>,
+    regexes: OnceLock<Vec<Regex>>,
+}
+
+impl UserAgentPatterns {
+    /// Creates a new list of user agent patterns.
+    pub fn new(patterns: Vec<String>) -> Self {
+        Self {
+            patterns,
+            regexes: OnceLock::new(),
+        }
+    }
+
+    /// Returns `true` if the list of patterns is empty.
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Returns `true` if any of the patterns match the given user agent.
+    pub fn is_match(&self, user_agent: &str) -> bool {
+        let regexes = self.regexes.get_or_init(|| self.parse_regexes());
+        regexes.iter().any(|regex| regex.is_match(user_agent))
+    }
+
+    /// Parses valid patterns from the list and logs invalid ones.
+    fn parse_regexes(&self) -> Vec<Regex> {
+        self.patterns
+            .iter()
+            .filter_map(|pattern| {
+                let result = RegexBuilder::new(pattern).case_insensitive(true).build();
+                match result {
+                    Ok(regex) => Some(regex),
+                    Err(error) => {
+                        relay_log::error!(
+                            error = &error as &dyn std::error::Error,
+                            pattern = pattern,
+                            "unable to compile user agent pattern"
+                        );
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+impl fmt::Debug for UserAgentPatterns {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.patterns.fmt(f)
+    }
+}
+
+impl Serialize for UserAgentPatterns {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.patterns.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for UserAgentPatterns {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let patterns = Deserialize::deserialize(deserializer)?;
+        Ok(UserAgentPatterns::new(patterns))
+    }
+}
+
+impl PartialEq for UserAgentPatterns {
+    fn eq(&self, other: &Self) -> bool {
+        self.patterns == other.patterns
+    }
+}
+
+/// Global configuration for the web crawlers filter.
+///
+/// Extends the lists of web crawlers built into Relay, so that new crawlers can be filtered
+/// without updating Relay. Project configs only enable or disable the filter.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WebCrawlersConfig {
+    /// User agents of web crawlers in addition to the built-in ones.
+    #[serde(skip_serializing_if = "UserAgentPatterns::is_empty")]
+    pub patterns: UserAgentPatterns,
+
+    /// User agents of web crawlers that are never filtered, in addition to the built-in ones.
+    #[serde(skip_serializing_if = "UserAgentPatterns::is_empty")]
+    pub allowed: UserAgentPatterns,
+}
+
+impl WebCrawlersConfig {
+    /// Returns true if no configuration for this filter is given.
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty() && self.allowed.is_empty()
+    }
+}
+
+/// Global configuration for the legacy browsers filter.
+///
+/// User agents matching these patterns are filtered in addition to the browser versions selected
+/// in the project config. Project configs only enable or disable the filter.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LegacyBrowsersConfig {
+    /// User agents of legacy browsers in addition to the built-in ones.
+    #[serde(skip_serializing_if = "UserAgentPatterns::is_empty")]
+    pub patterns: UserAgentPatterns,
+}
+
+impl LegacyBrowsersConfig {
+    /// Returns true if no configuration for this filter is given.
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+}
+
+/// Configuration for the geo location filter.
+///
+/// Requires a GeoIP database to be configured in Relay. Without a database, this filter has no
+/// effect.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GeoFilterConfig {
+    /// Two-letter country codes (ISO 3166-1 alpha-2) of clients that will be filtered.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub country_codes: Vec<String>,
+
+    /// Autonomous system numbers of networks that will be filtered.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub asns: Vec<u32>,
+}
+
+impl GeoFilterConfig {
+    /// Returns true if no configuration for this filter is given.
+    pub fn is_empty(&self) -> bool {
+        self.country_codes.is_empty() && self.asns.is_empty()
+    }
+}
+
 /// Configuration for the CSP filter.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -215,6 +365,38 @@ impl ReleasesFilterConfig {
     }
 }
 
+/// Configuration for the repeated errors filter.
+///
+/// Drops error events with the same fingerprint once more than `limit` of them have been seen
+/// within `window` seconds.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RepeatedErrorsFilterConfig {
+    /// Specifies whether this filter is enabled.
+    pub is_enabled: bool,
+    /// The number of identical errors that are accepted per window.
+    pub limit: u64,
+    /// The size of the window in seconds.
+    pub window: u64,
+}
+
+impl RepeatedErrorsFilterConfig {
+    /// Returns true if no configuration for this filter is given.
+    pub fn is_empty(&self) -> bool {
+        !self.is_enabled || self.limit == 0 || self.window == 0
+    }
+}
+
+impl Default for RepeatedErrorsFilterConfig {
+    fn default() -> Self {
+        Self {
+            is_enabled: false,
+            limit: 100,
+            window: 60,
+        }
+    }
+}
+
 /// Configuration for the legacy browsers filter.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -513,6 +695,10 @@ pub struct ProjectFiltersConfig {
     #[serde(default, skip_serializing_if = "ClientIpsFilterConfig::is_empty")]
     pub client_ips: ClientIpsFilterConfig,
 
+    /// Configuration for the geo location filter.
+    #[serde(default, skip_serializing_if = "GeoFilterConfig::is_empty")]
+    pub geo: GeoFilterConfig,
+
     /// Configuration for the Web Crawlers filter
     #[serde(default, skip_serializing_if = "FilterConfig::is_empty")]
     pub web_crawlers: FilterConfig,
@@ -544,6 +730,10 @@ pub struct ProjectFiltersConfig {
     )]
     pub ignore_transactions: IgnoreTransactionsFilterConfig,
 
+    /// Configuration for the repeated errors filter.
+    #[serde(default, skip_serializing_if = "RepeatedErrorsFilterConfig::is_empty")]
+    pub repeated_errors: RepeatedErrorsFilterConfig,
+
     /// Configuration for generic filters from the project configs.
     #[serde(default, skip_serializing_if = "GenericFiltersConfig::is_empty")]
     pub generic: GenericFiltersConfig,
@@ -554,6 +744,7 @@ impl ProjectFiltersConfig {
     pub fn is_empty(&self) -> bool {
         self.browser_extensions.is_empty()
             && self.client_ips.is_empty()
+            && self.geo.is_empty()
             && self.web_crawlers.is_empty()
             && self.csp.is_empty()
             && self.error_messages.is_empty()
@@ -561,6 +752,7 @@ impl ProjectFiltersConfig {
             && self.localhost.is_empty()
             && self.releases.is_empty()
             && self.ignore_transactions.is_empty()
+            && self.repeated_errors.is_empty()
             && self.generic.is_empty()
     }
 }
@@ -580,6 +772,10 @@ mod tests {
             client_ips: ClientIpsFilterConfig {
                 blacklisted_ips: [],
             },
+            geo: GeoFilterConfig {
+                country_codes: [],
+                asns: [],
+            },
             web_crawlers: FilterConfig {
                 is_enabled: false,
             },
@@ -603,6 +799,11 @@ mod tests {
                 patterns: [],
                 is_enabled: false,
             },
+            repeated_errors: RepeatedErrorsFilterConfig {
+                is_enabled: false,
+                limit: 100,
+                window: 60,
+            },
             generic: GenericFiltersConfig {
                 version: 0,
                 filters: GenericFiltersMap(
@@ -627,6 +828,10 @@ mod tests {
             client_ips: ClientIpsFilterConfig {
                 blacklisted_ips: vec!["127.0.0.1".to_string()],
             },
+            geo: GeoFilterConfig {
+                country_codes: vec!["XX".to_string()],
+                asns: vec![64496],
+            },
             web_crawlers: FilterConfig { is_enabled: true },
             csp: CspFilterConfig {
                 disallowed_sources: vec!["https://*".to_string()],
@@ -649,6 +854,11 @@ mod tests {
                 patterns: GlobPatterns::new(vec!["*health*".to_string()]),
                 is_enabled: true,
             },
+            repeated_errors: RepeatedErrorsFilterConfig {
+                is_enabled: true,
+                limit: 10,
+                window: 300,
+            },
             generic: GenericFiltersConfig {
                 version: 1,
                 filters: vec![GenericFilterConfig {
@@ -670,6 +880,14 @@ mod tests {
               "127.0.0.1"
             ]
           },
+          "geo": {
+            "countryCodes": [
+              "XX"
+            ],
+            "asns": [
+              64496
+            ]
+          },
           "webCrawlers": {
             "isEnabled": true
           },
@@ -704,6 +922,11 @@ mod tests {
             ],
             "isEnabled": true
           },
+          "repeatedErrors": {
+            "isEnabled": true,
+            "limit": 10,
+            "window": 300
+          },
           "generic": {
             "version": 1,
             "filters": [
@@ -0,0 +1,133 @@
+//! Implements event filtering based on the geographic location of the client.
+//!
+//! The client IP address is resolved through the GeoIP database configured in Relay. A project may
+//! filter clients from specific countries or from specific networks identified by their autonomous
+//! system number (ASN), for instance known scanner networks.
+
+use std::net::IpAddr;
+
+use relay_event_normalization::GeoIpLookup;
+
+use crate::{FilterStatKey, GeoFilterConfig};
+
+/// Checks if the client IP is located in one of the filtered countries or networks.
+///
+/// The ASN is read from the network database if one is configured, and from the traits of an
+/// Enterprise database otherwise. Plain City databases do not contain ASNs.
+fn matches(client_ip: IpAddr, config: &GeoFilterConfig, geoip_lookup: &GeoIpLookup) -> bool {
+    let Ok(Some(geo)) = geoip_lookup.lookup_ip(client_ip) else {
+        return false;
+    };
+
+    if let Some(country_code) = geo.country_code.value() {
+        let filtered = config
+            .country_codes
+            .iter()
+            .any(|c| c.eq_ignore_ascii_case(country_code));
+
+        if filtered {
+            return true;
+        }
+    }
+
+    if let Some(&asn) = geo.asn.value() {
+        if config.asns.iter().any(|&a| u64::from(a) == asn) {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Filters events by the country and network of the client IP.
+///
+/// The client IP is the address of the originator of the event, see
+/// [`client_ips::should_filter`](crate::client_ips::should_filter). Without a GeoIP database, no
+/// events are filtered.
+pub fn should_filter(
+    client_ip: Option<IpAddr>,
+    config: &GeoFilterConfig,
+    geoip_lookup: Option<&GeoIpLookup>,
+) -> Result<(), FilterStatKey> {
+    if config.is_empty() {
+        return Ok(());
+    }
+
+    let (Some(client_ip), Some(geoip_lookup)) = (client_ip, geoip_lookup) else {
+        return Ok(());
+    };
+
+    if matches(client_ip, config, geoip_lookup) {
+        return Err(FilterStatKey::GeoLocation);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn geoip_lookup() -> GeoIpLookup {
+        GeoIpLookup::open("../relay-event-normalization/tests/fixtures/GeoIP2-Enterprise-Test.mmdb")
+            .unwrap()
+    }
+
+    #[test]
+    fn test_should_filter_country() {
+        let lookup = geoip_lookup();
+        let client_ip = "2.125.160.216".parse().ok();
+
+        let config = GeoFilterConfig {
+            country_codes: vec!["gb".to_owned()],
+            asns: vec![],
+        };
+        assert_eq!(
+            should_filter(client_ip, &config, Some(&lookup)),
+            Err(FilterStatKey::GeoLocation)
+        );
+
+        let config = GeoFilterConfig {
+            country_codes: vec!["US".to_owned()],
+            asns: vec![],
+        };
+        assert_eq!(should_filter(client_ip, &config, Some(&lookup)), Ok(()));
+    }
+
+    #[test]
+    fn test_should_filter_asn() {
+        let lookup = geoip_lookup();
+        let client_ip = "89.160.20.112".parse().ok();
+
+        let config = GeoFilterConfig {
+            country_codes: vec![],
+            asns: vec![29518],
+        };
+        assert_eq!(
+            should_filter(client_ip, &config, Some(&lookup)),
+            Err(FilterStatKey::GeoLocation)
+        );
+
+        let config = GeoFilterConfig {
+            country_codes: vec![],
+            asns: vec![14671],
+        };
+        assert_eq!(should_filter(client_ip, &config, Some(&lookup)), Ok(()));
+
+        // The address has no network information.
+        let client_ip = "2.125.160.216".parse().ok();
+        assert_eq!(should_filter(client_ip, &config, Some(&lookup)), Ok(()));
+    }
+
+    #[test]
+    fn test_should_not_filter_without_lookup() {
+        let config = GeoFilterConfig {
+            country_codes: vec!["GB".to_owned()],
+            asns: vec![],
+        };
+        let client_ip = "2.125.160.216".parse().ok();
+
+        assert_eq!(should_filter(client_ip, &config, None), Ok(()));
+        assert_eq!(should_filter(None, &config, Some(&geoip_lookup())), Ok(()));
+    }
+}
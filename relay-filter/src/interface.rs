@@ -1,9 +1,10 @@
 //! This module contains the trait for items that can be filtered by Inbound Filters, plus
-//! the implementation for [`Event`].
+//! the implementations for [`Event`] and other protocol types.
 use url::Url;
 
 use relay_event_schema::protocol::{
-    Csp, Event, EventType, Exception, LogEntry, Replay, Span, Values,
+    Csp, Event, EventType, Exception, LogEntry, Replay, SessionAggregates, SessionUpdate, Span,
+    Values,
 };
 
 /// A data item to which filters can be applied.
@@ -14,6 +15,16 @@ pub trait Filterable {
     /// The exception values of the item. Only for error events.
     fn exceptions(&self) -> Option<&Values<Exception>>;
 
+    /// The explicit fingerprint of the item. Only for error events.
+    fn fingerprint(&self) -> Option<&[String]> {
+        None
+    }
+
+    /// The grouping hash computed from fingerprinting rules. Only for error events.
+    fn grouping_hash(&self) -> Option<&str> {
+        None
+    }
+
     /// The IP address of the client that sent the data.
     fn ip_addr(&self) -> Option<&str>;
 
@@ -45,6 +56,14 @@ impl Filterable for Event {
         self.exceptions.value()
     }
 
+    fn fingerprint(&self) -> Option<&[String]> {
+        Some(self.fingerprint.value()?.as_slice())
+    }
+
+    fn grouping_hash(&self) -> Option<&str> {
+        self.grouping_hash.as_str()
+    }
+
     fn ip_addr(&self) -> Option<&str> {
         let user = self.user.value()?;
         Some(user.ip_address.value()?.as_ref())
@@ -148,3 +167,77 @@ impl Filterable for Span {
         self.data.value()?.user_agent_original.as_str()
     }
 }
+
+impl Filterable for SessionUpdate {
+    fn csp(&self) -> Option<&Csp> {
+        // Only for events.
+        None
+    }
+
+    fn exceptions(&self) -> Option<&Values<Exception>> {
+        // Only for events.
+        None
+    }
+
+    fn ip_addr(&self) -> Option<&str> {
+        Some(self.attributes.ip_address.as_ref()?.as_str())
+    }
+
+    fn logentry(&self) -> Option<&LogEntry> {
+        // Only for events.
+        None
+    }
+
+    fn release(&self) -> Option<&str> {
+        Some(&self.attributes.release)
+    }
+
+    fn transaction(&self) -> Option<&str> {
+        None
+    }
+
+    fn url(&self) -> Option<Url> {
+        None
+    }
+
+    fn user_agent(&self) -> Option<&str> {
+        self.attributes.user_agent.as_deref()
+    }
+}
+
+impl Filterable for SessionAggregates {
+    fn csp(&self) -> Option<&Csp> {
+        // Only for events.
+        None
+    }
+
+    fn exceptions(&self) -> Option<&Values<Exception>> {
+        // Only for events.
+        None
+    }
+
+    fn ip_addr(&self) -> Option<&str> {
+        Some(self.attributes.ip_address.as_ref()?.as_str())
+    }
+
+    fn logentry(&self) -> Option<&LogEntry> {
+        // Only for events.
+        None
+    }
+
+    fn release(&self) -> Option<&str> {
+        Some(&self.attributes.release)
+    }
+
+    fn transaction(&self) -> Option<&str> {
+        None
+    }
+
+    fn url(&self) -> Option<Url> {
+        None
+    }
+
+    fn user_agent(&self) -> Option<&str> {
+        self.attributes.user_agent.as_deref()
+    }
+}
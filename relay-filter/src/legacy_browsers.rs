@@ -1,10 +1,15 @@
 //! Implements filtering for events originating from legacy browsers.
+//!
+//! The browser versions selected in the project config can be extended through the
+//! [`LegacyBrowsersConfig`] in the global config.
 
 use std::collections::BTreeSet;
 
 use relay_ua::UserAgent;
 
-use crate::{FilterStatKey, Filterable, LegacyBrowser, LegacyBrowsersFilterConfig};
+use crate::{
+    FilterStatKey, Filterable, LegacyBrowser, LegacyBrowsersConfig, LegacyBrowsersFilterConfig,
+};
 
 /// Checks if the event originates from legacy browsers.
 fn matches(user_agent: &str, browsers: &BTreeSet<LegacyBrowser>) -> bool {
@@ -57,16 +62,22 @@ fn matches(user_agent: &str, browsers: &BTreeSet<LegacyBrowser>) -> bool {
 }
 
 /// Filters events originating from legacy browsers.
+///
+/// The optional global configuration extends the browsers selected in the project config.
 pub fn should_filter<F: Filterable>(
     item: &F,
     config: &LegacyBrowsersFilterConfig,
+    global: Option<&LegacyBrowsersConfig>,
 ) -> Result<(), FilterStatKey> {
     if !config.is_enabled || config.browsers.is_empty() {
         return Ok(()); // globally disabled or no individual browser enabled
     }
 
     let browsers = &config.browsers;
-    if item.user_agent().map_or(false, |ua| matches(ua, browsers)) {
+    let is_legacy =
+        |ua: &str| matches(ua, browsers) || global.map_or(false, |g| g.patterns.is_match(ua));
+
+    if item.user_agent().map_or(false, is_legacy) {
         Err(FilterStatKey::LegacyBrowsers)
     } else {
         Ok(())
@@ -168,7 +179,7 @@ mod tests {
     const ANDROID_UA: &str = "Mozilla/5.0 (Linux; U; Android 3.2; nl-nl; GT-P6800 Build/HTJ85B) AppleWebKit/534.13 (KHTML, like Gecko) Version/4.0 Safari/534.13";
 
     use super::*;
-    use crate::testutils;
+    use crate::{testutils, UserAgentPatterns};
 
     fn get_legacy_browsers_config(
         is_enabled: bool,
@@ -192,6 +203,7 @@ mod tests {
         let filter_result = should_filter(
             &evt,
             &get_legacy_browsers_config(false, &[LegacyBrowser::Default]),
+            None,
         );
         assert_eq!(
             filter_result,
@@ -200,6 +212,29 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_filter_global_user_agents() {
+        let global = LegacyBrowsersConfig {
+            patterns: UserAgentPatterns::new(vec![r"ExampleBrowser/1\.".to_owned()]),
+        };
+        let config = get_legacy_browsers_config(true, &[LegacyBrowser::Default]);
+
+        let evt = testutils::get_event_with_user_agent("Mozilla/5.0 ExampleBrowser/1.2");
+        assert_eq!(
+            should_filter(&evt, &config, Some(&global)),
+            Err(FilterStatKey::LegacyBrowsers)
+        );
+        assert_eq!(should_filter(&evt, &config, None), Ok(()));
+
+        let evt = testutils::get_event_with_user_agent("Mozilla/5.0 ExampleBrowser/2.0");
+        assert_eq!(should_filter(&evt, &config, Some(&global)), Ok(()));
+
+        // Global patterns only apply if the project enables the filter.
+        let evt = testutils::get_event_with_user_agent("Mozilla/5.0 ExampleBrowser/1.2");
+        let disabled = get_legacy_browsers_config(false, &[LegacyBrowser::Default]);
+        assert_eq!(should_filter(&evt, &disabled, Some(&global)), Ok(()));
+    }
+
     #[test]
     fn test_filter_default_browsers() {
         for old_user_agent in &[
@@ -214,6 +249,7 @@ mod tests {
             let filter_result = should_filter(
                 &evt,
                 &get_legacy_browsers_config(true, &[LegacyBrowser::Default]),
+                None,
             );
             assert_ne!(
                 filter_result,
@@ -236,6 +272,7 @@ mod tests {
             let filter_result = should_filter(
                 &evt,
                 &get_legacy_browsers_config(true, &[LegacyBrowser::Default]),
+                None,
             );
             assert_eq!(
                 filter_result,
@@ -302,8 +339,11 @@ mod tests {
 
         for (ref user_agent, ref active_filters) in &test_configs {
             let evt = testutils::get_event_with_user_agent(user_agent);
-            let filter_result =
-                should_filter(&evt, &get_legacy_browsers_config(true, active_filters));
+            let filter_result = should_filter(
+                &evt,
+                &get_legacy_browsers_config(true, active_filters),
+                None,
+            );
             assert_ne!(
                 filter_result,
                 Ok(()),
@@ -334,6 +374,7 @@ mod tests {
             let filter_result = should_filter(
                 &evt,
                 &get_legacy_browsers_config(true, &[active_filter.clone()]),
+                None,
             );
             assert_eq!(
                 filter_result,
@@ -413,6 +454,7 @@ mod tests {
                 let filter_result = should_filter(
                     &evt,
                     &get_legacy_browsers_config(true, &[active_filter.clone()]),
+                    None,
                 );
                 assert_ne!(
                     filter_result,
@@ -442,6 +484,7 @@ mod tests {
                 let filter_result = should_filter(
                     &evt,
                     &get_legacy_browsers_config(true, &[active_filter.clone()]),
+                    None,
                 );
                 assert_eq!(
                     filter_result,
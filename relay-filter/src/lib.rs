@@ -3,9 +3,11 @@
 //! Events may be filtered base on the following configurable criteria.
 //!
 //! * localhost (filter events originating from the local machine)
+//! * geo location (filter events from clients in certain countries or networks)
 //! * browser extensions (filter events caused by known problematic browser extensions)
 //! * web crawlers (filter events sent by user agents known to be web crawlers)
 //! * legacy browsers (filter events originating from legacy browsers, can be configured)
+//! * repeated errors (filter identical errors seen too often within a time window)
 #![warn(missing_docs)]
 #![doc(
     html_logo_url = "https://raw.githubusercontent.com/getsentry/relay/master/artwork/relay-icon.png",
@@ -14,6 +16,7 @@
 
 use std::net::IpAddr;
 
+use relay_event_normalization::GeoIpLookup;
 use relay_protocol::Getter;
 
 pub mod browser_extensions;
@@ -21,8 +24,10 @@ pub mod client_ips;
 pub mod csp;
 pub mod error_messages;
 pub mod generic;
+pub mod geo;
 pub mod legacy_browsers;
 pub mod localhost;
+pub mod repeated_errors;
 pub mod transaction_name;
 pub mod web_crawlers;
 
@@ -44,11 +49,17 @@ pub use interface::Filterable;
 ///
 /// If the event should be filtered, the `Err` returned contains a filter reason.
 /// The reason is the message returned by the first filter that didn't pass.
+///
+/// Generic filters, web crawler and legacy browser patterns from the global config are applied in
+/// addition to the project's filter settings. The geo location filter requires a GeoIP lookup.
 pub fn should_filter<F: Filterable + Getter>(
     item: &F,
     client_ip: Option<IpAddr>,
     config: &ProjectFiltersConfig,
     global_config: Option<&GenericFiltersConfig>,
+    web_crawlers: Option<&WebCrawlersConfig>,
+    legacy_browsers: Option<&LegacyBrowsersConfig>,
+    geoip_lookup: Option<&GeoIpLookup>,
 ) -> Result<(), FilterStatKey> {
     // In order to maintain backwards compatibility, we still want to run the old matching logic,
     // but we will try to match generic filters first, since the goal is to eventually fade out
@@ -59,12 +70,13 @@ pub fn should_filter<F: Filterable + Getter>(
     // when making changes to this order.
     csp::should_filter(item, &config.csp)?;
     client_ips::should_filter(client_ip, &config.client_ips)?;
+    geo::should_filter(client_ip, &config.geo, geoip_lookup)?;
     releases::should_filter(item, &config.releases)?;
     error_messages::should_filter(item, &config.error_messages)?;
     localhost::should_filter(item, &config.localhost)?;
     browser_extensions::should_filter(item, &config.browser_extensions)?;
-    legacy_browsers::should_filter(item, &config.legacy_browsers)?;
-    web_crawlers::should_filter(item, &config.web_crawlers)?;
+    legacy_browsers::should_filter(item, &config.legacy_browsers, legacy_browsers)?;
+    web_crawlers::should_filter(item, &config.web_crawlers, web_crawlers)?;
     transaction_name::should_filter(item, &config.ignore_transactions)?;
 
     Ok(())
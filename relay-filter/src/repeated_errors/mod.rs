@@ -0,0 +1,430 @@
+//! Implements filtering of repeated identical errors.
+//!
+//! Errors are grouped by a fingerprint key. Once more than the configured number of errors with
+//! the same key has been seen within a time window, all further errors with that key are dropped
+//! until the window expires.
+//!
+//! The key is derived from the grouping hash that normalization computes from the project's
+//! fingerprinting rules, if present. Otherwise, it is derived from the explicit fingerprint of the
+//! event, or computed from the type and message of the last exception and its top-most stack frame,
+//! or from the log message if the event has no exceptions.
+
+use std::hash::Hasher as _;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use fnv::FnvHasher;
+use lru::LruCache;
+use relay_base_schema::project::ProjectId;
+
+use crate::{FilterStatKey, Filterable, RepeatedErrorsFilterConfig};
+
+#[cfg(feature = "redis")]
+mod redis;
+
+#[cfg(feature = "redis")]
+pub use self::redis::*;
+
+/// The fingerprint entry that stands for the default grouping.
+const DEFAULT_FINGERPRINT: &str = "{{default}}";
+
+/// The maximum number of keys tracked by [`InMemoryRepeatedErrors`].
+const MAX_IN_MEMORY_KEYS: usize = 10_000;
+
+/// Counts occurrences of errors per fingerprint key.
+pub trait RepeatedErrorsCounter {
+    /// Increments the counter for `key` and returns the number of occurrences in the current
+    /// window, including this one.
+    ///
+    /// Implementations return `0` if the count cannot be determined, which never filters.
+    fn increment(&self, key: &str, window: Duration) -> u64;
+}
+
+/// A [`RepeatedErrorsCounter`] that keeps counts in memory of this Relay instance.
+///
+/// Every key uses a fixed window that starts with the first error seen for it. The number of
+/// tracked keys is bounded. When the limit is reached, the least recently seen key is evicted to
+/// make space for a new one.
+#[derive(Debug)]
+pub struct InMemoryRepeatedErrors {
+    windows: Mutex<LruCache<String, (Instant, u64)>>,
+}
+
+impl InMemoryRepeatedErrors {
+    /// Creates a new, empty counter.
+    pub fn new() -> Self {
+        Self::with_capacity(NonZeroUsize::new(MAX_IN_MEMORY_KEYS).unwrap())
+    }
+
+    fn with_capacity(capacity: NonZeroUsize) -> Self {
+        Self {
+            windows: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    fn increment_at(&self, key: &str, window: Duration, now: Instant) -> u64 {
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+
+        if let Some((start, count)) = windows.get_mut(key) {
+            if now.duration_since(*start) < window {
+                *count += 1;
+            } else {
+                *start = now;
+                *count = 1;
+            }
+            return *count;
+        }
+
+        windows.push(key.to_owned(), (now, 1));
+        1
+    }
+}
+
+impl Default for InMemoryRepeatedErrors {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RepeatedErrorsCounter for InMemoryRepeatedErrors {
+    fn increment(&self, key: &str, window: Duration) -> u64 {
+        self.increment_at(key, window, Instant::now())
+    }
+}
+
+fn write_part(hasher: &mut FnvHasher, part: &str) {
+    // Hash the bytes directly and separate parts, so that the key is stable across releases.
+    std::hash::Hasher::write(hasher, part.as_bytes());
+    std::hash::Hasher::write_u8(hasher, 0);
+}
+
+fn is_default(part: &str) -> bool {
+    part.chars()
+        .filter(|c| !c.is_whitespace())
+        .eq(DEFAULT_FINGERPRINT.chars())
+}
+
+/// Writes the default grouping components of the item into the hasher.
+///
+/// Returns `false` if the item is not an error.
+fn write_default<F: Filterable>(hasher: &mut FnvHasher, item: &F) -> bool {
+    let exception = item
+        .exceptions()
+        .and_then(|values| values.values.value())
+        .and_then(|exceptions| exceptions.iter().rev().find_map(|e| e.value()));
+
+    if let Some(exception) = exception {
+        write_part(hasher, exception.ty.as_str().unwrap_or_default());
+        write_part(hasher, exception.value.as_str().unwrap_or_default());
+
+        let frame = exception
+            .stacktrace
+            .value()
+            .and_then(|stacktrace| stacktrace.frames.value())
+            .and_then(|frames| frames.iter().rev().find_map(|f| f.value()));
+
+        if let Some(frame) = frame {
+            write_part(hasher, frame.function.as_str().unwrap_or_default());
+            write_part(hasher, frame.module.as_str().unwrap_or_default());
+            let filename = frame.filename.value().map(|f| f.as_str());
+            write_part(hasher, filename.unwrap_or_default());
+        }
+
+        return true;
+    }
+
+    let message = item
+        .logentry()
+        .and_then(|logentry| logentry.formatted.value().or(logentry.message.value()));
+
+    match message {
+        Some(message) => {
+            write_part(hasher, message.as_ref());
+            true
+        }
+        None => false,
+    }
+}
+
+/// Computes the fingerprint key of an error.
+///
+/// Returns `None` if there is nothing to group the item by, that is, it has no explicit
+/// fingerprint, exceptions, or log message.
+fn fingerprint<F: Filterable>(item: &F) -> Option<u64> {
+    if let Some(grouping_hash) = item.grouping_hash() {
+        let mut hasher = FnvHasher::default();
+        write_part(&mut hasher, grouping_hash);
+        return Some(hasher.finish());
+    }
+
+    let Some(parts) = item.fingerprint().filter(|parts| !parts.is_empty()) else {
+        let mut hasher = FnvHasher::default();
+        return write_default(&mut hasher, item).then(|| hasher.finish());
+    };
+
+    let mut hasher = FnvHasher::default();
+    let mut has_parts = false;
+    for part in parts {
+        if is_default(part) {
+            has_parts |= write_default(&mut hasher, item);
+        } else {
+            write_part(&mut hasher, part);
+            has_parts = true;
+        }
+    }
+
+    has_parts.then(|| hasher.finish())
+}
+
+/// Filters errors that were seen more often than the configured limit within the time window.
+///
+/// The counter is incremented for every error that passes through this filter, so it must only be
+/// called once per event. Items that are not errors are never filtered.
+pub fn should_filter<F, C>(
+    item: &F,
+    project_id: ProjectId,
+    config: &RepeatedErrorsFilterConfig,
+    counter: &C,
+) -> Result<(), FilterStatKey>
+where
+    F: Filterable,
+    C: RepeatedErrorsCounter + ?Sized,
+{
+    if config.is_empty() {
+        return Ok(());
+    }
+
+    let Some(fingerprint) = fingerprint(item) else {
+        return Ok(());
+    };
+
+    let key = format!("{project_id}:{fingerprint:016x}");
+    let count = counter.increment(&key, Duration::from_secs(config.window));
+
+    if count > config.limit {
+        Err(FilterStatKey::RepeatedErrors)
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use relay_event_schema::protocol::{
+        Event, Exception, Frame, JsonLenientString, LogEntry, RawStacktrace, Stacktrace, Values,
+    };
+    use relay_protocol::Annotated;
+
+    use super::*;
+
+    fn get_event(ty: &str, value: &str, function: &str) -> Event {
+        let frame = Frame {
+            function: Annotated::new(function.to_owned()),
+            ..Frame::default()
+        };
+
+        let exception = Exception {
+            ty: Annotated::new(ty.to_owned()),
+            value: Annotated::new(JsonLenientString::from(value.to_owned())),
+            stacktrace: Annotated::new(Stacktrace(RawStacktrace {
+                frames: Annotated::new(vec![Annotated::new(frame)]),
+                ..RawStacktrace::default()
+            })),
+            ..Exception::default()
+        };
+
+        Event {
+            exceptions: Annotated::new(Values::new(vec![Annotated::new(exception)])),
+            ..Event::default()
+        }
+    }
+
+    fn config(limit: u64) -> RepeatedErrorsFilterConfig {
+        RepeatedErrorsFilterConfig {
+            is_enabled: true,
+            limit,
+            window: 60,
+        }
+    }
+
+    #[test]
+    fn test_should_filter_repeated() {
+        let counter = InMemoryRepeatedErrors::new();
+        let project_id = ProjectId::new(42);
+        let config = config(2);
+
+        let event = get_event("ValueError", "invalid value", "parse");
+        let other = get_event("ValueError", "invalid value", "validate");
+
+        assert_eq!(should_filter(&event, project_id, &config, &counter), Ok(()));
+        assert_eq!(should_filter(&event, project_id, &config, &counter), Ok(()));
+        assert_eq!(
+            should_filter(&event, project_id, &config, &counter),
+            Err(FilterStatKey::RepeatedErrors)
+        );
+
+        // A different top frame and a different project are counted separately.
+        assert_eq!(should_filter(&other, project_id, &config, &counter), Ok(()));
+        let other_project = ProjectId::new(43);
+        assert_eq!(
+            should_filter(&event, other_project, &config, &counter),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_should_not_filter_disabled() {
+        let counter = InMemoryRepeatedErrors::new();
+        let config = RepeatedErrorsFilterConfig {
+            is_enabled: false,
+            ..config(0)
+        };
+
+        let event = get_event("ValueError", "invalid value", "parse");
+        for _ in 0..3 {
+            assert_eq!(
+                should_filter(&event, ProjectId::new(42), &config, &counter),
+                Ok(())
+            );
+        }
+    }
+
+    #[test]
+    fn test_should_not_filter_non_errors() {
+        let counter = InMemoryRepeatedErrors::new();
+        let config = config(1);
+
+        let event = Event::default();
+        for _ in 0..3 {
+            assert_eq!(
+                should_filter(&event, ProjectId::new(42), &config, &counter),
+                Ok(())
+            );
+        }
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let event = get_event("ValueError", "invalid value", "parse");
+        let default = fingerprint(&event).unwrap();
+
+        let with_default = Event {
+            fingerprint: Annotated::new(vec!["{{ default }}".to_owned()].into()),
+            ..event.clone()
+        };
+        assert_eq!(fingerprint(&with_default), Some(default));
+
+        let explicit = Event {
+            fingerprint: Annotated::new(vec!["database-unavailable".to_owned()].into()),
+            ..event.clone()
+        };
+        let other = Event {
+            fingerprint: Annotated::new(vec!["database-unavailable".to_owned()].into()),
+            ..get_event("TypeError", "other", "other")
+        };
+        assert_ne!(fingerprint(&explicit), Some(default));
+        assert_eq!(fingerprint(&explicit), fingerprint(&other));
+
+        let message = Event {
+            logentry: Annotated::new(LogEntry::from("something happened".to_owned())),
+            ..Event::default()
+        };
+        assert!(fingerprint(&message).is_some());
+    }
+
+    #[test]
+    fn test_fingerprint_without_error() {
+        // An explicit fingerprint is sufficient to group events without exceptions or messages.
+        let explicit = Event {
+            fingerprint: Annotated::new(vec!["database-unavailable".to_owned()].into()),
+            ..Event::default()
+        };
+        assert!(fingerprint(&explicit).is_some());
+
+        let mixed = Event {
+            fingerprint: Annotated::new(
+                vec![
+                    "{{ default }}".to_owned(),
+                    "database-unavailable".to_owned(),
+                ]
+                .into(),
+            ),
+            ..Event::default()
+        };
+        assert!(fingerprint(&mixed).is_some());
+
+        // The default grouping alone has nothing to group by.
+        let default = Event {
+            fingerprint: Annotated::new(vec!["{{ default }}".to_owned()].into()),
+            ..Event::default()
+        };
+        assert_eq!(fingerprint(&default), None);
+    }
+
+    #[test]
+    fn test_should_filter_fingerprint_without_error() {
+        let counter = InMemoryRepeatedErrors::new();
+        let project_id = ProjectId::new(42);
+        let config = config(1);
+
+        let event = Event {
+            fingerprint: Annotated::new(vec!["database-unavailable".to_owned()].into()),
+            ..Event::default()
+        };
+
+        assert_eq!(should_filter(&event, project_id, &config, &counter), Ok(()));
+        assert_eq!(
+            should_filter(&event, project_id, &config, &counter),
+            Err(FilterStatKey::RepeatedErrors)
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_grouping_hash() {
+        let grouping_hash = Annotated::new("0123456789abcdef0123456789abcdef".to_owned());
+
+        let event = Event {
+            grouping_hash: grouping_hash.clone(),
+            ..get_event("ValueError", "invalid value", "parse")
+        };
+        let other = Event {
+            grouping_hash,
+            fingerprint: Annotated::new(vec!["database-unavailable".to_owned()].into()),
+            ..get_event("TypeError", "other", "other")
+        };
+
+        // The grouping hash takes precedence over the explicit fingerprint and default grouping.
+        assert_eq!(fingerprint(&event), fingerprint(&other));
+    }
+
+    #[test]
+    fn test_in_memory_window() {
+        let counter = InMemoryRepeatedErrors::new();
+        let window = Duration::from_secs(60);
+        let now = Instant::now();
+
+        assert_eq!(counter.increment_at("a", window, now), 1);
+        assert_eq!(counter.increment_at("a", window, now), 2);
+        assert_eq!(counter.increment_at("b", window, now), 1);
+
+        let later = now + window;
+        assert_eq!(counter.increment_at("a", window, later), 1);
+    }
+
+    #[test]
+    fn test_in_memory_evicts_least_recent() {
+        let counter = InMemoryRepeatedErrors::with_capacity(NonZeroUsize::new(2).unwrap());
+        let window = Duration::from_secs(60);
+        let now = Instant::now();
+
+        assert_eq!(counter.increment_at("a", window, now), 1);
+        assert_eq!(counter.increment_at("b", window, now), 1);
+        assert_eq!(counter.increment_at("a", window, now), 2);
+
+        // "b" is the least recently seen key and makes space for "c".
+        assert_eq!(counter.increment_at("c", window, now), 1);
+        assert_eq!(counter.increment_at("a", window, now), 3);
+        assert_eq!(counter.increment_at("b", window, now), 1);
+    }
+}
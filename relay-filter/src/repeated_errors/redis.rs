@@ -0,0 +1,59 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use relay_redis::{RedisError, RedisPool};
+
+use super::RepeatedErrorsCounter;
+
+/// A [`RepeatedErrorsCounter`] that shares counts across Relay instances through Redis.
+///
+/// Windows are aligned to multiples of the window size since the UNIX epoch, so that all instances
+/// count into the same Redis key.
+pub struct RedisRepeatedErrors {
+    redis: RedisPool,
+}
+
+impl RedisRepeatedErrors {
+    /// Creates a new Redis-backed counter.
+    pub fn new(redis: RedisPool) -> Self {
+        Self { redis }
+    }
+
+    fn try_increment(&self, key: &str, window: Duration) -> Result<u64, RedisError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let window = window.as_secs().max(1);
+        let redis_key = format!("repeated_errors:{{{key}}}:{}", now / window);
+
+        let mut client = self.redis.client()?;
+        let mut connection = client.connection()?;
+
+        let (count,): (u64,) = relay_redis::redis::pipe()
+            .cmd("INCR")
+            .arg(&redis_key)
+            .cmd("EXPIRE")
+            .arg(&redis_key)
+            .arg(window)
+            .ignore()
+            .query(&mut connection)
+            .map_err(RedisError::Redis)?;
+
+        Ok(count)
+    }
+}
+
+impl RepeatedErrorsCounter for RedisRepeatedErrors {
+    fn increment(&self, key: &str, window: Duration) -> u64 {
+        match self.try_increment(key, window) {
+            Ok(count) => count,
+            Err(error) => {
+                relay_log::error!(
+                    error = &error as &dyn std::error::Error,
+                    "failed to count repeated errors in redis"
+                );
+                0
+            }
+        }
+    }
+}
@@ -1,9 +1,12 @@
 //! Filters events coming from user agents known to be web crawlers.
+//!
+//! The built-in lists of web crawlers can be extended through the [`WebCrawlersConfig`] in the
+//! global config.
 
 use once_cell::sync::Lazy;
 use regex::Regex;
 
-use crate::{FilterConfig, FilterStatKey, Filterable};
+use crate::{FilterConfig, FilterStatKey, Filterable, WebCrawlersConfig};
 
 static WEB_CRAWLERS: Lazy<Regex> = Lazy::new(|| {
     Regex::new(
@@ -48,21 +51,33 @@ static ALLOWED_WEB_CRAWLERS: Lazy<Regex> = Lazy::new(|| {
 });
 
 /// Checks if the event originates from a known web crawler.
-fn matches(user_agent: Option<&str>) -> bool {
-    if let Some(user_agent) = user_agent {
-        WEB_CRAWLERS.is_match(user_agent) && !ALLOWED_WEB_CRAWLERS.is_match(user_agent)
-    } else {
-        false
-    }
+fn matches(user_agent: Option<&str>, global: Option<&WebCrawlersConfig>) -> bool {
+    let Some(user_agent) = user_agent else {
+        return false;
+    };
+
+    let is_crawler = WEB_CRAWLERS.is_match(user_agent)
+        || global.map_or(false, |g| g.patterns.is_match(user_agent));
+
+    let is_allowed = ALLOWED_WEB_CRAWLERS.is_match(user_agent)
+        || global.map_or(false, |g| g.allowed.is_match(user_agent));
+
+    is_crawler && !is_allowed
 }
 
 /// Filters events originating from a known web crawler.
-pub fn should_filter<F: Filterable>(item: &F, config: &FilterConfig) -> Result<(), FilterStatKey> {
+///
+/// The optional global configuration extends the built-in lists of crawlers.
+pub fn should_filter<F: Filterable>(
+    item: &F,
+    config: &FilterConfig,
+    global: Option<&WebCrawlersConfig>,
+) -> Result<(), FilterStatKey> {
     if !config.is_enabled {
         return Ok(());
     }
 
-    if matches(item.user_agent()) {
+    if matches(item.user_agent(), global) {
         return Err(FilterStatKey::WebCrawlers);
     }
 
@@ -72,12 +87,12 @@ pub fn should_filter<F: Filterable>(item: &F, config: &FilterConfig) -> Result<(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::testutils;
+    use crate::{testutils, UserAgentPatterns};
 
     #[test]
     fn test_filter_when_disabled() {
         let evt = testutils::get_event_with_user_agent("Googlebot");
-        let filter_result = should_filter(&evt, &FilterConfig { is_enabled: false });
+        let filter_result = should_filter(&evt, &FilterConfig { is_enabled: false }, None);
         assert_eq!(
             filter_result,
             Ok(()),
@@ -125,7 +140,7 @@ mod tests {
 
         for banned_user_agent in &user_agents {
             let event = testutils::get_event_with_user_agent(banned_user_agent);
-            let filter_result = should_filter(&event, &FilterConfig { is_enabled: true });
+            let filter_result = should_filter(&event, &FilterConfig { is_enabled: true }, None);
             assert_ne!(
                 filter_result,
                 Ok(()),
@@ -148,7 +163,7 @@ mod tests {
         ];
         for user_agent in &normal_user_agents {
             let event = testutils::get_event_with_user_agent(user_agent);
-            let filter_result = should_filter(&event, &FilterConfig { is_enabled: true });
+            let filter_result = should_filter(&event, &FilterConfig { is_enabled: true }, None);
             assert_eq!(
                 filter_result,
                 Ok(()),
@@ -156,4 +171,30 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_filter_global_user_agents() {
+        let global = WebCrawlersConfig {
+            patterns: UserAgentPatterns::new(vec![r"ExampleBot/\d+".to_owned(), "(".to_owned()]),
+            allowed: UserAgentPatterns::new(vec!["examplebot/2".to_owned()]),
+        };
+        let config = FilterConfig { is_enabled: true };
+
+        let event = testutils::get_event_with_user_agent("Mozilla/5.0 (ExampleBot/1)");
+        assert_eq!(
+            should_filter(&event, &config, Some(&global)),
+            Err(FilterStatKey::WebCrawlers)
+        );
+        assert_eq!(should_filter(&event, &config, None), Ok(()));
+
+        let event = testutils::get_event_with_user_agent("Mozilla/5.0 (ExampleBot/2)");
+        assert_eq!(should_filter(&event, &config, Some(&global)), Ok(()));
+
+        // Built-in crawlers are still filtered.
+        let event = testutils::get_event_with_user_agent("Googlebot");
+        assert_eq!(
+            should_filter(&event, &config, Some(&global)),
+            Err(FilterStatKey::WebCrawlers)
+        );
+    }
 }
@@ -1,7 +1,7 @@
 //! Core functionality of metrics aggregation.
 
 use std::collections::hash_map::Entry;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::error::Error;
 use std::hash::{Hash, Hasher};
 use std::time::Duration;
@@ -56,6 +56,9 @@ enum AggregateMetricsErrorKind {
     /// A metric bucket is too large for the per-project bytes limit.
     #[error("project metrics limit exceeded")]
     ProjectLimitExceeded,
+    /// A metric bucket is too large for the per-namespace bytes limit.
+    #[error("namespace metrics limit exceeded: {0}")]
+    NamespaceLimitExceeded(MetricNamespace),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -149,6 +152,23 @@ pub enum FlushBatching {
     None,
 }
 
+/// Rollup applied to the buckets of a metric namespace before they are flushed.
+///
+/// Rollups trade granularity for volume: buckets of the namespace are aggregated into coarser time
+/// windows and lose the configured tags, which reduces the number of buckets sent upstream.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct RollupConfig {
+    /// The time interval in seconds into which buckets of the namespace are aggregated.
+    ///
+    /// Intervals smaller than the aggregator's `bucket_interval` have no effect. Intervals that are
+    /// not a multiple of the aggregator's `bucket_interval` are rounded up to the next multiple.
+    pub bucket_interval: u64,
+
+    /// Tags removed from buckets of the namespace before aggregation.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub drop_tags: BTreeSet<String>,
+}
+
 /// Parameters used by the [`Aggregator`].
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(default)]
@@ -200,6 +220,24 @@ pub struct AggregatorConfig {
     /// Defaults to `None`, i.e. no limit.
     pub max_project_key_bucket_bytes: Option<usize>,
 
+    /// Maximum amount of bytes used for metrics aggregation per metric namespace.
+    ///
+    /// Similar measuring technique to `max_total_bucket_bytes`, but enforced per namespace. A
+    /// namespace that exceeds its budget is rejected before it can exhaust the global limit, so
+    /// that other namespaces continue to be accepted. Namespaces without an entry are not limited.
+    ///
+    /// Defaults to an empty map, i.e. no limits.
+    pub max_namespace_bucket_bytes: BTreeMap<MetricNamespace, usize>,
+
+    /// Rollups applied to buckets per metric namespace.
+    ///
+    /// Buckets of a namespace with a [`RollupConfig`] are aggregated into the rollup's coarser
+    /// `bucket_interval` and lose the configured tags. Flush times, shifts and partitions are
+    /// computed from the rolled-up buckets.
+    ///
+    /// Defaults to an empty map, i.e. no rollups.
+    pub rollups: BTreeMap<MetricNamespace, RollupConfig>,
+
     /// The number of logical partitions that can receive flushed buckets.
     ///
     /// If set, buckets are partitioned by (bucket key % flush_partitions), and routed
@@ -221,9 +259,40 @@ pub struct AggregatorConfig {
 }
 
 impl AggregatorConfig {
-    /// Returns the time width buckets.
-    fn bucket_interval(&self) -> Duration {
-        Duration::from_secs(self.bucket_interval)
+    /// Returns the time width of buckets in the given namespace in seconds.
+    ///
+    /// This is the aggregator's `bucket_interval` unless a rollup is configured for the namespace.
+    fn bucket_interval_for(&self, namespace: MetricNamespace) -> u64 {
+        let Some(rollup) = self.rollups.get(&namespace) else {
+            return self.bucket_interval;
+        };
+
+        let interval = self.bucket_interval.max(1);
+        let rollup_interval = rollup.bucket_interval.max(interval);
+        // Round up to a multiple of the bucket interval, so that rolled-up buckets align with the
+        // buckets they are aggregated from.
+        rollup_interval.div_ceil(interval) * interval
+    }
+
+    /// Applies the configured rollup for the key's namespace to a bucket key.
+    ///
+    /// Moves the key into its rolled-up time window and removes dropped tags.
+    fn rollup_key(&self, mut key: BucketKey) -> BucketKey {
+        let namespace = key.namespace();
+        let Some(rollup) = self.rollups.get(&namespace) else {
+            return key;
+        };
+
+        let interval = self.bucket_interval_for(namespace);
+        let ts = key.timestamp.as_secs();
+        key.timestamp = UnixTimestamp::from_secs((ts / interval) * interval);
+
+        if !rollup.drop_tags.is_empty() {
+            key.tags
+                .retain(|tag_key, _| !rollup.drop_tags.contains(tag_key));
+        }
+
+        key
     }
 
     /// Returns the initial flush delay after the end of a bucket's original time window.
@@ -235,7 +304,7 @@ impl AggregatorConfig {
     ///
     /// This distributes buckets over time to prevent peaks.
     fn flush_time_shift(&self, bucket: &BucketKey) -> Duration {
-        let shift_range = self.bucket_interval * 1000;
+        let shift_range = self.bucket_interval_for(bucket.namespace()) * 1000;
 
         // Fall back to default flushing by project if no partitioning is configured.
         let (batching, partitions) = match (self.flush_batching, self.flush_partitions) {
@@ -291,6 +360,8 @@ impl Default for AggregatorConfig {
             max_tag_key_length: 200,
             max_tag_value_length: 200,
             max_project_key_bucket_bytes: None,
+            max_namespace_bucket_bytes: BTreeMap::new(),
+            rollups: BTreeMap::new(),
             flush_batching: FlushBatching::default(),
             flush_partitions: None,
         }
@@ -466,7 +537,9 @@ fn get_flush_time(
     reference_time: Instant,
     bucket_key: &BucketKey,
 ) -> Instant {
-    let initial_flush = bucket_key.timestamp + config.bucket_interval() + config.initial_delay();
+    let bucket_interval = config.bucket_interval_for(bucket_key.namespace());
+    let initial_flush =
+        bucket_key.timestamp + Duration::from_secs(bucket_interval) + config.initial_delay();
 
     let now = UnixTimestamp::now();
     let backdated = initial_flush <= now;
@@ -485,9 +558,9 @@ fn get_flush_time(
         // The rationale behind this is that we want to flush this bucket in the earliest slot
         // together with buckets that have similar characteristics (e.g., same partition,
         // project...).
-        let floored_timestamp = (now.as_secs() / config.bucket_interval) * config.bucket_interval;
+        let floored_timestamp = (now.as_secs() / bucket_interval) * bucket_interval;
         UnixTimestamp::from_secs(floored_timestamp)
-            + config.bucket_interval()
+            + Duration::from_secs(bucket_interval)
             + config.initial_delay()
     } else {
         // If the initial flush is still pending, use that.
@@ -532,6 +605,7 @@ pub struct Aggregator {
     config: AggregatorConfig,
     buckets: HashMap<BucketKey, QueuedBucket>,
     cost_tracker: CostTracker,
+    namespace_cost: BTreeMap<MetricNamespace, usize>,
     reference_time: Instant,
 }
 
@@ -548,6 +622,7 @@ impl Aggregator {
             config,
             buckets: HashMap::new(),
             cost_tracker: CostTracker::default(),
+            namespace_cost: BTreeMap::new(),
             reference_time: Instant::now(),
         }
     }
@@ -567,6 +642,22 @@ impl Aggregator {
         self.cost_tracker.totals_cost_exceeded(max_total_cost)
     }
 
+    /// Returns `true` if the namespace has reached its configured budget.
+    ///
+    /// See [`AggregatorConfig::max_namespace_bucket_bytes`].
+    pub fn namespace_cost_exceeded(&self, namespace: MetricNamespace) -> bool {
+        let Some(&max_cost) = self.config.max_namespace_bucket_bytes.get(&namespace) else {
+            return false;
+        };
+
+        self.namespace_cost.get(&namespace).copied().unwrap_or(0) >= max_cost
+    }
+
+    /// Returns the namespaces that have a budget configured in this aggregator.
+    pub fn budgeted_namespaces(&self) -> impl Iterator<Item = MetricNamespace> + '_ {
+        self.config.max_namespace_bucket_bytes.keys().copied()
+    }
+
     /// Converts this aggregator into a vector of [`Bucket`].
     pub fn into_buckets(self) -> Vec<Bucket> {
         relay_statsd::metric!(
@@ -574,13 +665,13 @@ impl Aggregator {
             aggregator = &self.name,
         );
 
-        let bucket_interval = self.config.bucket_interval;
+        let config = self.config;
 
         self.buckets
             .into_iter()
             .map(|(key, entry)| Bucket {
                 timestamp: key.timestamp,
-                width: bucket_interval,
+                width: config.bucket_interval_for(key.namespace()),
                 name: key.metric_name,
                 value: entry.value,
                 tags: key.tags,
@@ -589,6 +680,30 @@ impl Aggregator {
             .collect()
     }
 
+    /// Returns a copy of all buckets in the aggregator, grouped by project key.
+    ///
+    /// In contrast to [`Self::pop_flush_buckets`], this leaves the aggregator unchanged. It is used
+    /// to persist the state of the aggregator.
+    pub fn snapshot(&self) -> HashMap<ProjectKey, Vec<Bucket>> {
+        let mut buckets = HashMap::<_, Vec<_>>::new();
+
+        for (key, entry) in &self.buckets {
+            let mut metadata = entry.metadata;
+            metadata.extracted_from_indexed = key.extracted_from_indexed;
+
+            buckets.entry(key.project_key).or_default().push(Bucket {
+                timestamp: key.timestamp,
+                width: self.config.bucket_interval_for(key.namespace()),
+                name: key.metric_name.clone(),
+                value: entry.value.clone(),
+                tags: key.tags.clone(),
+                metadata,
+            });
+        }
+
+        buckets
+    }
+
     /// Pop and return the partitions with buckets that are eligible for flushing out according to
     /// bucket interval.
     ///
@@ -618,8 +733,9 @@ impl Aggregator {
             timer(MetricTimers::BucketsScanDuration),
             aggregator = &self.name,
             {
-                let bucket_interval = self.config.bucket_interval;
+                let config = &self.config;
                 let cost_tracker = &mut self.cost_tracker;
+                let namespace_cost = &mut self.namespace_cost;
                 self.buckets.retain(|key, entry| {
                     if force || entry.elapsed() {
                         // Take the value and leave a placeholder behind. It'll be removed right after.
@@ -627,6 +743,9 @@ impl Aggregator {
                         let metadata = mem::take(&mut entry.metadata);
                         cost_tracker.subtract_cost(key.project_key, key.cost());
                         cost_tracker.subtract_cost(key.project_key, value.cost());
+                        if let Some(cost) = namespace_cost.get_mut(&key.namespace()) {
+                            *cost = cost.saturating_sub(key.cost() + value.cost());
+                        }
 
                         let (bucket_count, item_count) = stats
                             .entry((value.ty(), key.namespace()))
@@ -636,7 +755,7 @@ impl Aggregator {
 
                         let bucket = Bucket {
                             timestamp: key.timestamp,
-                            width: bucket_interval,
+                            width: config.bucket_interval_for(key.namespace()),
                             name: key.metric_name.clone(),
                             value,
                             tags: key.tags.clone(),
@@ -644,7 +763,7 @@ impl Aggregator {
                         };
 
                         partitions
-                            .entry(self.config.flush_partitions.map(|p| key.partition_key(p)))
+                            .entry(config.flush_partitions.map(|p| key.partition_key(p)))
                             .or_insert_with(HashMap::new)
                             .entry(key.project_key)
                             .or_insert_with(Vec::new)
@@ -711,6 +830,7 @@ impl Aggregator {
             extracted_from_indexed: bucket.metadata.extracted_from_indexed,
         };
         let key = validate_bucket_key(key, &self.config)?;
+        let key = self.config.rollup_key(key);
 
         // XXX: This is not a great implementation of cost enforcement.
         //
@@ -738,6 +858,11 @@ impl Aggregator {
         // items before they are parsed, as we can be sure that the new metric bucket will be
         // rejected in the aggregator regardless of whether it is merged into existing buckets,
         // whether it is just a counter, etc.
+        let namespace = key.namespace();
+        if self.namespace_cost_exceeded(namespace) {
+            return Err(AggregateMetricsErrorKind::NamespaceLimitExceeded(namespace).into());
+        }
+
         self.cost_tracker.check_limits_exceeded(
             project_key,
             max_total_bucket_bytes,
@@ -775,6 +900,13 @@ impl Aggregator {
         }
 
         self.cost_tracker.add_cost(project_key, added_cost);
+        if self
+            .config
+            .max_namespace_bucket_bytes
+            .contains_key(&namespace)
+        {
+            *self.namespace_cost.entry(namespace).or_insert(0) += added_cost;
+        }
 
         Ok(())
     }
@@ -793,6 +925,15 @@ impl Aggregator {
                 match &error.kind {
                     // Ignore invalid timestamp errors.
                     AggregateMetricsErrorKind::InvalidTimestamp(_) => {}
+                    // Namespace budgets are expected to be exceeded under load and are reported
+                    // as back pressure instead.
+                    AggregateMetricsErrorKind::NamespaceLimitExceeded(namespace) => {
+                        relay_statsd::metric!(
+                            counter(MetricCounters::NamespaceLimitExceeded) += 1,
+                            aggregator = &self.name,
+                            namespace = namespace.as_str(),
+                        );
+                    }
                     _other => {
                         relay_log::error!(
                             tags.aggregator = self.name,
@@ -914,6 +1055,8 @@ mod tests {
             max_tag_key_length: 200,
             max_tag_value_length: 200,
             max_project_key_bucket_bytes: None,
+            max_namespace_bucket_bytes: BTreeMap::new(),
+            rollups: BTreeMap::new(),
             flush_batching: FlushBatching::default(),
             flush_partitions: None,
         }
@@ -1493,6 +1636,122 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_aggregator_cost_enforcement_namespace() {
+        relay_test::setup();
+        let mut config = test_config();
+        config
+            .max_namespace_bucket_bytes
+            .insert(MetricNamespace::Custom, 1);
+
+        let mut custom = some_bucket(None);
+        custom.name = "c:custom/foo".into();
+
+        let mut aggregator: Aggregator = Aggregator::new(config);
+        let project_key = ProjectKey::parse("a94ae32be2584e0bbd7a4cbb95971fed").unwrap();
+
+        aggregator.merge(project_key, custom.clone(), None).unwrap();
+        assert!(aggregator.namespace_cost_exceeded(MetricNamespace::Custom));
+        assert_eq!(
+            aggregator
+                .merge(project_key, custom, None)
+                .unwrap_err()
+                .kind,
+            AggregateMetricsErrorKind::NamespaceLimitExceeded(MetricNamespace::Custom)
+        );
+
+        // Other namespaces are not affected by the exceeded budget.
+        assert!(!aggregator.namespace_cost_exceeded(MetricNamespace::Transactions));
+        aggregator
+            .merge(project_key, some_bucket(None), None)
+            .unwrap();
+
+        // Flushing releases the budget.
+        aggregator.pop_flush_buckets(true);
+        assert!(!aggregator.namespace_cost_exceeded(MetricNamespace::Custom));
+    }
+
+    #[test]
+    fn test_aggregator_rollup() {
+        let mut config = test_config();
+        config.bucket_interval = 10;
+        config.rollups.insert(
+            MetricNamespace::Custom,
+            RollupConfig {
+                bucket_interval: 60,
+                drop_tags: BTreeSet::from(["drop".to_owned()]),
+            },
+        );
+
+        let project_key = ProjectKey::parse("a94ae32be2584e0bbd7a4cbb95971fee").unwrap();
+        let mut aggregator: Aggregator = Aggregator::new(config);
+
+        for (ts, tag) in [(999994711, "a"), (999994745, "b")] {
+            let mut custom = some_bucket(Some(UnixTimestamp::from_secs(ts)));
+            custom.name = "c:custom/foo@none".into();
+            custom.tags = BTreeMap::from([
+                ("drop".to_owned(), tag.to_owned()),
+                ("keep".to_owned(), "value".to_owned()),
+            ]);
+            aggregator.merge(project_key, custom, None).unwrap();
+
+            // Namespaces without a rollup keep their original granularity.
+            let transaction = some_bucket(Some(UnixTimestamp::from_secs(ts)));
+            aggregator.merge(project_key, transaction, None).unwrap();
+        }
+
+        let mut buckets = aggregator.into_buckets();
+        buckets.sort_by_key(|b| (b.name.clone(), b.timestamp));
+        assert_eq!(buckets.len(), 3);
+
+        // Both custom buckets fall into the same 60 second window and lose the dropped tag.
+        let custom = &buckets[0];
+        assert_eq!(&*custom.name, "c:custom/foo@none");
+        assert_eq!(custom.timestamp, UnixTimestamp::from_secs(999994680));
+        assert_eq!(custom.width, 60);
+        assert_eq!(
+            custom.tags,
+            BTreeMap::from([("keep".to_owned(), "value".to_owned())])
+        );
+        assert_eq!(custom.value, BucketValue::counter(84.into()));
+
+        let timestamps: Vec<_> = buckets[1..]
+            .iter()
+            .map(|b| (b.timestamp, b.width))
+            .collect();
+        assert_eq!(
+            timestamps,
+            vec![
+                (UnixTimestamp::from_secs(999994710), 10),
+                (UnixTimestamp::from_secs(999994740), 10),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rollup_interval_alignment() {
+        let mut config = test_config();
+        config.bucket_interval = 10;
+        config.rollups.insert(
+            MetricNamespace::Spans,
+            RollupConfig {
+                bucket_interval: 25,
+                drop_tags: BTreeSet::new(),
+            },
+        );
+        config.rollups.insert(
+            MetricNamespace::Custom,
+            RollupConfig {
+                bucket_interval: 5,
+                drop_tags: BTreeSet::new(),
+            },
+        );
+
+        assert_eq!(config.bucket_interval_for(MetricNamespace::Spans), 30);
+        assert_eq!(config.bucket_interval_for(MetricNamespace::Custom), 10);
+        assert_eq!(config.bucket_interval_for(MetricNamespace::Sessions), 10);
+    }
+
     #[test]
     fn test_parse_flush_batching() {
         let json = r#"{"shift_key": "partition"}"#;
@@ -1582,4 +1841,61 @@ mod tests {
 
         assert_eq!(flush_time_1, flush_time_2);
     }
+
+    #[test]
+    fn test_aggregator_snapshot_restore() {
+        let project_key = ProjectKey::parse("a94ae32be2584e0bbd7a4cbb95971fee").unwrap();
+        let mut aggregator = Aggregator::new(test_config());
+
+        let mut bucket = some_bucket(None);
+        bucket.metadata.extracted_from_indexed = true;
+        aggregator.merge(project_key, bucket, None).unwrap();
+        aggregator
+            .merge(project_key, some_bucket(None), None)
+            .unwrap();
+
+        let snapshot = aggregator.snapshot();
+        assert_eq!(snapshot[&project_key].len(), 2);
+        // Taking a snapshot does not remove buckets.
+        assert_eq!(aggregator.bucket_count(), 2);
+
+        let mut restored = Aggregator::new(test_config());
+        for (project_key, buckets) in snapshot {
+            restored.merge_all(project_key, buckets, None);
+        }
+
+        let mut expected = aggregator.snapshot().remove(&project_key).unwrap();
+        let mut actual = restored.snapshot().remove(&project_key).unwrap();
+        expected.sort_by_key(|b| b.metadata.extracted_from_indexed);
+        actual.sort_by_key(|b| b.metadata.extracted_from_indexed);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_aggregator_restore_outdated_snapshot() {
+        let project_key = ProjectKey::parse("a94ae32be2584e0bbd7a4cbb95971fee").unwrap();
+        let config = AggregatorConfig {
+            max_secs_in_past: 3600,
+            ..test_config()
+        };
+
+        let mut aggregator = Aggregator::new(config.clone());
+        aggregator
+            .merge(project_key, some_bucket(Some(UnixTimestamp::now())), None)
+            .unwrap();
+        let mut snapshot = aggregator.snapshot();
+
+        // A bucket that has become too old since the snapshot was taken.
+        snapshot
+            .get_mut(&project_key)
+            .unwrap()
+            .push(some_bucket(Some(UnixTimestamp::from_secs(999994711))));
+
+        let mut restored = Aggregator::new(config);
+        for (project_key, buckets) in snapshot {
+            restored.merge_all(project_key, buckets, None);
+        }
+
+        assert_eq!(restored.bucket_count(), 1);
+    }
 }
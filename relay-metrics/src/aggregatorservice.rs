@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::time::Duration;
 
 use relay_base_schema::project::ProjectKey;
@@ -8,9 +8,12 @@ use relay_system::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::aggregator::{self, AggregatorConfig, FlushBatching};
+use crate::aggregator::{self, AggregatorConfig, FlushBatching, RollupConfig};
+use crate::backpressure::{BackpressureSlot, NamespaceBackpressure};
 use crate::bucket::Bucket;
+use crate::snapshot::{self, SnapshotConfig};
 use crate::statsd::{MetricCounters, MetricHistograms, MetricTimers};
+use crate::MetricNamespace;
 
 /// Parameters used by the [`AggregatorService`].
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -69,6 +72,23 @@ pub struct AggregatorServiceConfig {
     /// Defaults to `None`, i.e. no limit.
     pub max_project_key_bucket_bytes: Option<usize>,
 
+    /// Maximum amount of bytes used for metrics aggregation per metric namespace.
+    ///
+    /// Similar measuring technique to `max_total_bucket_bytes`, but enforced per namespace. A
+    /// namespace that exceeds its budget is rejected and reported via [`NamespaceBackpressure`],
+    /// while other namespaces continue to be accepted.
+    ///
+    /// Defaults to an empty map, i.e. no limits.
+    pub max_namespace_bucket_bytes: BTreeMap<MetricNamespace, usize>,
+
+    /// Rollups applied to buckets per metric namespace before they are flushed.
+    ///
+    /// Buckets of a namespace with a [`RollupConfig`] are aggregated into a coarser time interval
+    /// and lose the configured tags, which reduces the volume sent upstream.
+    ///
+    /// Defaults to an empty map, i.e. no rollups.
+    pub rollups: BTreeMap<MetricNamespace, RollupConfig>,
+
     // TODO(dav1dde): move these config values to a better spot
     /// The approximate maximum number of bytes submitted within one flush cycle.
     ///
@@ -114,6 +134,8 @@ impl Default for AggregatorServiceConfig {
             max_tag_key_length: 200,
             max_tag_value_length: 200,
             max_project_key_bucket_bytes: None,
+            max_namespace_bucket_bytes: BTreeMap::new(),
+            rollups: BTreeMap::new(),
             max_flush_bytes: 5_000_000, // 5 MB
             flush_partitions: None,
             flush_batching: FlushBatching::Project,
@@ -133,6 +155,8 @@ impl From<&AggregatorServiceConfig> for AggregatorConfig {
             max_tag_key_length: value.max_tag_key_length,
             max_tag_value_length: value.max_tag_value_length,
             max_project_key_bucket_bytes: value.max_project_key_bucket_bytes,
+            max_namespace_bucket_bytes: value.max_namespace_bucket_bytes.clone(),
+            rollups: value.rollups.clone(),
             flush_partitions: value.flush_partitions,
             flush_batching: value.flush_batching,
         }
@@ -234,6 +258,13 @@ pub struct AggregatorService {
     receiver: Option<Recipient<FlushBuckets, NoResponse>>,
     max_total_bucket_bytes: Option<usize>,
     flush_interval_ms: u64,
+    snapshot: Option<SnapshotConfig>,
+    /// The background task that last wrote the snapshot file.
+    ///
+    /// Removing the snapshot awaits this task, so that a write can never complete after the
+    /// snapshot has been removed.
+    snapshot_task: Option<tokio::task::JoinHandle<()>>,
+    backpressure: BackpressureSlot,
 }
 
 impl AggregatorService {
@@ -245,21 +276,175 @@ impl AggregatorService {
         config: AggregatorServiceConfig,
         receiver: Option<Recipient<FlushBuckets, NoResponse>>,
     ) -> Self {
-        Self::named("default".to_owned(), config, receiver)
+        Self::named(
+            "default".to_owned(),
+            config,
+            receiver,
+            None,
+            NamespaceBackpressure::default(),
+        )
     }
 
-    /// Like [`Self::new`], but with a provided name.
+    /// Like [`Self::new`], but with a provided name, optional snapshot configuration, and a shared
+    /// back pressure signal.
+    ///
+    /// If a [`SnapshotConfig`] is given, buckets from a previous snapshot of this aggregator are
+    /// restored immediately, and the aggregator periodically writes new snapshots.
     pub(crate) fn named(
         name: String,
         config: AggregatorServiceConfig,
         receiver: Option<Recipient<FlushBuckets, NoResponse>>,
+        snapshot: Option<SnapshotConfig>,
+        backpressure: NamespaceBackpressure,
     ) -> Self {
-        Self {
+        let mut service = Self {
             receiver,
             state: AggregatorState::Running,
             max_total_bucket_bytes: config.max_total_bucket_bytes,
             aggregator: aggregator::Aggregator::named(name, AggregatorConfig::from(&config)),
             flush_interval_ms: config.flush_interval_ms,
+            snapshot,
+            snapshot_task: None,
+            backpressure: backpressure.slot(),
+        };
+
+        service.restore_snapshot();
+        service.update_backpressure();
+        service
+    }
+
+    /// Publishes the budget state of all namespaces with a configured budget.
+    fn update_backpressure(&self) {
+        for namespace in self.aggregator.budgeted_namespaces() {
+            let exceeded = self.aggregator.namespace_cost_exceeded(namespace);
+            self.backpressure.set(namespace, exceeded);
+        }
+    }
+
+    /// Returns the path of this aggregator's snapshot file, if snapshots are enabled.
+    fn snapshot_path(&self) -> Option<std::path::PathBuf> {
+        let config = self.snapshot.as_ref()?;
+        Some(config.path(self.aggregator.name()))
+    }
+
+    /// Merges all buckets from a previously written snapshot into the aggregator.
+    ///
+    /// Buckets that are no longer within the valid timestamp range of the aggregator are dropped.
+    /// The snapshot file is kept until it is overwritten by the next snapshot, or removed after
+    /// the final flush during shutdown.
+    fn restore_snapshot(&mut self) {
+        let Some(path) = self.snapshot_path() else {
+            return;
+        };
+
+        let buckets = match snapshot::read(&path) {
+            Ok(buckets) => buckets,
+            Err(error) => {
+                relay_log::error!(
+                    error = &error as &dyn std::error::Error,
+                    tags.aggregator = self.aggregator.name(),
+                    "failed to restore metrics snapshot"
+                );
+                return;
+            }
+        };
+
+        let mut restored = 0i64;
+        let mut discarded = 0;
+        for (project_key, buckets) in buckets {
+            for bucket in buckets {
+                match self
+                    .aggregator
+                    .merge(project_key, bucket, self.max_total_bucket_bytes)
+                {
+                    Ok(()) => restored += 1,
+                    Err(_) => discarded += 1,
+                }
+            }
+        }
+
+        relay_log::info!(
+            tags.aggregator = self.aggregator.name(),
+            "restored {restored} buckets from metrics snapshot, discarded {discarded}"
+        );
+        relay_statsd::metric!(
+            counter(MetricCounters::BucketsRestored) += restored,
+            aggregator = self.aggregator.name(),
+        );
+    }
+
+    /// Writes all buckets currently held by the aggregator to the snapshot file.
+    ///
+    /// Serialization happens synchronously, while the file is written in the background. If the
+    /// previous snapshot is still being written, this snapshot is skipped.
+    fn write_snapshot(&mut self) {
+        let Some(path) = self.snapshot_path() else {
+            return;
+        };
+
+        if self
+            .snapshot_task
+            .as_ref()
+            .is_some_and(|task| !task.is_finished())
+        {
+            relay_log::debug!(
+                tags.aggregator = self.aggregator.name(),
+                "skipping metrics snapshot, previous snapshot still in progress"
+            );
+            return;
+        }
+
+        let data = relay_statsd::metric!(
+            timer(MetricTimers::SnapshotDuration),
+            aggregator = self.aggregator.name(),
+            { snapshot::serialize(self.aggregator.snapshot()) }
+        );
+
+        let data = match data {
+            Ok(data) => data,
+            Err(error) => {
+                relay_log::error!(
+                    error = &error as &dyn std::error::Error,
+                    tags.aggregator = self.aggregator.name(),
+                    "failed to serialize metrics snapshot"
+                );
+                return;
+            }
+        };
+
+        let name = self.aggregator.name().to_owned();
+        self.snapshot_task = Some(tokio::spawn(async move {
+            if let Err(error) = snapshot::write(path, data).await {
+                relay_log::error!(
+                    error = &error as &dyn std::error::Error,
+                    tags.aggregator = name,
+                    "failed to write metrics snapshot"
+                );
+            }
+        }));
+    }
+
+    /// Removes the snapshot file after any pending snapshot write has completed.
+    ///
+    /// This disables snapshots for the remaining lifetime of the aggregator, so the file is only
+    /// removed once.
+    async fn remove_snapshot(&mut self) {
+        let Some(path) = self.snapshot_path() else {
+            return;
+        };
+        self.snapshot = None;
+
+        // A write that completes after the removal would resurrect the snapshot.
+        if let Some(pending) = self.snapshot_task.take() {
+            let _ = pending.await;
+        }
+
+        if let Err(error) = snapshot::remove(&path) {
+            relay_log::error!(
+                error = &error as &dyn std::error::Error,
+                tags.aggregator = self.aggregator.name(),
+                "failed to remove metrics snapshot"
+            );
         }
     }
 
@@ -284,6 +469,8 @@ impl AggregatorService {
             return;
         }
 
+        self.update_backpressure();
+
         let partitions_count = partitions.len() as u64;
         relay_log::trace!("flushing {} partitions to receiver", partitions_count);
         relay_statsd::metric!(
@@ -326,6 +513,7 @@ impl AggregatorService {
         } = msg;
         self.aggregator
             .merge_all(project_key, buckets, self.max_total_bucket_bytes);
+        self.update_backpressure();
     }
 
     fn handle_message(&mut self, message: Aggregator) {
@@ -346,6 +534,13 @@ impl AggregatorService {
         )
     }
 
+    /// Returns `true` if snapshots are enabled and the aggregator is not shutting down.
+    ///
+    /// During shutdown, all buckets are flushed and the snapshot is removed afterwards.
+    fn can_snapshot(&self) -> bool {
+        self.snapshot.is_some() && matches!(self.state, AggregatorState::Running)
+    }
+
     fn handle_shutdown(&mut self, message: Shutdown) {
         if message.timeout.is_some() {
             self.state = AggregatorState::ShuttingDown;
@@ -361,13 +556,31 @@ impl Service for AggregatorService {
             let mut ticker = tokio::time::interval(Duration::from_millis(self.flush_interval_ms));
             let mut shutdown = Controller::shutdown_handle();
 
+            // The ticker is always created, but only polled if snapshots are enabled.
+            let snapshot_interval = match self.snapshot {
+                Some(ref config) => config.interval.max(Duration::from_secs(1)),
+                None => Duration::from_secs(60),
+            };
+            let mut snapshot_ticker = tokio::time::interval_at(
+                tokio::time::Instant::now() + snapshot_interval,
+                snapshot_interval,
+            );
+
             // Note that currently this loop never exits and will run till the tokio runtime shuts
             // down. This is about to change with the refactoring for the shutdown process.
             loop {
                 tokio::select! {
                     biased;
 
-                    _ = ticker.tick() => self.try_flush(),
+                    _ = ticker.tick() => {
+                        self.try_flush();
+                        // All buckets are flushed during shutdown, restoring the last snapshot on
+                        // the next start would duplicate them.
+                        if matches!(self.state, AggregatorState::ShuttingDown) {
+                            self.remove_snapshot().await;
+                        }
+                    }
+                    _ = snapshot_ticker.tick(), if self.can_snapshot() => self.write_snapshot(),
                     Some(message) = rx.recv() => self.handle_message(message),
                     shutdown = shutdown.notified() => self.handle_shutdown(shutdown),
 
@@ -529,4 +742,41 @@ mod tests {
         // receiver must have 1 bucket flushed
         assert_eq!(receiver.bucket_count(), 1);
     }
+
+    #[tokio::test]
+    async fn test_snapshot_removed_after_final_flush() {
+        relay_test::setup();
+
+        let dir = tempfile::tempdir().unwrap();
+        let snapshot = SnapshotConfig {
+            directory: dir.path().to_owned(),
+            interval: Duration::from_secs(60),
+        };
+        let path = snapshot.path("default");
+
+        let mut bucket = some_bucket();
+        bucket.timestamp = UnixTimestamp::now();
+        let project_key = ProjectKey::parse("a94ae32be2584e0bbd7a4cbb95971fee").unwrap();
+        let data = snapshot::serialize(HashMap::from([(project_key, vec![bucket])])).unwrap();
+        snapshot::write(path.clone(), data).await.unwrap();
+
+        let mut service = AggregatorService::named(
+            "default".to_owned(),
+            AggregatorServiceConfig::default(),
+            None,
+            Some(snapshot),
+            NamespaceBackpressure::default(),
+        );
+
+        // The snapshot is kept until the restored buckets have been flushed.
+        assert_eq!(service.aggregator.bucket_count(), 1);
+        assert!(path.exists());
+
+        service.state = AggregatorState::ShuttingDown;
+        service.try_flush();
+        service.remove_snapshot().await;
+
+        assert_eq!(service.aggregator.bucket_count(), 0);
+        assert!(!path.exists());
+    }
 }
@@ -0,0 +1,144 @@
+use std::fmt;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, RwLock};
+
+use crate::MetricNamespace;
+
+/// Shared signal for metric namespaces that have exceeded their aggregation budget.
+///
+/// Aggregators update this signal whenever the cost of a namespace with a configured
+/// [`max_namespace_bucket_bytes`](crate::AggregatorServiceConfig::max_namespace_bucket_bytes)
+/// budget changes. Consumers can use it to reject metrics of the affected namespaces early and to
+/// surface back pressure to load balancers, without affecting other namespaces.
+///
+/// Every aggregator publishes its state into a separate [`BackpressureSlot`]. A namespace is
+/// exceeded as long as at least one of the aggregators reports it as exceeded.
+///
+/// Cloning the signal is cheap, all clones share the same state.
+#[derive(Clone, Default)]
+pub struct NamespaceBackpressure(Arc<RwLock<Vec<Arc<AtomicU8>>>>);
+
+impl NamespaceBackpressure {
+    /// Returns `true` if the given namespace currently exceeds its budget.
+    pub fn is_exceeded(&self, namespace: MetricNamespace) -> bool {
+        self.load() & mask(namespace) != 0
+    }
+
+    /// Returns all namespaces that currently exceed their budget.
+    pub fn exceeded(&self) -> Vec<MetricNamespace> {
+        let state = self.load();
+        MetricNamespace::all()
+            .into_iter()
+            .filter(|namespace| state & mask(*namespace) != 0)
+            .collect()
+    }
+
+    /// Registers a new slot for an aggregator to publish its state into.
+    pub(crate) fn slot(&self) -> BackpressureSlot {
+        let slot = Arc::new(AtomicU8::new(0));
+        self.0
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Arc::clone(&slot));
+        BackpressureSlot(slot)
+    }
+
+    /// Combines the state of all slots.
+    fn load(&self) -> u8 {
+        self.0
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .fold(0, |state, slot| state | slot.load(Ordering::Relaxed))
+    }
+}
+
+impl fmt::Debug for NamespaceBackpressure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("NamespaceBackpressure")
+            .field(&self.exceeded())
+            .finish()
+    }
+}
+
+/// The back pressure state of a single aggregator within a [`NamespaceBackpressure`] signal.
+///
+/// Dropping the slot clears its state.
+#[derive(Debug)]
+pub(crate) struct BackpressureSlot(Arc<AtomicU8>);
+
+impl BackpressureSlot {
+    /// Marks the given namespace as exceeding or not exceeding its budget.
+    pub fn set(&self, namespace: MetricNamespace, exceeded: bool) {
+        let mask = mask(namespace);
+        if exceeded {
+            self.0.fetch_or(mask, Ordering::Relaxed);
+        } else {
+            self.0.fetch_and(!mask, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Drop for BackpressureSlot {
+    fn drop(&mut self) {
+        self.0.store(0, Ordering::Relaxed);
+    }
+}
+
+// Every namespace needs its own bit in the `u8` state.
+const _: () = assert!(MetricNamespace::all().len() <= u8::BITS as usize);
+
+fn mask(namespace: MetricNamespace) -> u8 {
+    let index = MetricNamespace::all()
+        .iter()
+        .position(|ns| *ns == namespace)
+        .unwrap_or_default();
+
+    1 << index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backpressure_shared() {
+        let backpressure = NamespaceBackpressure::default();
+        let slot = backpressure.clone().slot();
+        assert!(backpressure.exceeded().is_empty());
+
+        slot.set(MetricNamespace::Custom, true);
+        slot.set(MetricNamespace::Spans, true);
+        assert!(backpressure.is_exceeded(MetricNamespace::Custom));
+        assert!(!backpressure.is_exceeded(MetricNamespace::Transactions));
+        assert_eq!(
+            backpressure.exceeded(),
+            vec![MetricNamespace::Spans, MetricNamespace::Custom]
+        );
+
+        slot.set(MetricNamespace::Custom, false);
+        assert_eq!(backpressure.exceeded(), vec![MetricNamespace::Spans]);
+    }
+
+    #[test]
+    fn test_backpressure_aggregators_disagree() {
+        let backpressure = NamespaceBackpressure::default();
+        let first = backpressure.slot();
+        let second = backpressure.slot();
+
+        first.set(MetricNamespace::Custom, true);
+        second.set(MetricNamespace::Custom, false);
+        assert!(backpressure.is_exceeded(MetricNamespace::Custom));
+
+        second.set(MetricNamespace::Custom, true);
+        first.set(MetricNamespace::Custom, false);
+        assert!(backpressure.is_exceeded(MetricNamespace::Custom));
+
+        second.set(MetricNamespace::Custom, false);
+        assert!(!backpressure.is_exceeded(MetricNamespace::Custom));
+
+        first.set(MetricNamespace::Spans, true);
+        drop(first);
+        assert!(backpressure.exceeded().is_empty());
+    }
+}
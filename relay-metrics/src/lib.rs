@@ -73,14 +73,17 @@ pub mod cogs;
 pub mod meta;
 
 mod aggregatorservice;
+mod backpressure;
 mod bucket;
 mod finite;
 mod protocol;
 mod router;
+mod snapshot;
 mod statsd;
 mod view;
 
 pub use aggregatorservice::*;
+pub use backpressure::NamespaceBackpressure;
 pub use bucket::*;
 pub use finite::*;
 #[cfg(feature = "redis")]
@@ -88,4 +91,5 @@ pub use meta::RedisMetricMetaStore;
 pub use meta::{MetaAggregator, MetricMeta};
 pub use protocol::*;
 pub use router::*;
+pub use snapshot::SnapshotConfig;
 pub use view::*;
@@ -6,7 +6,10 @@ use relay_system::{Addr, NoResponse, Recipient, Service};
 use serde::{Deserialize, Serialize};
 
 use crate::aggregatorservice::{AggregatorService, FlushBuckets};
-use crate::{AcceptsMetrics, Aggregator, AggregatorServiceConfig, MergeBuckets, MetricNamespace};
+use crate::{
+    AcceptsMetrics, Aggregator, AggregatorServiceConfig, MergeBuckets, MetricNamespace,
+    NamespaceBackpressure, SnapshotConfig,
+};
 
 /// Contains an [`AggregatorServiceConfig`] for a specific scope.
 ///
@@ -84,21 +87,37 @@ pub struct RouterService {
     default_config: AggregatorServiceConfig,
     secondary_configs: Vec<ScopedAggregatorConfig>,
     receiver: Option<Recipient<FlushBuckets, NoResponse>>,
+    snapshot: Option<SnapshotConfig>,
+    backpressure: NamespaceBackpressure,
 }
 
 impl RouterService {
     /// Create a new router service.
+    ///
+    /// If `snapshot` is set, all aggregators persist their state to the configured directory and
+    /// restore it on startup.
     pub fn new(
         default_config: AggregatorServiceConfig,
         secondary_configs: Vec<ScopedAggregatorConfig>,
         receiver: Option<Recipient<FlushBuckets, NoResponse>>,
+        snapshot: Option<SnapshotConfig>,
     ) -> Self {
         Self {
             default_config,
             secondary_configs,
             receiver,
+            snapshot,
+            backpressure: NamespaceBackpressure::default(),
         }
     }
+
+    /// Returns the back pressure signal shared by all aggregators of this router.
+    ///
+    /// The signal reports namespaces that exceed their configured budget in any of the
+    /// aggregators. See [`NamespaceBackpressure`].
+    pub fn backpressure(&self) -> NamespaceBackpressure {
+        self.backpressure.clone()
+    }
 }
 
 impl Service for RouterService {
@@ -137,18 +156,35 @@ impl StartedRouter {
             default_config,
             secondary_configs,
             receiver,
+            snapshot,
+            backpressure,
         } = router;
 
         let secondary = secondary_configs
             .into_iter()
             .map(|c| {
-                let addr = AggregatorService::named(c.name, c.config, receiver.clone()).start();
+                let addr = AggregatorService::named(
+                    c.name,
+                    c.config,
+                    receiver.clone(),
+                    snapshot.clone(),
+                    backpressure.clone(),
+                )
+                .start();
                 (c.condition, addr)
             })
             .collect();
 
+        let default = AggregatorService::named(
+            "default".to_owned(),
+            default_config,
+            receiver,
+            snapshot,
+            backpressure,
+        );
+
         Self {
-            default: AggregatorService::new(default_config, receiver).start(),
+            default: default.start(),
             secondary,
         }
     }
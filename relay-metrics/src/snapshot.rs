@@ -0,0 +1,198 @@
+//! Persistence of the aggregator state across restarts.
+//!
+//! The [`AggregatorService`](crate::AggregatorService) periodically writes all buckets it holds to
+//! a snapshot file and restores them on startup. This limits the loss of metrics if Relay crashes
+//! or its graceful shutdown times out before all buckets could be flushed.
+//!
+//! Snapshots provide at-least-once semantics: buckets flushed after the last snapshot was written
+//! are restored again if Relay does not shut down gracefully. A restored snapshot is kept until it
+//! is overwritten by the next snapshot or removed after the final flush during shutdown, so that
+//! restored buckets survive another crash right after startup.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use relay_base_schema::project::ProjectKey;
+use serde::{Deserialize, Serialize};
+
+use crate::bucket::Bucket;
+
+/// Configuration for periodic snapshots of the aggregator state.
+#[derive(Clone, Debug)]
+pub struct SnapshotConfig {
+    /// The directory in which snapshot files are stored.
+    ///
+    /// Every aggregator writes to its own file within this directory, named after the aggregator.
+    pub directory: PathBuf,
+    /// The interval in which snapshots are written.
+    pub interval: Duration,
+}
+
+impl SnapshotConfig {
+    /// Returns the path of the snapshot file for the aggregator with the given name.
+    pub fn path(&self, aggregator: &str) -> PathBuf {
+        self.directory
+            .join(format!("metrics-{aggregator}.snapshot"))
+    }
+}
+
+/// A bucket in a snapshot file, together with the parts of its aggregation key that are not part
+/// of the serialized [`Bucket`].
+#[derive(Debug, Deserialize, Serialize)]
+struct SnapshotBucket {
+    project_key: ProjectKey,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    extracted_from_indexed: bool,
+    #[serde(flatten)]
+    bucket: Bucket,
+}
+
+/// Serializes buckets grouped by project key into the snapshot format.
+///
+/// The snapshot contains one JSON-encoded bucket per line.
+pub(crate) fn serialize(buckets: HashMap<ProjectKey, Vec<Bucket>>) -> serde_json::Result<Vec<u8>> {
+    let mut output = Vec::new();
+
+    for (project_key, buckets) in buckets {
+        for bucket in buckets {
+            let entry = SnapshotBucket {
+                project_key,
+                extracted_from_indexed: bucket.metadata.extracted_from_indexed,
+                bucket,
+            };
+
+            serde_json::to_writer(&mut output, &entry)?;
+            output.push(b'\n');
+        }
+    }
+
+    Ok(output)
+}
+
+/// Parses a snapshot created by [`serialize`].
+///
+/// Lines that cannot be parsed are skipped, so that a partially corrupted snapshot still restores
+/// all valid buckets.
+pub(crate) fn deserialize(data: &[u8]) -> HashMap<ProjectKey, Vec<Bucket>> {
+    let mut buckets = HashMap::<_, Vec<_>>::new();
+
+    for line in data.split(|&b| b == b'\n') {
+        if line.is_empty() {
+            continue;
+        }
+
+        match serde_json::from_slice::<SnapshotBucket>(line) {
+            Ok(entry) => {
+                let mut bucket = entry.bucket;
+                bucket.metadata.extracted_from_indexed = entry.extracted_from_indexed;
+                buckets.entry(entry.project_key).or_default().push(bucket);
+            }
+            Err(error) => {
+                relay_log::debug!(
+                    error = &error as &dyn std::error::Error,
+                    "skipping invalid bucket in metrics snapshot"
+                );
+            }
+        }
+    }
+
+    buckets
+}
+
+/// Atomically replaces the snapshot at `path` with the given contents.
+///
+/// The data is written to a temporary file first, which is then moved to the final location. This
+/// ensures that a crash while writing never leaves a truncated snapshot behind.
+pub(crate) async fn write(path: PathBuf, data: Vec<u8>) -> io::Result<()> {
+    let tmp_path = path.with_extension("snapshot.tmp");
+    tokio::fs::write(&tmp_path, data).await?;
+    tokio::fs::rename(&tmp_path, &path).await
+}
+
+/// Reads the snapshot at `path`.
+///
+/// The snapshot is left in place. Returns an empty map if no snapshot exists.
+pub(crate) fn read(path: &Path) -> io::Result<HashMap<ProjectKey, Vec<Bucket>>> {
+    match std::fs::read(path) {
+        Ok(data) => Ok(deserialize(&data)),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(HashMap::new()),
+        Err(error) => Err(error),
+    }
+}
+
+/// Removes the snapshot at `path` if it exists.
+pub(crate) fn remove(path: &Path) -> io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use relay_common::time::UnixTimestamp;
+
+    use crate::{BucketMetadata, BucketValue};
+
+    use super::*;
+
+    fn bucket(extracted_from_indexed: bool) -> Bucket {
+        let timestamp = UnixTimestamp::from_secs(999994710);
+        let mut metadata = BucketMetadata::new(timestamp);
+        metadata.extracted_from_indexed = extracted_from_indexed;
+
+        Bucket {
+            timestamp,
+            width: 10,
+            name: "c:transactions/foo@none".into(),
+            value: BucketValue::counter(42.into()),
+            tags: BTreeMap::from([("tag".to_owned(), "value".to_owned())]),
+            metadata,
+        }
+    }
+
+    #[test]
+    fn test_snapshot_roundtrip() {
+        let project_key = ProjectKey::parse("a94ae32be2584e0bbd7a4cbb95971fee").unwrap();
+        let buckets = HashMap::from([(project_key, vec![bucket(false), bucket(true)])]);
+
+        let data = serialize(buckets.clone()).unwrap();
+        assert_eq!(deserialize(&data), buckets);
+    }
+
+    #[test]
+    fn test_snapshot_skips_invalid_lines() {
+        let project_key = ProjectKey::parse("a94ae32be2584e0bbd7a4cbb95971fee").unwrap();
+        let buckets = HashMap::from([(project_key, vec![bucket(false)])]);
+
+        let mut data = b"{\"invalid\":true}\n".to_vec();
+        data.extend(serialize(buckets.clone()).unwrap());
+        data.extend(b"{\"project_key\":\"a94ae32b"); // truncated line
+
+        assert_eq!(deserialize(&data), buckets);
+    }
+
+    #[test]
+    fn test_snapshot_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = SnapshotConfig {
+            directory: dir.path().to_owned(),
+            interval: Duration::from_secs(1),
+        };
+        let path = config.path("default");
+
+        // A missing snapshot restores nothing.
+        assert!(read(&path).unwrap().is_empty());
+
+        let project_key = ProjectKey::parse("a94ae32be2584e0bbd7a4cbb95971fee").unwrap();
+        let buckets = HashMap::from([(project_key, vec![bucket(false)])]);
+        std::fs::write(&path, serialize(buckets.clone()).unwrap()).unwrap();
+
+        assert_eq!(read(&path).unwrap(), buckets);
+        assert!(path.exists());
+    }
+}
@@ -48,6 +48,19 @@ pub enum MetricCounters {
     /// This metric is tagged with:
     ///  - `aggregator`: The name of the metrics aggregator (usually `"default"`).
     BucketsDropped,
+
+    /// Incremented for every bucket restored from a snapshot on startup.
+    ///
+    /// This metric is tagged with:
+    ///  - `aggregator`: The name of the metrics aggregator (usually `"default"`).
+    BucketsRestored,
+
+    /// Incremented for every bucket rejected because its namespace exceeded its budget.
+    ///
+    /// This metric is tagged with:
+    ///  - `aggregator`: The name of the metrics aggregator (usually `"default"`).
+    ///  - `namespace`: The namespace of the rejected bucket.
+    NamespaceLimitExceeded,
     /// Incremented every time the meta aggregator emitted an update that needs to be stored or
     /// sent upstream.
     MetaAggregatorUpdate,
@@ -64,6 +77,8 @@ impl CounterMetric for MetricCounters {
             Self::MergeHit => "metrics.buckets.merge.hit",
             Self::MergeMiss => "metrics.buckets.merge.miss",
             Self::BucketsDropped => "metrics.buckets.dropped",
+            Self::BucketsRestored => "metrics.buckets.restored",
+            Self::NamespaceLimitExceeded => "metrics.buckets.namespace_limit_exceeded",
             Self::MetaAggregatorUpdate => "metrics.meta.agg.update",
             Self::MetaAggregatorItems => "metrics.meta.agg.items",
             #[cfg(feature = "redis")]
@@ -88,6 +103,13 @@ pub enum MetricTimers {
     /// This metric is tagged with:
     ///  - `message`: The type of message that was processed.
     AggregatorServiceDuration,
+    /// Time in milliseconds spent serializing a snapshot of the aggregator state.
+    ///
+    /// Writing the snapshot to disk happens in the background and is not part of this timer.
+    ///
+    /// This metric is tagged with:
+    ///  - `aggregator`: The name of the metrics aggregator (usually `"default"`).
+    SnapshotDuration,
 }
 
 impl TimerMetric for MetricTimers {
@@ -95,6 +117,7 @@ impl TimerMetric for MetricTimers {
         match *self {
             Self::BucketsScanDuration => "metrics.buckets.scan_duration",
             Self::AggregatorServiceDuration => "metrics.aggregator.message.duration",
+            Self::SnapshotDuration => "metrics.aggregator.snapshot.duration",
         }
     }
 }
@@ -61,6 +61,19 @@ pub enum CheckInStatus {
     Unknown,
 }
 
+impl CheckInStatus {
+    /// Returns the string representation of this status.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Error => "error",
+            Self::InProgress => "in_progress",
+            Self::Missed => "missed",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
 fn uuid_simple<S>(uuid: &Uuid, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: serde::Serializer,
@@ -181,6 +194,9 @@ pub struct ProcessedCheckInResult {
 
     /// The JSON payload of the processed check-in.
     pub payload: Vec<u8>,
+
+    /// The processed check-in, which inbound filters can be applied to.
+    pub check_in: CheckIn,
 }
 
 /// Normalizes a monitor check-in payload.
@@ -224,6 +240,7 @@ pub fn process_check_in(
     Ok(ProcessedCheckInResult {
         routing_hint,
         payload: serde_json::to_vec(&check_in)?,
+        check_in,
     })
 }
 
@@ -3,13 +3,14 @@ use std::iter::FusedIterator;
 
 use regex::bytes::RegexBuilder as BytesRegexBuilder;
 use regex::{Match, Regex};
+use relay_base_schema::data_category::DataCategory;
 use relay_event_schema::processor::{FieldAttrs, Pii, ProcessingState, ValueType};
 use smallvec::SmallVec;
 use utf16string::{LittleEndian, WStr};
 
 use crate::compiledconfig::RuleRef;
 use crate::regexes::{get_regex_for_rule_type, ReplaceBehavior};
-use crate::{utils, CompiledPiiConfig, Redaction};
+use crate::{utils, validation, CompiledPiiConfig, Redaction};
 
 /// The minimum length a string needs to be in a binary blob.
 ///
@@ -23,6 +24,7 @@ fn apply_regex_to_utf8_bytes(
     rule: &RuleRef,
     regex: &Regex,
     replace_behavior: &ReplaceBehavior,
+    hash_key: Option<&str>,
 ) -> SmallVec<[(usize, usize); 1]> {
     let mut matches = SmallVec::<[(usize, usize); 1]>::new();
 
@@ -42,7 +44,16 @@ fn apply_regex_to_utf8_bytes(
         }
     };
 
+    let number_group = validation::number_group(&rule.ty);
     for captures in regex.captures_iter(data) {
+        let number = &captures[number_group];
+        let labeled = captures[0].len() > number.len();
+        let is_valid = std::str::from_utf8(number)
+            .is_ok_and(|number| validation::is_valid_number(&rule.ty, number, labeled));
+        if !is_valid {
+            continue;
+        }
+
         for (idx, group) in captures.iter().enumerate() {
             if let Some(group) = group {
                 if group.start() == group.end() {
@@ -65,7 +76,7 @@ fn apply_regex_to_utf8_bytes(
     }
 
     for (start, end) in matches.iter() {
-        data[*start..*end].apply_redaction(&rule.redaction);
+        data[*start..*end].apply_redaction(&rule.redaction, hash_key);
     }
     matches
 }
@@ -75,25 +86,37 @@ fn apply_regex_to_utf16le_bytes(
     rule: &RuleRef,
     regex: &Regex,
     replace_behavior: &ReplaceBehavior,
+    hash_key: Option<&str>,
 ) -> bool {
     let mut changed = false;
     for segment in WStrSegmentIter::new(data) {
         match replace_behavior {
             ReplaceBehavior::Value => {
-                for re_match in regex.find_iter(&segment.decoded) {
+                for captures in regex.captures_iter(&segment.decoded) {
+                    if !validation::is_valid_match(&rule.ty, &captures) {
+                        continue;
+                    }
+
+                    let Some(re_match) = captures.get(0) else {
+                        continue;
+                    };
                     changed = true;
                     let match_wstr = get_wstr_match(&segment.decoded, re_match, segment.encoded);
-                    match_wstr.apply_redaction(&rule.redaction);
+                    match_wstr.apply_redaction(&rule.redaction, hash_key);
                 }
             }
             ReplaceBehavior::Groups(ref replace_groups) => {
                 for captures in regex.captures_iter(&segment.decoded) {
+                    if !validation::is_valid_match(&rule.ty, &captures) {
+                        continue;
+                    }
+
                     for group_idx in replace_groups.iter() {
                         if let Some(re_match) = captures.get(*group_idx as usize) {
                             changed = true;
                             let match_wstr =
                                 get_wstr_match(&segment.decoded, re_match, segment.encoded);
-                            match_wstr.apply_redaction(&rule.redaction);
+                            match_wstr.apply_redaction(&rule.redaction, hash_key);
                         }
                     }
                 }
@@ -152,25 +175,31 @@ trait StringMods: AsRef<[u8]> {
     fn swap_content(&mut self, replacement: &str, padding: char);
 
     /// Apply a PII scrubbing redaction to this string slice.
-    fn apply_redaction(&mut self, redaction: &Redaction) {
+    ///
+    /// The `hash_key` is used by [`Redaction::KeyedHash`].
+    fn apply_redaction(&mut self, redaction: &Redaction, hash_key: Option<&str>) {
         const PADDING: char = '*';
         const MASK: char = '*';
 
-        match redaction {
-            Redaction::Default | Redaction::Remove => {
+        match (redaction, hash_key) {
+            (Redaction::Default | Redaction::Remove, _) | (Redaction::KeyedHash, None) => {
                 self.fill_content(PADDING);
             }
-            Redaction::Mask => {
+            (Redaction::Mask, _) => {
                 self.fill_content(MASK);
             }
-            Redaction::Hash => {
+            (Redaction::Hash, _) => {
                 let hashed = utils::hash_value(self.as_ref());
                 self.swap_content(&hashed, PADDING);
             }
-            Redaction::Replace(ref replace) => {
+            (Redaction::KeyedHash, Some(key)) => {
+                let hashed = utils::hash_value_with_key(key.as_bytes(), self.as_ref());
+                self.swap_content(&hashed, PADDING);
+            }
+            (Redaction::Replace(ref replace), _) => {
                 self.swap_content(replace.text.as_str(), PADDING);
             }
-            Redaction::Other => relay_log::warn!("Incoming redaction is not supported"),
+            (Redaction::Other, _) => relay_log::warn!("Incoming redaction is not supported"),
         }
     }
 }
@@ -337,6 +366,7 @@ struct WStrSegment<'a> {
 pub struct PiiAttachmentsProcessor<'a> {
     compiled_config: &'a CompiledPiiConfig,
     root_state: ProcessingState<'static>,
+    hash_key: Option<&'a str>,
 }
 
 /// Which encodings to scrub for `scrub_bytes`.
@@ -363,9 +393,18 @@ impl<'a> PiiAttachmentsProcessor<'a> {
         PiiAttachmentsProcessor {
             compiled_config,
             root_state,
+            hash_key: None,
         }
     }
 
+    /// Sets the secret key used by [`Redaction::KeyedHash`].
+    ///
+    /// Without a key, keyed hash redactions remove the value.
+    pub fn with_hash_key(mut self, hash_key: Option<&'a str>) -> Self {
+        self.hash_key = hash_key;
+        self
+    }
+
     /// Returns the processing state for the file with the given name.
     pub(crate) fn state<'s>(
         &'s self,
@@ -395,7 +434,10 @@ impl<'a> PiiAttachmentsProcessor<'a> {
 
         let mut changed = false;
 
-        for (selector, rules) in &self.compiled_config.applications {
+        for ((selector, rules), _) in self
+            .compiled_config
+            .applications_for(Some(DataCategory::Attachment))
+        {
             if selector.matches_path(&state.path()) {
                 for rule in rules {
                     // Note:
@@ -412,8 +454,13 @@ impl<'a> PiiAttachmentsProcessor<'a> {
                     {
                         match encodings {
                             ScrubEncodings::Utf8 => {
-                                let matches =
-                                    apply_regex_to_utf8_bytes(data, rule, regex, &replace_behavior);
+                                let matches = apply_regex_to_utf8_bytes(
+                                    data,
+                                    rule,
+                                    regex,
+                                    &replace_behavior,
+                                    self.hash_key,
+                                );
                                 changed |= !(matches.is_empty());
                             }
                             ScrubEncodings::Utf16Le => {
@@ -422,11 +469,17 @@ impl<'a> PiiAttachmentsProcessor<'a> {
                                     rule,
                                     regex,
                                     &replace_behavior,
+                                    self.hash_key,
                                 );
                             }
                             ScrubEncodings::All => {
-                                let matches =
-                                    apply_regex_to_utf8_bytes(data, rule, regex, &replace_behavior);
+                                let matches = apply_regex_to_utf8_bytes(
+                                    data,
+                                    rule,
+                                    regex,
+                                    &replace_behavior,
+                                    self.hash_key,
+                                );
                                 changed |= !(matches.is_empty());
 
                                 // Only scrub regions with the UTF-16 scrubber if they haven't been
@@ -451,6 +504,7 @@ impl<'a> PiiAttachmentsProcessor<'a> {
                                         rule,
                                         regex,
                                         &replace_behavior,
+                                        self.hash_key,
                                     );
                                 }
                             }
@@ -726,6 +780,48 @@ mod tests {
         .run();
     }
 
+    #[test]
+    fn test_nationalid_masking() {
+        AttachmentBytesTestCase::Builtin {
+            selector: "$binary",
+            rule: "@nationalid:mask",
+            filename: "foo.txt",
+            value_type: ValueType::Binary,
+            input: b"BSN: 111222333, order 111222333",
+            output: b"BSN: *********, order 111222333",
+            changed: true,
+        }
+        .run();
+    }
+
+    #[test]
+    fn test_nationalid_masking_utf16() {
+        AttachmentBytesTestCase::Builtin {
+            selector: "$binary",
+            rule: "@nationalid:mask",
+            filename: "foo.txt",
+            value_type: ValueType::Binary,
+            input: utf16le("BSN: 111222333, order 111222333").as_slice(),
+            output: utf16le("BSN: *********, order 111222333").as_slice(),
+            changed: true,
+        }
+        .run();
+    }
+
+    #[test]
+    fn test_phone_masking_utf16() {
+        AttachmentBytesTestCase::Builtin {
+            selector: "$binary",
+            rule: "@phone:mask",
+            filename: "foo.txt",
+            value_type: ValueType::Binary,
+            input: utf16le("call +1 415 555 2671 or +1 234").as_slice(),
+            output: utf16le("call *************** or +1 234").as_slice(),
+            changed: true,
+        }
+        .run();
+    }
+
     #[test]
     fn test_selectors() {
         for wrong_selector in &[
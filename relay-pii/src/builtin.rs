@@ -334,6 +334,87 @@ declare_builtin_rules! {
         redaction: Redaction::Remove,
     };
 
+    // international phone numbers
+    "@phone" => rule_alias!("@phone:replace");
+    "@phone:replace" => RuleSpec {
+        ty: RuleType::Phone,
+        redaction: Redaction::Replace(ReplaceRedaction {
+            text: "[phone]".into(),
+        }),
+    };
+    "@phone:filter" => RuleSpec {
+        ty: RuleType::Phone,
+        redaction: Redaction::Replace(ReplaceRedaction {
+            text: "[Filtered]".into(),
+        }),
+    };
+    "@phone:mask" => RuleSpec {
+        ty: RuleType::Phone,
+        redaction: Redaction::Mask,
+    };
+    "@phone:hash" => RuleSpec {
+        ty: RuleType::Phone,
+        redaction: Redaction::Hash,
+    };
+    "@phone:remove" => RuleSpec {
+        ty: RuleType::Phone,
+        redaction: Redaction::Remove,
+    };
+
+    // street addresses
+    "@address" => rule_alias!("@address:replace");
+    "@address:replace" => RuleSpec {
+        ty: RuleType::Address,
+        redaction: Redaction::Replace(ReplaceRedaction {
+            text: "[address]".into(),
+        }),
+    };
+    "@address:filter" => RuleSpec {
+        ty: RuleType::Address,
+        redaction: Redaction::Replace(ReplaceRedaction {
+            text: "[Filtered]".into(),
+        }),
+    };
+    "@address:mask" => RuleSpec {
+        ty: RuleType::Address,
+        redaction: Redaction::Mask,
+    };
+    "@address:hash" => RuleSpec {
+        ty: RuleType::Address,
+        redaction: Redaction::Hash,
+    };
+    "@address:remove" => RuleSpec {
+        ty: RuleType::Address,
+        redaction: Redaction::Remove,
+    };
+
+    // national identification numbers of EU countries
+    "@nationalid" => rule_alias!("@nationalid:replace");
+    "@nationalid:replace" => RuleSpec {
+        ty: RuleType::NationalId,
+        redaction: Redaction::Replace(ReplaceRedaction {
+            text: "[nationalid]".into(),
+        }),
+    };
+    "@nationalid:filter" => RuleSpec {
+        ty: RuleType::NationalId,
+        redaction: Redaction::Replace(ReplaceRedaction {
+            text: "[Filtered]".into(),
+        }),
+    };
+    "@nationalid:mask" => RuleSpec {
+        ty: RuleType::NationalId,
+        redaction: Redaction::Mask,
+    };
+    "@nationalid:hash" => RuleSpec {
+        ty: RuleType::NationalId,
+        redaction: Redaction::Hash,
+    };
+    "@nationalid:remove" => RuleSpec {
+        ty: RuleType::NationalId,
+        redaction: Redaction::Remove,
+    };
+
     // user path rules
     "@userpath" => rule_alias!("@userpath:replace");
     "@userpath:replace" => RuleSpec {
@@ -1244,6 +1325,92 @@ HdmUCGvfKiF2CodxyLon1XkK8pX+Ap86MbJhluqK
         );
     }
 
+    #[test]
+    fn test_phone() {
+        assert_text_rule!(
+            rule = "@phone";
+            input = "Call me at +1 415 555 2671 tomorrow";
+            output = "Call me at [phone] tomorrow";
+            remarks = vec![
+                Remark::with_range(RemarkType::Substituted, "@phone", (11, 18)),
+            ];
+        );
+        assert_text_rule!(
+            rule = "@phone:mask";
+            input = "Call me at +1 415 555 2671 tomorrow";
+            output = "Call me at *************** tomorrow";
+            remarks = vec![
+                Remark::with_range(RemarkType::Masked, "@phone:mask", (11, 26)),
+            ];
+        );
+        assert_rule_not_applied!(
+            rule = "@phone";
+            input = "upgraded to +1 2.3";
+        );
+    }
+
+    #[test]
+    fn test_address() {
+        assert_text_rule!(
+            rule = "@address";
+            input = "Ship to 221B Baker Street, London";
+            output = "Ship to [address], London";
+            remarks = vec![
+                Remark::with_range(RemarkType::Substituted, "@address", (8, 17)),
+            ];
+        );
+        assert_text_rule!(
+            rule = "@address:mask";
+            input = "Wohnhaft in Lindenweg 12a, Berlin";
+            output = "Wohnhaft in *************, Berlin";
+            remarks = vec![
+                Remark::with_range(RemarkType::Masked, "@address:mask", (12, 25)),
+            ];
+        );
+        assert_rule_not_applied!(
+            rule = "@address";
+            input = "Processed 42 items in 3 batches";
+        );
+    }
+
+    #[test]
+    fn test_nationalid() {
+        assert_text_rule!(
+            rule = "@nationalid";
+            input = "DNI: 12345678Z.";
+            output = "DNI: [nationalid].";
+            remarks = vec![
+                Remark::with_range(RemarkType::Substituted, "@nationalid", (5, 17)),
+            ];
+        );
+        assert_text_rule!(
+            rule = "@nationalid:mask";
+            input = "NIR 1 85 05 78 006 084 91";
+            output = "NIR *********************";
+            remarks = vec![
+                Remark::with_range(RemarkType::Masked, "@nationalid:mask", (4, 25)),
+            ];
+        );
+        // Plain digit sequences are only scrubbed after a label, which is retained.
+        assert_text_rule!(
+            rule = "@nationalid";
+            input = "BSN: 111222333";
+            output = "BSN: [nationalid]";
+            remarks = vec![
+                Remark::with_range(RemarkType::Substituted, "@nationalid", (5, 17)),
+            ];
+        );
+        assert_rule_not_applied!(
+            rule = "@nationalid";
+            input = "order id 111222333, tax 86095742719";
+        );
+        // Numbers of the same shape with invalid check digits are not scrubbed.
+        assert_rule_not_applied!(
+            rule = "@nationalid";
+            input = "order 12345678A, ticket 123456789, bsn 000000000";
+        );
+    }
+
     #[test]
     fn test_userpath() {
         assert_text_rule!(
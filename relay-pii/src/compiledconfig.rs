@@ -1,6 +1,9 @@
 use std::cmp::Ordering;
 use std::collections::BTreeSet;
 
+use relay_base_schema::data_category::DataCategory;
+use relay_event_schema::processor::ValueType;
+
 use crate::builtin::BUILTIN_RULES_MAP;
 use crate::{PiiConfig, PiiConfigError, Redaction, RuleSpec, RuleType, SelectorSpec};
 
@@ -11,12 +14,15 @@ use crate::{PiiConfig, PiiConfigError, Redaction, RuleSpec, RuleType, SelectorSp
 #[derive(Debug, Clone)]
 pub struct CompiledPiiConfig {
     pub(super) applications: Vec<(SelectorSpec, BTreeSet<RuleRef>)>,
+    /// The data categories of each entry in `applications`, or `None` if it is not scoped.
+    scopes: Vec<Option<BTreeSet<DataCategory>>>,
 }
 
 impl CompiledPiiConfig {
     /// Computes the compiled PII config.
     pub fn new(config: &PiiConfig) -> Self {
         let mut applications = Vec::new();
+        let mut scopes = Vec::new();
         for (selector, rules) in &config.applications {
             #[allow(clippy::mutable_key_type)]
             let mut rule_set = BTreeSet::default();
@@ -24,9 +30,47 @@ impl CompiledPiiConfig {
                 collect_rules(config, &mut rule_set, rule_id, None);
             }
             applications.push((selector.clone(), rule_set));
+            scopes.push(config.scopes.get(selector).cloned());
+        }
+
+        CompiledPiiConfig {
+            applications,
+            scopes,
         }
+    }
 
-        CompiledPiiConfig { applications }
+    /// Returns the selectors and rules that apply to data of the given category, along with the
+    /// scope of the application.
+    ///
+    /// Applications without a scope apply to all data. Scoped applications only apply if the
+    /// category is part of their scope, and never if no category is given.
+    pub(super) fn applications_for(
+        &self,
+        category: Option<DataCategory>,
+    ) -> impl Iterator<
+        Item = (
+            &(SelectorSpec, BTreeSet<RuleRef>),
+            Option<&BTreeSet<DataCategory>>,
+        ),
+    > {
+        self.applications
+            .iter()
+            .zip(&self.scopes)
+            .filter(move |(_, scope)| match scope {
+                Some(scope) => category.is_some_and(|c| scope.contains(&c)),
+                None => true,
+            })
+            .map(|(application, scope)| (application, scope.as_ref()))
+    }
+
+    /// Returns `true` if any selector refers to attachments or binary data.
+    ///
+    /// Attachments are only scrubbed by rules with selectors that explicitly refer to them.
+    pub fn has_attachment_selectors(&self) -> bool {
+        self.applications.iter().any(|(selector, _)| {
+            selector.refers_to_type(ValueType::Attachments)
+                || selector.refers_to_type(ValueType::Binary)
+        })
     }
 
     /// Force compilation of all regex patterns in this config.
@@ -53,6 +97,9 @@ impl CompiledPiiConfig {
                 | RuleType::Pemkey
                 | RuleType::UrlAuth
                 | RuleType::UsSsn
+                | RuleType::Phone
+                | RuleType::Address
+                | RuleType::NationalId
                 | RuleType::Password
                 | RuleType::Multiple(_)
                 | RuleType::Alias(_)
@@ -3,6 +3,7 @@ use std::collections::{BTreeMap, BTreeSet};
 use std::sync::OnceLock;
 
 use regex::{Regex, RegexBuilder};
+use relay_base_schema::data_category::DataCategory;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::{CompiledPiiConfig, Redaction, SelectorSpec};
@@ -182,6 +183,12 @@ pub enum RuleType {
     UrlAuth,
     /// US SSN.
     UsSsn,
+    /// International phone numbers with a country calling code.
+    Phone,
+    /// Street addresses in free text.
+    Address,
+    /// National identification numbers of EU countries, validated by their check digits.
+    NationalId,
     /// Keys that look like passwords
     Password,
     /// When a regex matches a key, a value is removed
@@ -237,6 +244,13 @@ pub struct PiiConfig {
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub applications: BTreeMap<SelectorSpec, Vec<String>>,
 
+    /// Restricts applications to data categories.
+    ///
+    /// Maps selectors in `applications` to the data categories they apply to, for instance
+    /// `transaction` or `replay`. Applications without a scope apply to all data.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub scopes: BTreeMap<SelectorSpec, BTreeSet<DataCategory>>,
+
     /// PII config derived from datascrubbing settings.
     ///
     /// Cached because the conversion process is expensive.
@@ -252,10 +266,14 @@ impl PartialEq for PiiConfig {
             rules,
             vars,
             applications,
+            scopes,
             compiled: _compiled,
         } = &self;
 
-        rules == &other.rules && vars == &other.vars && applications == &other.applications
+        rules == &other.rules
+            && vars == &other.vars
+            && applications == &other.applications
+            && scopes == &other.scopes
     }
 }
 
@@ -0,0 +1,722 @@
+//! Scrubbing of compressed and structured attachments.
+//!
+//! Plain attachment scrubbing operates on the raw bytes of an attachment, which means that PII in
+//! compressed files is never found by the patterns. This module unpacks gzip and zip containers,
+//! scrubs their contents, and packs them again. JSON documents are additionally parsed, so that
+//! selectors can address their keys.
+
+use std::io::{self, Read, Write};
+
+use flate2::read::{DeflateDecoder, MultiGzDecoder};
+use flate2::write::{DeflateEncoder, GzEncoder};
+use flate2::{Compression, Crc};
+use relay_base_schema::data_category::DataCategory;
+use relay_event_schema::processor::{self, ValueType};
+use relay_protocol::{Annotated, Value};
+
+use crate::{PiiAttachmentsProcessor, PiiProcessor, ScrubEncodings};
+
+/// Magic bytes at the start of a gzip stream.
+const GZIP_MAGIC: &[u8] = b"\x1f\x8b";
+
+/// Signature of a zip local file header.
+const ZIP_LOCAL_HEADER: u32 = 0x0403_4b50;
+/// Signature of a zip central directory file header.
+const ZIP_CENTRAL_HEADER: u32 = 0x0201_4b50;
+/// Signature of the zip end of central directory record.
+const ZIP_END_OF_DIRECTORY: u32 = 0x0605_4b50;
+
+/// Size of the fixed part of a zip local file header.
+const LOCAL_HEADER_SIZE: usize = 30;
+/// Size of the fixed part of a zip central directory file header.
+const CENTRAL_HEADER_SIZE: usize = 46;
+/// Size of the fixed part of the zip end of central directory record.
+const END_OF_DIRECTORY_SIZE: usize = 22;
+
+/// Zip compression method for stored (uncompressed) entries.
+const METHOD_STORED: u16 = 0;
+/// Zip compression method for deflated entries.
+const METHOD_DEFLATED: u16 = 8;
+
+/// Zip flag indicating an encrypted entry.
+const FLAG_ENCRYPTED: u16 = 1 << 0;
+/// Zip flag indicating that sizes and checksum follow the data in a data descriptor.
+const FLAG_DATA_DESCRIPTOR: u16 = 1 << 3;
+
+/// An error returned from [`PiiAttachmentsProcessor::scrub_container`].
+#[derive(Debug, thiserror::Error)]
+pub enum ScrubContainerError {
+    /// The contents of the container exceed the size limit when decompressed.
+    #[error("decompressed attachment exceeds size limit")]
+    TooLarge,
+
+    /// The compressed stream could not be decompressed or compressed.
+    #[error("failed to decompress attachment")]
+    Compression(#[from] io::Error),
+
+    /// The zip archive is malformed or uses unsupported features, such as ZIP64.
+    #[error("invalid or unsupported zip archive")]
+    InvalidZip,
+}
+
+/// The format of an attachment, detected from its contents.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Format {
+    Gzip,
+    Zip,
+    Json,
+    Xml,
+}
+
+impl Format {
+    fn detect(data: &[u8]) -> Option<Self> {
+        if data.starts_with(GZIP_MAGIC) {
+            return Some(Self::Gzip);
+        }
+
+        if read_u32(data, 0) == Some(ZIP_LOCAL_HEADER) {
+            return Some(Self::Zip);
+        }
+
+        match data.iter().find(|b| !b.is_ascii_whitespace()) {
+            Some(b'{' | b'[') => Some(Self::Json),
+            Some(b'<') => Some(Self::Xml),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> PiiAttachmentsProcessor<'a> {
+    /// Applies PII scrubbing rules to compressed and structured attachments.
+    ///
+    /// Gzip and zip containers are decompressed, their contents are scrubbed and then compressed
+    /// again. JSON documents are scrubbed with selectors into their keys in addition to the
+    /// patterns applied to plain attachments, while XML documents are scrubbed as text. All
+    /// contents are scrubbed with the state of the attachment's `filename`.
+    ///
+    /// Documents are only parsed as JSON if they declare it, either by the `content_type` of the
+    /// attachment or by a `.json` file name within a container. Other documents that look like
+    /// JSON are scrubbed as text.
+    ///
+    /// To protect against decompression bombs, the total decompressed size of a container must not
+    /// exceed `limit` bytes, otherwise [`ScrubContainerError::TooLarge`] is returned.
+    ///
+    /// Returns the new payload if the attachment was modified, and `None` if it was not modified
+    /// or is not in one of the supported formats.
+    pub fn scrub_container(
+        &self,
+        filename: &str,
+        content_type: Option<&str>,
+        data: &[u8],
+        limit: usize,
+    ) -> Result<Option<Vec<u8>>, ScrubContainerError> {
+        match Format::detect(data) {
+            Some(Format::Gzip) => self.scrub_gzip(filename, data, limit),
+            Some(Format::Zip) => self.scrub_zip(filename, data, limit),
+            Some(Format::Json | Format::Xml) => {
+                let json = content_type.is_some_and(is_json_content_type);
+                let mut content = data.to_vec();
+                let changed = self.scrub_content(filename, &mut content, json);
+                Ok(changed.then_some(content))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Scrubs the decompressed contents of a container in place.
+    ///
+    /// If `json` is set and the contents look like a JSON document, they are also scrubbed with
+    /// selectors. Returns `true`, if the contents were modified.
+    fn scrub_content(&self, filename: &str, content: &mut Vec<u8>, json: bool) -> bool {
+        let state = self.state(filename, ValueType::Binary);
+        let mut changed = self.scrub_bytes(content, &state, ScrubEncodings::All);
+
+        if json && Format::detect(content) == Some(Format::Json) {
+            if let Some(scrubbed) = self.scrub_json(filename, content) {
+                *content = scrubbed;
+                changed = true;
+            }
+        }
+
+        changed
+    }
+
+    /// Scrubs a JSON document with the [`PiiProcessor`].
+    ///
+    /// Returns the serialized document if it was modified, or `None` if it was not modified or
+    /// could not be parsed. Since serialization does not preserve the original formatting, the
+    /// original bytes must be kept if nothing was scrubbed.
+    fn scrub_json(&self, filename: &str, content: &[u8]) -> Option<Vec<u8>> {
+        let original = serde_json::from_slice::<serde_json::Value>(content).ok()?;
+
+        let value_type = match original {
+            serde_json::Value::Array(_) => ValueType::Array,
+            _ => ValueType::Object,
+        };
+
+        let mut annotated = Annotated::<Value>::from(original);
+        let unscrubbed = annotated.clone();
+
+        let state = self.state(filename, value_type);
+        let mut processor = PiiProcessor::new(self.compiled_config)
+            .with_hash_key(self.hash_key)
+            .with_scope(DataCategory::Attachment);
+        processor::process_value(&mut annotated, &mut processor, &state).ok()?;
+
+        // Scrubbed values carry remarks, so this also detects values replaced by equal values.
+        if annotated == unscrubbed {
+            return None;
+        }
+
+        let scrubbed = annotated
+            .into_value()
+            .map_or(serde_json::Value::Null, serde_json::Value::from);
+
+        serde_json::to_vec(&scrubbed).ok()
+    }
+
+    fn scrub_gzip(
+        &self,
+        filename: &str,
+        data: &[u8],
+        limit: usize,
+    ) -> Result<Option<Vec<u8>>, ScrubContainerError> {
+        let mut content = read_limited(MultiGzDecoder::new(data), limit)?;
+        let name = filename.strip_suffix(".gz").unwrap_or(filename);
+        if !self.scrub_content(filename, &mut content, is_json_name(name.as_bytes())) {
+            return Ok(None);
+        }
+
+        let mut encoder = GzEncoder::new(Vec::with_capacity(data.len()), Compression::default());
+        encoder.write_all(&content)?;
+        Ok(Some(encoder.finish()?))
+    }
+
+    /// Scrubs all stored and deflated entries of a zip archive.
+    ///
+    /// The archive is rewritten from its central directory. Encrypted entries and entries with
+    /// other compression methods are copied unmodified, unless they use data descriptors. Data
+    /// descriptors are removed, since sizes and checksums of rewritten entries are stored in their
+    /// local headers.
+    fn scrub_zip(
+        &self,
+        filename: &str,
+        data: &[u8],
+        limit: usize,
+    ) -> Result<Option<Vec<u8>>, ScrubContainerError> {
+        let directory = ZipDirectory::parse(data).ok_or(ScrubContainerError::InvalidZip)?;
+
+        let mut output = Vec::with_capacity(data.len());
+        let mut central = Vec::with_capacity(directory.size);
+        let mut remaining = limit;
+        let mut changed = false;
+
+        let mut offset = directory.offset;
+        for _ in 0..directory.entries {
+            let entry = ZipEntry::parse(data, offset).ok_or(ScrubContainerError::InvalidZip)?;
+            offset = entry.next_offset;
+
+            let local = &data[entry.local_offset..];
+            if read_u32(local, 0) != Some(ZIP_LOCAL_HEADER) {
+                return Err(ScrubContainerError::InvalidZip);
+            }
+            let name_len = usize::from(read_u16(local, 26).ok_or(ScrubContainerError::InvalidZip)?);
+            let extra_len =
+                usize::from(read_u16(local, 28).ok_or(ScrubContainerError::InvalidZip)?);
+            let header_end = LOCAL_HEADER_SIZE + name_len + extra_len;
+            let compressed = local
+                .get(header_end..header_end + entry.compressed_size)
+                .ok_or(ScrubContainerError::InvalidZip)?;
+
+            let header = &data[entry.header_offset..entry.next_offset];
+            let entry_name_len =
+                usize::from(read_u16(header, 28).ok_or(ScrubContainerError::InvalidZip)?);
+            let entry_name = header
+                .get(CENTRAL_HEADER_SIZE..CENTRAL_HEADER_SIZE + entry_name_len)
+                .ok_or(ScrubContainerError::InvalidZip)?;
+
+            let mut entry_data = compressed.to_vec();
+            let mut crc = entry.crc;
+            let mut uncompressed_size = entry.uncompressed_size;
+
+            if entry.flags & FLAG_ENCRYPTED != 0 {
+                // The password check of encrypted entries depends on the data descriptor flag.
+                if entry.flags & FLAG_DATA_DESCRIPTOR != 0 {
+                    return Err(ScrubContainerError::InvalidZip);
+                }
+            } else {
+                let content = match entry.method {
+                    METHOD_STORED => Some(compressed.to_vec()),
+                    METHOD_DEFLATED => {
+                        Some(read_limited(DeflateDecoder::new(compressed), remaining)?)
+                    }
+                    _ => None,
+                };
+
+                if let Some(mut content) = content {
+                    remaining = remaining
+                        .checked_sub(content.len())
+                        .ok_or(ScrubContainerError::TooLarge)?;
+
+                    if self.scrub_content(filename, &mut content, is_json_name(entry_name)) {
+                        changed = true;
+
+                        let mut hasher = Crc::new();
+                        hasher.update(&content);
+                        crc = hasher.sum();
+                        uncompressed_size = content.len();
+
+                        entry_data = match entry.method {
+                            METHOD_DEFLATED => {
+                                let mut encoder =
+                                    DeflateEncoder::new(Vec::new(), Compression::default());
+                                encoder.write_all(&content)?;
+                                encoder.finish()?
+                            }
+                            _ => content,
+                        };
+                    }
+                }
+            }
+
+            let new_offset = output.len();
+            let flags = entry.flags & !FLAG_DATA_DESCRIPTOR;
+            let compressed_size = to_u32(entry_data.len())?;
+            let uncompressed_size = to_u32(uncompressed_size)?;
+
+            // Local file header with updated flags, checksum and sizes.
+            output.extend_from_slice(&local[..6]);
+            output.extend_from_slice(&flags.to_le_bytes());
+            output.extend_from_slice(&local[8..14]);
+            output.extend_from_slice(&crc.to_le_bytes());
+            output.extend_from_slice(&compressed_size.to_le_bytes());
+            output.extend_from_slice(&uncompressed_size.to_le_bytes());
+            output.extend_from_slice(&local[26..header_end]);
+            output.extend_from_slice(&entry_data);
+
+            // Central directory header with the same updates and the new local header offset.
+            central.extend_from_slice(&header[..8]);
+            central.extend_from_slice(&flags.to_le_bytes());
+            central.extend_from_slice(&header[10..16]);
+            central.extend_from_slice(&crc.to_le_bytes());
+            central.extend_from_slice(&compressed_size.to_le_bytes());
+            central.extend_from_slice(&uncompressed_size.to_le_bytes());
+            central.extend_from_slice(&header[28..42]);
+            central.extend_from_slice(&to_u32(new_offset)?.to_le_bytes());
+            central.extend_from_slice(&header[CENTRAL_HEADER_SIZE..]);
+        }
+
+        if !changed {
+            return Ok(None);
+        }
+
+        let central_offset = to_u32(output.len())?;
+        let central_size = to_u32(central.len())?;
+        output.extend_from_slice(&central);
+
+        // End of central directory record with the new directory location.
+        let end = &data[directory.end_offset..];
+        output.extend_from_slice(&end[..12]);
+        output.extend_from_slice(&central_size.to_le_bytes());
+        output.extend_from_slice(&central_offset.to_le_bytes());
+        output.extend_from_slice(&end[20..]);
+
+        Ok(Some(output))
+    }
+}
+
+/// Returns `true` if the MIME type declares a JSON document.
+fn is_json_content_type(content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or_default().trim();
+    mime.eq_ignore_ascii_case("application/json") || mime.to_ascii_lowercase().ends_with("+json")
+}
+
+/// Returns `true` if the file name has a `.json` extension.
+fn is_json_name(name: &[u8]) -> bool {
+    name.len() >= 5 && name[name.len() - 5..].eq_ignore_ascii_case(b".json")
+}
+
+/// Reads a decompressed stream of at most `limit` bytes.
+fn read_limited(reader: impl Read, limit: usize) -> Result<Vec<u8>, ScrubContainerError> {
+    let mut content = Vec::new();
+    reader
+        .take(limit.saturating_add(1) as u64)
+        .read_to_end(&mut content)?;
+
+    if content.len() > limit {
+        return Err(ScrubContainerError::TooLarge);
+    }
+
+    Ok(content)
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes(bytes.try_into().ok()?))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+fn to_u32(value: usize) -> Result<u32, ScrubContainerError> {
+    value
+        .try_into()
+        .map_err(|_| ScrubContainerError::InvalidZip)
+}
+
+/// Location of the central directory of a zip archive.
+struct ZipDirectory {
+    /// Number of entries in the central directory.
+    entries: u16,
+    /// Offset of the first central directory header.
+    offset: usize,
+    /// Size of the central directory in bytes.
+    size: usize,
+    /// Offset of the end of central directory record.
+    end_offset: usize,
+}
+
+impl ZipDirectory {
+    /// Locates the end of central directory record, which is followed by a comment of at most
+    /// 64KiB.
+    fn parse(data: &[u8]) -> Option<Self> {
+        let last = data.len().checked_sub(END_OF_DIRECTORY_SIZE)?;
+        let first = last.saturating_sub(usize::from(u16::MAX));
+
+        let end_offset = (first..=last).rev().find(|&offset| {
+            read_u32(data, offset) == Some(ZIP_END_OF_DIRECTORY)
+                && read_u16(data, offset + 20).is_some_and(|len| {
+                    offset + END_OF_DIRECTORY_SIZE + usize::from(len) == data.len()
+                })
+        })?;
+
+        let end = &data[end_offset..];
+        let entries = read_u16(end, 10)?;
+        let size = read_u32(end, 12)?;
+        let offset = read_u32(end, 16)?;
+
+        // ZIP64 archives store their directory elsewhere and are not supported.
+        if entries == u16::MAX || size == u32::MAX || offset == u32::MAX {
+            return None;
+        }
+
+        Some(Self {
+            entries,
+            offset: offset.try_into().ok()?,
+            size: size.try_into().ok()?,
+            end_offset,
+        })
+    }
+}
+
+/// A file header in the central directory of a zip archive.
+struct ZipEntry {
+    flags: u16,
+    method: u16,
+    crc: u32,
+    compressed_size: usize,
+    uncompressed_size: usize,
+    /// Offset of this central directory header.
+    header_offset: usize,
+    /// Offset of the corresponding local file header.
+    local_offset: usize,
+    /// Offset of the next central directory header.
+    next_offset: usize,
+}
+
+impl ZipEntry {
+    fn parse(data: &[u8], offset: usize) -> Option<Self> {
+        let header = data.get(offset..)?;
+        if read_u32(header, 0)? != ZIP_CENTRAL_HEADER {
+            return None;
+        }
+
+        let variable_len = usize::from(read_u16(header, 28)?)
+            + usize::from(read_u16(header, 30)?)
+            + usize::from(read_u16(header, 32)?);
+        let next_offset = offset + CENTRAL_HEADER_SIZE + variable_len;
+        if next_offset > data.len() {
+            return None;
+        }
+
+        let local_offset = usize::try_from(read_u32(header, 42)?).ok()?;
+        if local_offset >= offset {
+            return None;
+        }
+
+        Some(Self {
+            flags: read_u16(header, 8)?,
+            method: read_u16(header, 10)?,
+            crc: read_u32(header, 16)?,
+            compressed_size: read_u32(header, 20)?.try_into().ok()?,
+            uncompressed_size: read_u32(header, 24)?.try_into().ok()?,
+            header_offset: offset,
+            local_offset,
+            next_offset,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PiiConfig;
+
+    fn processor_config() -> PiiConfig {
+        serde_json::from_value(serde_json::json!({
+            "applications": {
+                "$attachments.**": ["@ip:replace"],
+                "$attachments.'data.json'.password": ["@anything:remove"],
+            }
+        }))
+        .unwrap()
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn gunzip(data: &[u8]) -> Vec<u8> {
+        read_limited(MultiGzDecoder::new(data), usize::MAX - 1).unwrap()
+    }
+
+    /// Builds a zip archive with a single deflated entry.
+    fn zip(name: &str, data: &[u8]) -> Vec<u8> {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let mut crc = Crc::new();
+        crc.update(data);
+
+        let mut fields = Vec::new();
+        fields.extend_from_slice(&0u16.to_le_bytes()); // flags
+        fields.extend_from_slice(&METHOD_DEFLATED.to_le_bytes());
+        fields.extend_from_slice(&[0; 4]); // time and date
+        fields.extend_from_slice(&crc.sum().to_le_bytes());
+        fields.extend_from_slice(&(compressed.len() as u32).to_le_bytes());
+        fields.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        fields.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        fields.extend_from_slice(&0u16.to_le_bytes()); // extra length
+
+        let mut output = Vec::new();
+        output.extend_from_slice(&ZIP_LOCAL_HEADER.to_le_bytes());
+        output.extend_from_slice(&20u16.to_le_bytes()); // version needed
+        output.extend_from_slice(&fields);
+        output.extend_from_slice(name.as_bytes());
+        output.extend_from_slice(&compressed);
+
+        let central_offset = output.len();
+        output.extend_from_slice(&ZIP_CENTRAL_HEADER.to_le_bytes());
+        output.extend_from_slice(&20u16.to_le_bytes()); // version made by
+        output.extend_from_slice(&20u16.to_le_bytes()); // version needed
+        output.extend_from_slice(&fields);
+        output.extend_from_slice(&[0; 12]); // comment, disk, attributes
+        output.extend_from_slice(&0u32.to_le_bytes()); // local header offset
+        output.extend_from_slice(name.as_bytes());
+        let central_size = output.len() - central_offset;
+
+        output.extend_from_slice(&ZIP_END_OF_DIRECTORY.to_le_bytes());
+        output.extend_from_slice(&[0; 4]); // disk numbers
+        output.extend_from_slice(&1u16.to_le_bytes());
+        output.extend_from_slice(&1u16.to_le_bytes());
+        output.extend_from_slice(&(central_size as u32).to_le_bytes());
+        output.extend_from_slice(&(central_offset as u32).to_le_bytes());
+        output.extend_from_slice(&0u16.to_le_bytes()); // comment length
+
+        output
+    }
+
+    /// Reads the contents of the first entry of a zip archive.
+    fn unzip(data: &[u8]) -> Vec<u8> {
+        let directory = ZipDirectory::parse(data).unwrap();
+        let entry = ZipEntry::parse(data, directory.offset).unwrap();
+        let local = &data[entry.local_offset..];
+        let start = LOCAL_HEADER_SIZE + usize::from(read_u16(local, 26).unwrap());
+        let compressed = &local[start..start + entry.compressed_size];
+        let content = read_limited(DeflateDecoder::new(compressed), usize::MAX - 1).unwrap();
+
+        let mut crc = Crc::new();
+        crc.update(&content);
+        assert_eq!(crc.sum(), entry.crc);
+        assert_eq!(content.len(), entry.uncompressed_size);
+
+        content
+    }
+
+    #[test]
+    fn test_scrub_gzip() {
+        let config = processor_config();
+        let compiled = config.compiled();
+        let processor = PiiAttachmentsProcessor::new(compiled);
+
+        let data = gzip(b"connection from 127.0.0.1 accepted");
+        let scrubbed = processor
+            .scrub_container("app.log.gz", None, &data, 1024)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(gunzip(&scrubbed), b"connection from [ip]***** accepted");
+    }
+
+    #[test]
+    fn test_scrub_gzip_unmodified() {
+        let config = processor_config();
+        let compiled = config.compiled();
+        let processor = PiiAttachmentsProcessor::new(compiled);
+
+        let data = gzip(b"nothing to see here");
+        let result = processor.scrub_container("app.log.gz", None, &data, 1024);
+        assert!(matches!(result, Ok(None)));
+    }
+
+    #[test]
+    fn test_scrub_gzip_too_large() {
+        let config = processor_config();
+        let compiled = config.compiled();
+        let processor = PiiAttachmentsProcessor::new(compiled);
+
+        let data = gzip(&[b'a'; 4096]);
+        let result = processor.scrub_container("app.log.gz", None, &data, 1024);
+        assert!(matches!(result, Err(ScrubContainerError::TooLarge)));
+    }
+
+    #[test]
+    fn test_scrub_zip() {
+        let config = processor_config();
+        let compiled = config.compiled();
+        let processor = PiiAttachmentsProcessor::new(compiled);
+
+        let data = zip("app.log", b"connection from 127.0.0.1 accepted");
+        let scrubbed = processor
+            .scrub_container("logs.zip", None, &data, 1024)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(unzip(&scrubbed), b"connection from [ip]***** accepted");
+    }
+
+    #[test]
+    fn test_scrub_zip_invalid() {
+        let config = processor_config();
+        let compiled = config.compiled();
+        let processor = PiiAttachmentsProcessor::new(compiled);
+
+        let mut data = zip("app.log", b"connection from 127.0.0.1 accepted");
+        data.truncate(data.len() - 4);
+
+        let result = processor.scrub_container("logs.zip", None, &data, 1024);
+        assert!(matches!(result, Err(ScrubContainerError::InvalidZip)));
+    }
+
+    #[test]
+    fn test_scrub_json() {
+        let config = processor_config();
+        let compiled = config.compiled();
+        let processor = PiiAttachmentsProcessor::new(compiled);
+
+        let data = br#"{"user": "admin", "password": "hunter2"}"#;
+        let scrubbed = processor
+            .scrub_container("data.json", Some("application/json"), data, 1024)
+            .unwrap()
+            .unwrap();
+
+        let value: serde_json::Value = serde_json::from_slice(&scrubbed).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({"user": "admin", "password": null})
+        );
+    }
+
+    #[test]
+    fn test_scrub_json_unmodified() {
+        let config = processor_config();
+        let compiled = config.compiled();
+        let processor = PiiAttachmentsProcessor::new(compiled);
+
+        // Serialization would change the formatting and the number.
+        let data = b"{ \"user\": \"admin\", \"ratio\": 1.0 }";
+        let result = processor.scrub_container("data.json", Some("application/json"), data, 1024);
+        assert!(matches!(result, Ok(None)));
+    }
+
+    #[test]
+    fn test_scrub_json_without_content_type() {
+        let config = processor_config();
+        let compiled = config.compiled();
+        let processor = PiiAttachmentsProcessor::new(compiled);
+
+        // Without a JSON content type, the document is only scrubbed as text.
+        let data = br#"{"password": "hunter2", "ip": "127.0.0.1"}"#;
+        let scrubbed = processor
+            .scrub_container("data.json", Some("text/plain"), data, 1024)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(scrubbed, br#"{"password": "hunter2", "ip": "[ip]*****"}"#);
+    }
+
+    #[test]
+    fn test_scrub_json_in_zip() {
+        let config = processor_config();
+        let compiled = config.compiled();
+        let processor = PiiAttachmentsProcessor::new(compiled);
+
+        let data = zip("data.json", br#"{"password": "hunter2"}"#);
+        let scrubbed = processor
+            .scrub_container("data.json", None, &data, 1024)
+            .unwrap()
+            .unwrap();
+
+        let value: serde_json::Value = serde_json::from_slice(&unzip(&scrubbed)).unwrap();
+        assert_eq!(value, serde_json::json!({"password": null}));
+    }
+
+    #[test]
+    fn test_scrub_json_in_gzip() {
+        let config = processor_config();
+        let compiled = config.compiled();
+        let processor = PiiAttachmentsProcessor::new(compiled);
+
+        let data = gzip(br#"{"password": "hunter2", "ip": "127.0.0.1"}"#);
+        let scrubbed = processor
+            .scrub_container("data.json", None, &data, 1024)
+            .unwrap()
+            .unwrap();
+
+        let value: serde_json::Value = serde_json::from_slice(&gunzip(&scrubbed)).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({"password": null, "ip": "[ip]*****"})
+        );
+    }
+
+    #[test]
+    fn test_scrub_xml() {
+        let config = processor_config();
+        let compiled = config.compiled();
+        let processor = PiiAttachmentsProcessor::new(compiled);
+
+        let data = b"<client><ip>127.0.0.1</ip></client>";
+        let scrubbed = processor
+            .scrub_container("client.xml", Some("text/xml"), data, 1024)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(scrubbed, b"<client><ip>[ip]*****</ip></client>");
+    }
+
+    #[test]
+    fn test_scrub_plain() {
+        let config = processor_config();
+        let compiled = config.compiled();
+        let processor = PiiAttachmentsProcessor::new(compiled);
+
+        let result = processor.scrub_container("app.log", None, b"127.0.0.1", 1024);
+        assert!(matches!(result, Ok(None)));
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::BTreeSet;
+
+use relay_base_schema::data_category::DataCategory;
+use relay_event_schema::processor::{self, ProcessValue, ProcessingAction, ProcessingState};
+use relay_protocol::Annotated;
+use serde::Serialize;
+
+use crate::processor::PiiProcessor;
+use crate::redactions::Redaction;
+use crate::selector::SelectorSpec;
+
+/// Describes how a PII rule redacted a single value.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct PiiExplanation {
+    /// The path of the redacted value.
+    pub path: String,
+    /// The selector in the PII config that matched the path.
+    pub selector: SelectorSpec,
+    /// The identifier of the rule, as it is recorded in the remarks of the value.
+    pub rule_id: String,
+    /// The redaction method of the rule.
+    pub redaction: Redaction,
+    /// The data categories the matching application is scoped to, or `None` if it is unscoped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<BTreeSet<DataCategory>>,
+    /// The range of characters in the original value replaced by the rule, from start to end.
+    ///
+    /// This is `None` if the value is not a string, for instance an object that was removed.
+    pub original_range: Option<(usize, usize)>,
+    /// The length of [`original_range`](Self::original_range) in characters.
+    pub original_length: Option<usize>,
+}
+
+/// Returns the range of characters in `original` that differ from `scrubbed`.
+///
+/// If the value was removed, this is the entire value. Otherwise, the range spans from the first
+/// to the last replaced character.
+pub(crate) fn replaced_range(original: &str, scrubbed: Option<&str>) -> (usize, usize) {
+    let length = original.chars().count();
+    let Some(scrubbed) = scrubbed else {
+        return (0, length);
+    };
+
+    let prefix = original
+        .chars()
+        .zip(scrubbed.chars())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = original
+        .chars()
+        .rev()
+        .zip(scrubbed.chars().rev())
+        .take(length.min(scrubbed.chars().count()) - prefix)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    (prefix, length - suffix)
+}
+
+/// Explains which rules of a PII config redact which parts of the value, without modifying it.
+///
+/// This runs the `processor` on a copy of the value and records every rule application that
+/// changes or removes a value. The processor should be configured like the one used for scrubbing,
+/// so that its scope and hash key are taken into account. Rules are applied in the same order as
+/// during scrubbing, so values removed by a rule are not reported again for subsequent rules.
+pub fn explain_pii<T>(
+    value: &Annotated<T>,
+    processor: PiiProcessor<'_>,
+) -> Result<Vec<PiiExplanation>, ProcessingAction>
+where
+    T: ProcessValue + Clone,
+{
+    let mut value = value.clone();
+    let mut processor = processor.with_explanations();
+    processor::process_value(&mut value, &mut processor, ProcessingState::root())?;
+    Ok(processor.into_explanations())
+}
+
+#[cfg(test)]
+mod tests {
+    use relay_event_schema::protocol::Event;
+
+    use super::*;
+    use crate::PiiConfig;
+
+    #[test]
+    fn test_explain_pii() {
+        let config = serde_json::from_str::<PiiConfig>(
+            r#"{
+                "applications": {
+                    "$string": ["@ip", "@email:mask"],
+                    "extra.secret": ["@anything:remove"]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let event = Annotated::<Event>::from_json(
+            r#"{
+                "logentry": {"formatted": "request from 127.0.0.1"},
+                "extra": {
+                    "contact": "jane@example.com",
+                    "secret": {"token": "abc"}
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let explanations = explain_pii(&event, PiiProcessor::new(config.compiled())).unwrap();
+        assert!(explanations.iter().all(|e| e.scope.is_none()));
+        let explained = explanations
+            .iter()
+            .map(|e| {
+                (
+                    e.path.as_str(),
+                    e.selector.to_string(),
+                    e.rule_id.as_str(),
+                    e.original_range,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            explained,
+            [
+                (
+                    "logentry.formatted",
+                    "$string".to_owned(),
+                    "@ip",
+                    Some((13, 22))
+                ),
+                (
+                    "extra.contact",
+                    "$string".to_owned(),
+                    "@email:mask",
+                    Some((0, 16))
+                ),
+                (
+                    "extra.secret",
+                    "extra.secret".to_owned(),
+                    "@anything:remove",
+                    None
+                ),
+            ]
+        );
+
+        // The event itself is not modified.
+        assert_eq!(
+            event
+                .value()
+                .unwrap()
+                .logentry
+                .value()
+                .unwrap()
+                .formatted
+                .as_str(),
+            Some("request from 127.0.0.1")
+        );
+    }
+
+    #[test]
+    fn test_replaced_range() {
+        assert_eq!(replaced_range("from 127.0.0.1", Some("from [ip]")), (5, 14));
+        assert_eq!(replaced_range("a 127.0.0.1 b", Some("a [ip] b")), (2, 11));
+        assert_eq!(
+            replaced_range("jane@example.com", Some("****************")),
+            (0, 16)
+        );
+        assert_eq!(replaced_range("héllo", None), (0, 5));
+        // Replacements that repeat characters of the original value do not overlap.
+        assert_eq!(replaced_range("aa", Some("a")), (1, 2));
+    }
+
+    #[test]
+    fn test_explain_pii_scope() {
+        let config = serde_json::from_str::<PiiConfig>(
+            r#"{
+                "applications": {"$string": ["@ip"]},
+                "scopes": {"$string": ["transaction"]}
+            }"#,
+        )
+        .unwrap();
+
+        let event =
+            Annotated::<Event>::from_json(r#"{"logentry": {"formatted": "127.0.0.1"}}"#).unwrap();
+
+        let unscoped = explain_pii(&event, PiiProcessor::new(config.compiled())).unwrap();
+        assert!(unscoped.is_empty());
+
+        let processor = PiiProcessor::new(config.compiled()).with_scope(DataCategory::Transaction);
+        let scoped = explain_pii(&event, processor).unwrap();
+        assert_eq!(scoped.len(), 1);
+        assert_eq!(
+            scoped[0].scope,
+            Some(BTreeSet::from([DataCategory::Transaction]))
+        );
+    }
+}
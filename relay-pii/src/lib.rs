@@ -10,7 +10,9 @@ mod attachments;
 mod builtin;
 mod compiledconfig;
 mod config;
+mod containers;
 mod convert;
+mod explain;
 mod generate_selectors;
 mod legacy;
 mod minidumps;
@@ -19,10 +21,13 @@ mod redactions;
 mod regexes;
 mod selector;
 mod utils;
+mod validation;
 
 pub use self::attachments::*;
 pub use self::compiledconfig::*;
 pub use self::config::*;
+pub use self::containers::*;
+pub use self::explain::*;
 pub use self::generate_selectors::selector_suggestions_from_value;
 pub use self::legacy::*;
 pub use self::minidumps::*;
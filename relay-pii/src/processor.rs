@@ -4,6 +4,7 @@ use std::mem;
 use std::sync::OnceLock;
 
 use regex::Regex;
+use relay_base_schema::data_category::DataCategory;
 use relay_event_schema::processor::{
     self, enum_set, process_value, Chunk, Pii, ProcessValue, ProcessingAction, ProcessingResult,
     ProcessingState, Processor, ValueType,
@@ -15,13 +16,17 @@ use relay_protocol::{Annotated, Array, Meta, Remark, RemarkType, Value};
 
 use crate::compiledconfig::{CompiledPiiConfig, RuleRef};
 use crate::config::RuleType;
+use crate::explain::{self, PiiExplanation};
 use crate::redactions::Redaction;
 use crate::regexes::{self, PatternType, ReplaceBehavior, ANYTHING_REGEX};
-use crate::utils;
+use crate::{utils, validation};
 
 /// A processor that performs PII stripping.
 pub struct PiiProcessor<'a> {
     compiled_config: &'a CompiledPiiConfig,
+    hash_key: Option<&'a str>,
+    scope: Option<DataCategory>,
+    explanations: Option<Vec<PiiExplanation>>,
 }
 
 impl<'a> PiiProcessor<'a> {
@@ -29,11 +34,46 @@ impl<'a> PiiProcessor<'a> {
     pub fn new(compiled_config: &'a CompiledPiiConfig) -> PiiProcessor<'a> {
         // this constructor needs to be cheap... a new PiiProcessor is created for each event. Move
         // any init logic into CompiledPiiConfig::new.
-        PiiProcessor { compiled_config }
+        PiiProcessor {
+            compiled_config,
+            hash_key: None,
+            scope: None,
+            explanations: None,
+        }
+    }
+
+    /// Sets the secret key used by [`Redaction::KeyedHash`].
+    ///
+    /// Without a key, keyed hash redactions remove the value.
+    pub fn with_hash_key(mut self, hash_key: Option<&'a str>) -> Self {
+        self.hash_key = hash_key;
+        self
+    }
+
+    /// Sets the data category of the processed data.
+    ///
+    /// Applications scoped to data categories in the PII config only apply if their scope
+    /// contains this category. Without a category, only unscoped applications apply.
+    pub fn with_scope(mut self, category: DataCategory) -> Self {
+        self.scope = Some(category);
+        self
+    }
+
+    /// Records every rule application that changes or removes a value.
+    ///
+    /// See [`explain_pii`](crate::explain_pii).
+    pub(crate) fn with_explanations(mut self) -> Self {
+        self.explanations = Some(Vec::new());
+        self
+    }
+
+    /// Returns the rule applications recorded since [`Self::with_explanations`].
+    pub(crate) fn into_explanations(self) -> Vec<PiiExplanation> {
+        self.explanations.unwrap_or_default()
     }
 
     fn apply_all_rules(
-        &self,
+        &mut self,
         meta: &mut Meta,
         state: &ProcessingState<'_>,
         mut value: Option<&mut String>,
@@ -43,12 +83,49 @@ impl<'a> PiiProcessor<'a> {
             return Ok(());
         }
 
-        for (selector, rules) in self.compiled_config.applications.iter() {
+        let compiled_config = self.compiled_config;
+        for ((selector, rules), scope) in compiled_config.applications_for(self.scope) {
             if selector.matches_path(&state.path()) {
                 #[allow(clippy::needless_option_as_deref)]
                 for rule in rules {
+                    let original = self
+                        .explanations
+                        .is_some()
+                        .then(|| value.as_deref().cloned());
+
                     let reborrowed_value = value.as_deref_mut();
-                    apply_rule_to_value(meta, rule, state.path().key(), reborrowed_value)?;
+                    let result = apply_rule_to_value(
+                        meta,
+                        rule,
+                        state.path().key(),
+                        reborrowed_value,
+                        self.hash_key,
+                    );
+
+                    if let (Some(explanations), Some(original)) = (&mut self.explanations, original)
+                    {
+                        if result.is_err() || original.as_ref() != value.as_deref() {
+                            // Values are removed by returning an error.
+                            let scrubbed = value
+                                .as_deref()
+                                .map(String::as_str)
+                                .filter(|_| result.is_ok());
+                            let original_range = original
+                                .map(|original| explain::replaced_range(&original, scrubbed));
+
+                            explanations.push(PiiExplanation {
+                                path: state.path().to_string(),
+                                selector: selector.clone(),
+                                rule_id: rule.origin.clone(),
+                                redaction: rule.redaction.clone(),
+                                scope: scope.cloned(),
+                                original_range,
+                                original_length: original_range.map(|(start, end)| end - start),
+                            });
+                        }
+                    }
+
+                    result?;
                 }
             }
         }
@@ -379,6 +456,7 @@ fn apply_rule_to_value(
     rule: &RuleRef,
     key: Option<&str>,
     mut value: Option<&mut String>,
+    hash_key: Option<&str>,
 ) -> ProcessingResult {
     // The rule might specify to remove or to redact. If redaction is chosen, we need to
     // chunk up the value, otherwise we need to simply mark the value for deletion.
@@ -396,7 +474,7 @@ fn apply_rule_to_value(
         ($regex:expr, $replace_behavior:expr) => {
             if let Some(ref mut value) = value {
                 processor::process_chunked_value(value, meta, |chunks| {
-                    apply_regex_to_chunks(chunks, rule, $regex, $replace_behavior)
+                    apply_regex_to_chunks(chunks, rule, $regex, $replace_behavior, hash_key)
                 });
             }
         };
@@ -434,6 +512,7 @@ fn apply_regex_to_chunks<'a>(
     rule: &RuleRef,
     regex: &Regex,
     replace_behavior: ReplaceBehavior,
+    hash_key: Option<&str>,
 ) -> Vec<Chunk<'a>> {
     // NB: This function allocates the entire string and all chunks a second time. This means it
     // cannot reuse chunks and reallocates them. Ideally, we would be able to run the regex directly
@@ -456,8 +535,12 @@ fn apply_regex_to_chunks<'a>(
         return chunks;
     }
 
-    // Early exit if this regex does not match and return the original chunks.
-    let mut captures_iter = regex.captures_iter(&search_string).peekable();
+    // Early exit if this regex does not match and return the original chunks. Matches that fail
+    // validation for the rule type are skipped entirely.
+    let mut captures_iter = regex
+        .captures_iter(&search_string)
+        .filter(|captures| validation::is_valid_match(&rule.ty, captures))
+        .peekable();
     if captures_iter.peek().is_none() {
         return chunks;
     }
@@ -513,7 +596,7 @@ fn apply_regex_to_chunks<'a>(
                                 &mut rv,
                                 &mut replacement_chunks,
                             );
-                            insert_replacement_chunks(rule, g.as_str(), &mut rv);
+                            insert_replacement_chunks(rule, g.as_str(), &mut rv, hash_key);
                             pos = g.end();
                         }
                     }
@@ -526,23 +609,28 @@ fn apply_regex_to_chunks<'a>(
             // We only want to replace a string value, and the replacement chunk for that is
             // inserted by insert_replacement_chunks. Adding chunks from replacement_chunks
             // results in the incorrect behavior of a total of more chunks than the input.
-            insert_replacement_chunks(rule, &search_string, &mut rv);
+            insert_replacement_chunks(rule, &search_string, &mut rv, hash_key);
         }
     }
 
     rv
 }
 
-fn insert_replacement_chunks(rule: &RuleRef, text: &str, output: &mut Vec<Chunk<'_>>) {
-    match &rule.redaction {
-        Redaction::Default | Redaction::Remove => {
+fn insert_replacement_chunks(
+    rule: &RuleRef,
+    text: &str,
+    output: &mut Vec<Chunk<'_>>,
+    hash_key: Option<&str>,
+) {
+    match (&rule.redaction, hash_key) {
+        (Redaction::Default | Redaction::Remove, _) | (Redaction::KeyedHash, None) => {
             output.push(Chunk::Redaction {
                 text: Cow::Borrowed(""),
                 rule_id: Cow::Owned(rule.origin.to_string()),
                 ty: RemarkType::Removed,
             });
         }
-        Redaction::Mask => {
+        (Redaction::Mask, _) => {
             let buf = vec!['*'; text.chars().count()];
 
             output.push(Chunk::Redaction {
@@ -551,21 +639,28 @@ fn insert_replacement_chunks(rule: &RuleRef, text: &str, output: &mut Vec<Chunk<
                 text: buf.into_iter().collect(),
             })
         }
-        Redaction::Hash => {
+        (Redaction::Hash, _) => {
             output.push(Chunk::Redaction {
                 ty: RemarkType::Pseudonymized,
                 rule_id: Cow::Owned(rule.origin.to_string()),
                 text: Cow::Owned(utils::hash_value(text.as_bytes())),
             });
         }
-        Redaction::Replace(replace) => {
+        (Redaction::KeyedHash, Some(key)) => {
+            output.push(Chunk::Redaction {
+                ty: RemarkType::Pseudonymized,
+                rule_id: Cow::Owned(rule.origin.to_string()),
+                text: Cow::Owned(utils::hash_value_with_key(key.as_bytes(), text.as_bytes())),
+            });
+        }
+        (Redaction::Replace(replace), _) => {
             output.push(Chunk::Redaction {
                 ty: RemarkType::Substituted,
                 rule_id: Cow::Owned(rule.origin.to_string()),
                 text: Cow::Owned(replace.text.clone()),
             });
         }
-        Redaction::Other => relay_log::warn!("Incoming redaction is not supported"),
+        (Redaction::Other, _) => relay_log::warn!("Incoming redaction is not supported"),
     }
 }
 
@@ -686,6 +781,107 @@ mod tests {
         assert_annotated_snapshot!(event);
     }
 
+    #[test]
+    fn test_redact_keyed_hash() {
+        let config = serde_json::from_str::<PiiConfig>(
+            r#"
+            {
+                "rules": {
+                    "email_hmac": {
+                        "type": "email",
+                        "redaction": {"method": "keyed_hash"}
+                    }
+                },
+                "applications": {
+                    "$string": ["email_hmac"]
+                }
+            }
+            "#,
+        )
+        .unwrap();
+
+        let scrub = |hash_key: Option<&str>| {
+            let mut event = Annotated::new(Event {
+                extra: {
+                    let mut map = Object::new();
+                    map.insert(
+                        "email".to_string(),
+                        Annotated::new(ExtraValue(Value::String("test@example.com".to_string()))),
+                    );
+                    Annotated::new(map)
+                },
+                ..Default::default()
+            });
+
+            let mut processor = PiiProcessor::new(config.compiled()).with_hash_key(hash_key);
+            process_value(&mut event, &mut processor, ProcessingState::root()).unwrap();
+
+            let extra = event.value().unwrap().extra.value().unwrap();
+            let value = extra.get("email").unwrap().value().unwrap();
+            value.0.as_str().unwrap().to_owned()
+        };
+
+        let hashed = scrub(Some("secret"));
+        assert_eq!(
+            hashed,
+            utils::hash_value_with_key(b"secret", b"test@example.com")
+        );
+        // The pseudonym is stable, but differs from unkeyed hashes and other keys.
+        assert_eq!(hashed, scrub(Some("secret")));
+        assert_ne!(hashed, utils::hash_value(b"test@example.com"));
+        assert_ne!(hashed, scrub(Some("other")));
+
+        // Without a key, the value is removed.
+        assert_eq!(scrub(None), "");
+    }
+
+    #[test]
+    fn test_scoped_applications() {
+        let config = serde_json::from_str::<PiiConfig>(
+            r#"
+            {
+                "applications": {
+                    "extra.token": ["@anything:remove"],
+                    "$string": ["@email"]
+                },
+                "scopes": {
+                    "extra.token": ["transaction", "replay"]
+                }
+            }
+            "#,
+        )
+        .unwrap();
+
+        let scrub = |scope: Option<DataCategory>| {
+            let mut event = Annotated::<Event>::from_json(
+                r#"{"extra": {"token": "abc", "contact": "test@example.com"}}"#,
+            )
+            .unwrap();
+
+            let mut processor = PiiProcessor::new(config.compiled());
+            if let Some(scope) = scope {
+                processor = processor.with_scope(scope);
+            }
+            process_value(&mut event, &mut processor, ProcessingState::root()).unwrap();
+
+            let extra = event.value().unwrap().extra.value().unwrap();
+            let token = extra.get("token").unwrap().value().is_some();
+            let contact = extra.get("contact").unwrap().value().unwrap();
+            (token, contact.0.as_str().unwrap().to_owned())
+        };
+
+        // Unscoped applications apply to all data, scoped ones only to their categories.
+        assert_eq!(scrub(None), (true, "[email]".to_owned()));
+        assert_eq!(
+            scrub(Some(DataCategory::Error)),
+            (true, "[email]".to_owned())
+        );
+        assert_eq!(
+            scrub(Some(DataCategory::Transaction)),
+            (false, "[email]".to_owned())
+        );
+    }
+
     #[test]
     fn test_redact_containers() {
         let config = serde_json::from_str::<PiiConfig>(
@@ -1240,6 +1436,7 @@ mod tests {
             &rule,
             &Regex::new(r#".*"#).unwrap(),
             ReplaceBehavior::Value,
+            None,
         );
         assert_eq!(chunks, res);
     }
@@ -1264,6 +1461,7 @@ mod tests {
             &rule,
             &Regex::new(r#".*"#).unwrap(),
             ReplaceBehavior::Groups(smallvec::smallvec![0]),
+            None,
         );
         assert_eq!(chunks, res);
     }
@@ -47,6 +47,13 @@ pub enum Redaction {
     Mask,
     /// Replaces the value with a hash
     Hash,
+    /// Replaces the value with a keyed hash (HMAC).
+    ///
+    /// The key is not part of the PII config. It is configured in Relay or the project config and
+    /// passed to the processor, see [`PiiProcessor::with_hash_key`](crate::PiiProcessor::with_hash_key).
+    /// This produces stable pseudonyms that cannot be reversed by hashing candidate values. If no
+    /// key is available, the value is removed instead.
+    KeyedHash,
     /// Added for forward compatibility as catch-all variant.
     #[serde(other, skip_serializing)]
     Other,
@@ -68,6 +75,14 @@ mod tests {
         assert!(deser == redaction);
     }
 
+    #[test]
+    fn test_redaction_deser_keyed_hash() {
+        let json = r#"{"method": "keyed_hash"}"#;
+
+        let deser: Redaction = serde_json::from_str(json).unwrap();
+        assert_eq!(deser, Redaction::KeyedHash);
+    }
+
     #[test]
     fn test_redaction_deser_other() {
         let json = r#"{"method": "foo", "text": "[filter]"}"#;
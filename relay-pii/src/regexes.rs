@@ -93,6 +93,14 @@ pub fn get_regex_for_rule_type(
         RuleType::UrlAuth => smallvec![(v, &*URL_AUTH_REGEX, ReplaceBehavior::replace_group(1))],
         RuleType::UsSsn => smallvec![(v, &*US_SSN_REGEX, ReplaceBehavior::replace_match())],
         RuleType::Userpath => smallvec![(v, &*PATH_REGEX, ReplaceBehavior::replace_group(1))],
+        RuleType::Phone => smallvec![(v, &*PHONE_REGEX, ReplaceBehavior::replace_match())],
+        RuleType::Address => smallvec![
+            (v, &*STREET_ADDRESS_REGEX, ReplaceBehavior::replace_match()),
+            (v, &*STRASSE_ADDRESS_REGEX, ReplaceBehavior::replace_match()),
+        ],
+        RuleType::NationalId => {
+            smallvec![(v, &*NATIONAL_ID_REGEX, ReplaceBehavior::replace_group(1))]
+        }
 
         // These ought to have been resolved in CompiledConfig
         RuleType::Alias(_) | RuleType::Multiple(_) | RuleType::Unknown(_) => smallvec![],
@@ -284,6 +292,87 @@ static US_SSN_REGEX: Lazy<Regex> = Lazy::new(|| {
     .unwrap()
 });
 
+// International phone numbers, which must start with `+` or `00` followed by the country calling
+// code. The number of digits is validated after matching, see `validation::is_valid_phone`.
+static PHONE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?x)
+            (?:\+|\b00)
+            [1-9]\d{0,2}
+            (?:[-.\ ]?\(\d{1,4}\))?
+            (?:[-.\ ]?\d{1,4}){2,6}
+            \b
+        ",
+    )
+    .unwrap()
+});
+
+// Street addresses with a house number followed by the street name, as common in English
+// speaking countries (e.g. "221B Baker Street").
+static STREET_ADDRESS_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?x)
+            \b
+            \d{1,5}[A-Za-z]?
+            (?:\ [A-Z][A-Za-z'-]*){1,4}
+            \ (?:
+                Street|St|Avenue|Ave|Road|Rd|Boulevard|Blvd|Lane|Ln|Drive|Dr|
+                Court|Ct|Place|Pl|Square|Sq|Terrace|Way
+            )
+            \b\.?
+        ",
+    )
+    .unwrap()
+});
+
+// Street addresses with the street name followed by the house number, as common in German
+// speaking countries (e.g. "Hauptstraße 12a").
+static STRASSE_ADDRESS_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?x)
+            \b
+            (?:[A-ZÄÖÜ][\w-]*\ )?
+            [A-ZÄÖÜ][\w-]*?
+            (?:straße|strasse|Straße|Strasse|weg|Weg|gasse|Gasse|platz|Platz|allee|Allee|ring|Ring)
+            \ \d{1,4}[a-z]?
+            \b
+        ",
+    )
+    .unwrap()
+});
+
+// Candidates for national identification numbers. Every alternative only describes the shape of
+// the number, the check digits are verified after matching, see `validation::is_valid_national_id`.
+//
+// Plain digit sequences are too common to be scrubbed based on their check digits alone, so they
+// are only accepted after a label naming the identifier. Only the number in the first group is
+// replaced, the label is retained.
+static NATIONAL_ID_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?x)
+            (?:
+                \b(?i:
+                    steuer-?id(?:entifikationsnummer)? | idnr | tin
+                    | pesel
+                    | bsn | burgerservicenummer | sofinummer
+                )
+                \ ?(?:nr\.?|no\.?)?\ ?[:\#=]?\ ?
+            )?
+            \b(
+                # Spain: DNI and NIE
+                [XYZ]?\d{7,8}-?[A-Z]
+                # Italy: codice fiscale
+                | [A-Z]{6}[0-9LMNPQRSTUV]{2}[A-EHLMPRST][0-9LMNPQRSTUV]{2}[A-Z][0-9LMNPQRSTUV]{3}[A-Z]
+                # France: NIR
+                | [12]\ ?\d{2}\ ?\d{2}\ ?(?:\d{2}|2A|2B)\ ?\d{3}\ ?\d{3}\ ?\d{2}
+                # Germany: Steuer-ID, Poland: PESEL, Netherlands: BSN
+                | \d{11} | \d{9}
+            )\b
+        ",
+    )
+    .unwrap()
+});
+
 static PASSWORD_KEY_REGEX: Lazy<Regex> = Lazy::new(|| {
     Regex::new(
         r"(?i)(password|secret|passwd|api_key|apikey|auth|credentials|mysql_pwd|privatekey|private_key|token|bearer)"
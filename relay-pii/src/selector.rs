@@ -258,6 +258,17 @@ impl SelectorSpec {
             SelectorSpec::Not(ref x) => !x.matches_path(path),
         }
     }
+
+    /// Returns `true` if any part of the selector refers to the given value type.
+    pub(crate) fn refers_to_type(&self, ty: ValueType) -> bool {
+        match *self {
+            SelectorSpec::And(ref xs) | SelectorSpec::Or(ref xs) => {
+                xs.iter().any(|x| x.refers_to_type(ty))
+            }
+            SelectorSpec::Not(ref x) => x.refers_to_type(ty),
+            SelectorSpec::Path(ref path_items) => path_items.contains(&SelectorPathItem::Type(ty)),
+        }
+    }
 }
 
 impl fmt::Display for SelectorSpec {
@@ -658,4 +669,20 @@ mod tests {
         // WAT.  We have the full path to a field here.
         assert_matches_pii_true!(minidump_state_inner, "$attachments.$minidump.$binary",);
     }
+
+    #[test]
+    fn test_refers_to_type() {
+        let refers = |s: &str| {
+            SelectorSpec::from_str(s)
+                .unwrap()
+                .refers_to_type(ValueType::Binary)
+        };
+
+        assert!(refers("$binary"));
+        assert!(refers("$attachments.$binary"));
+        assert!(refers("$string || $binary"));
+        assert!(refers("$string && !$binary"));
+        assert!(!refers("$string"));
+        assert!(!refers("$attachments.**"));
+    }
 }
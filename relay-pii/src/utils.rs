@@ -38,7 +38,16 @@ pub fn process_pairlist<P: Processor, T: ProcessValue + AsPair>(
 }
 
 pub fn hash_value(data: &[u8]) -> String {
-    let mut mac = Hmac::<Sha1>::new_from_slice(&[]).unwrap();
+    hash_value_with_key(&[], data)
+}
+
+/// Computes an HMAC of `data` with a secret `key`.
+///
+/// Unlike [`hash_value`], the result cannot be reversed by hashing candidate values unless the key
+/// is known.
+pub fn hash_value_with_key(key: &[u8], data: &[u8]) -> String {
+    // HMAC accepts keys of any length, so this never fails.
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).unwrap();
     mac.update(data);
     format!("{:X}", mac.finalize().into_bytes())
 }
@@ -0,0 +1,287 @@
+//! Validation of regex matches for builtin rules.
+//!
+//! Some PII cannot be described precisely by a regular expression alone. For these rule types,
+//! the regex only describes the shape of a candidate, and matches are validated here before they
+//! are redacted. This keeps the false positive rate of rules like [`RuleType::NationalId`] low,
+//! since arbitrary numbers of the same length are unlikely to carry valid check digits.
+
+use regex::Captures;
+
+use crate::config::RuleType;
+
+/// Returns the index of the capture group that holds the number validated for rule type `ty`.
+///
+/// National identification numbers may be preceded by a label, which is not part of the number.
+pub fn number_group(ty: &RuleType) -> usize {
+    match ty {
+        RuleType::NationalId => 1,
+        _ => 0,
+    }
+}
+
+/// Returns `true` if the regex match `captures` should be redacted by a rule of type `ty`.
+///
+/// Rule types without additional validation accept all matches.
+pub fn is_valid_match(ty: &RuleType, captures: &Captures<'_>) -> bool {
+    let number = &captures[number_group(ty)];
+    // The match is longer than the number if it is preceded by a label.
+    is_valid_number(ty, number, captures[0].len() > number.len())
+}
+
+/// Returns `true` if the `number` matched by a rule of type `ty` should be redacted.
+///
+/// `number` is the text of the capture group returned by [`number_group`], and `labeled` is
+/// `true` if the match contains a label in addition to the number.
+pub fn is_valid_number(ty: &RuleType, number: &str, labeled: bool) -> bool {
+    match ty {
+        RuleType::Phone => is_valid_phone(number),
+        RuleType::NationalId => is_valid_national_id(number, labeled),
+        _ => true,
+    }
+}
+
+/// Validates the length of an international phone number.
+///
+/// E.164 limits phone numbers including the country calling code to 15 digits. Numbers shorter
+/// than 8 digits are not accepted, as they are more likely to be other numeric values.
+fn is_valid_phone(text: &str) -> bool {
+    let digits = text.chars().filter(char::is_ascii_digit).count();
+    // The international call prefix `00` is not part of the number.
+    let digits = match text.starts_with("00") {
+        true => digits.saturating_sub(2),
+        false => digits,
+    };
+
+    (8..=15).contains(&digits)
+}
+
+/// Validates the check digits of a national identification number.
+///
+/// The number format is inferred from its length and characters. Supported are:
+///
+///  - Spain: DNI and NIE
+///  - Italy: codice fiscale
+///  - France: NIR (numéro de sécurité sociale)
+///  - Germany: Steuerliche Identifikationsnummer
+///  - Poland: PESEL
+///  - Netherlands: BSN (burgerservicenummer)
+///
+/// Numbers consisting only of digits are only accepted if they are `labeled` with the name of the
+/// identifier, since plain digit sequences pass the check digits too often. Degenerate numbers,
+/// where all digits are the same, are never accepted.
+fn is_valid_national_id(text: &str, labeled: bool) -> bool {
+    let id: String = text.chars().filter(|c| !matches!(c, ' ' | '-')).collect();
+    if !id.is_ascii() {
+        return false;
+    }
+
+    let bytes = id.as_bytes();
+    if bytes.iter().all(|&byte| byte == bytes[0]) {
+        return false;
+    }
+
+    let numeric = bytes.iter().all(u8::is_ascii_digit);
+    if numeric && matches!(bytes.len(), 9 | 11) && !labeled {
+        return false;
+    }
+
+    match bytes.len() {
+        9 if numeric => is_valid_bsn(bytes),
+        9 => is_valid_dni(bytes),
+        11 if numeric => is_valid_steuer_id(bytes) || is_valid_pesel(bytes),
+        15 => is_valid_nir(&id),
+        16 => is_valid_codice_fiscale(bytes),
+        _ => false,
+    }
+}
+
+/// Converts an ASCII digit into its numeric value.
+fn digit(byte: u8) -> u32 {
+    u32::from(byte - b'0')
+}
+
+/// Spanish DNI (8 digits) or NIE (`X`, `Y` or `Z` followed by 7 digits) with a check letter.
+fn is_valid_dni(bytes: &[u8]) -> bool {
+    const LETTERS: &[u8; 23] = b"TRWAGMYFPDXBNJZSQVHLCKE";
+
+    let (number, check) = bytes.split_at(bytes.len() - 1);
+    let mut value = match number[0] {
+        b'X' => 0,
+        b'Y' => 1,
+        b'Z' => 2,
+        b'0'..=b'9' => digit(number[0]),
+        _ => return false,
+    };
+
+    for &byte in &number[1..] {
+        if !byte.is_ascii_digit() {
+            return false;
+        }
+        value = value * 10 + digit(byte);
+    }
+
+    LETTERS[(value % 23) as usize] == check[0]
+}
+
+/// Italian codice fiscale with a check letter over the first 15 characters.
+fn is_valid_codice_fiscale(bytes: &[u8]) -> bool {
+    // Values of characters at odd positions, indexed by `0-9` and `A-Z` respectively.
+    const ODD: [u32; 26] = [
+        1, 0, 5, 7, 9, 13, 15, 17, 19, 21, 2, 4, 18, 20, 11, 3, 6, 8, 12, 14, 16, 10, 22, 25, 24,
+        23,
+    ];
+
+    let mut sum = 0;
+    for (index, &byte) in bytes[..15].iter().enumerate() {
+        let value = match byte {
+            b'0'..=b'9' => digit(byte),
+            b'A'..=b'Z' => u32::from(byte - b'A'),
+            _ => return false,
+        };
+
+        // Positions are counted from 1, so even indexes are odd positions.
+        sum += match index % 2 {
+            0 => ODD[value as usize],
+            _ => value,
+        };
+    }
+
+    u32::from(bytes[15]) == u32::from(b'A') + sum % 26
+}
+
+/// French NIR with a two digit key, where the departments of Corsica are encoded as `2A` and `2B`.
+fn is_valid_nir(id: &str) -> bool {
+    let (number, key) = id.split_at(13);
+    let number = number.replace("2A", "19").replace("2B", "18");
+
+    let (Ok(number), Ok(key)) = (number.parse::<u64>(), key.parse::<u64>()) else {
+        return false;
+    };
+
+    97 - number % 97 == key
+}
+
+/// German tax identification number with an ISO 7064 MOD 11,10 check digit.
+fn is_valid_steuer_id(bytes: &[u8]) -> bool {
+    if bytes[0] == b'0' {
+        return false;
+    }
+
+    // Within the first ten digits, exactly one digit occurs two or three times.
+    let mut counts = [0u8; 10];
+    for &byte in &bytes[..10] {
+        counts[digit(byte) as usize] += 1;
+    }
+    let repeated = counts.iter().filter(|&&count| count > 1).count();
+    if repeated != 1 || counts.iter().any(|&count| count > 3) {
+        return false;
+    }
+
+    let mut product = 10;
+    for &byte in &bytes[..10] {
+        let sum = match (digit(byte) + product) % 10 {
+            0 => 10,
+            sum => sum,
+        };
+        product = (sum * 2) % 11;
+    }
+
+    let check = match 11 - product {
+        10 => 0,
+        check => check,
+    };
+
+    check == digit(bytes[10])
+}
+
+/// Polish PESEL with a weighted check digit.
+fn is_valid_pesel(bytes: &[u8]) -> bool {
+    const WEIGHTS: [u32; 10] = [1, 3, 7, 9, 1, 3, 7, 9, 1, 3];
+
+    let sum: u32 = WEIGHTS
+        .iter()
+        .zip(bytes)
+        .map(|(weight, &byte)| weight * digit(byte))
+        .sum();
+
+    (10 - sum % 10) % 10 == digit(bytes[10])
+}
+
+/// Dutch BSN, validated with the "elfproef" (eleven test).
+fn is_valid_bsn(bytes: &[u8]) -> bool {
+    // BSNs are issued starting at 10000000, shorter numbers are padded with leading zeros.
+    if bytes[..2] == *b"00" {
+        return false;
+    }
+
+    let sum: i64 = bytes[..8]
+        .iter()
+        .enumerate()
+        .map(|(index, &byte)| (9 - index as i64) * i64::from(digit(byte)))
+        .sum();
+
+    (sum - i64::from(digit(bytes[8]))) % 11 == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phone() {
+        assert!(is_valid_phone("+1 415 555 2671"));
+        assert!(is_valid_phone("+49 (30) 1234567"));
+        assert!(is_valid_phone("0044 20 7946 0958"));
+        assert!(!is_valid_phone("+1 234"));
+        assert!(!is_valid_phone("+49 30 1234 5678 9012 3456"));
+    }
+
+    #[test]
+    fn test_national_id() {
+        for valid in [
+            "12345678Z",             // DNI
+            "X1234567L",             // NIE
+            "RSSMRA85T10A562S",      // codice fiscale
+            "1 85 05 78 006 084 91", // NIR
+            "86095742719",           // Steuer-ID
+            "44051401359",           // PESEL
+            "111222333",             // BSN
+        ] {
+            assert!(is_valid_national_id(valid, true), "{valid}");
+        }
+
+        for invalid in [
+            "12345678A",
+            "RSSMRA85T10A562T",
+            "1 85 05 78 006 084 92",
+            "86095742718",
+            "111222334",
+            "123456789012",
+        ] {
+            assert!(!is_valid_national_id(invalid, true), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_national_id_unlabeled() {
+        assert!(is_valid_national_id("12345678Z", false));
+        assert!(is_valid_national_id("1 85 05 78 006 084 91", false));
+
+        for unlabeled in ["86095742719", "44051401359", "111222333"] {
+            assert!(!is_valid_national_id(unlabeled, false), "{unlabeled}");
+        }
+    }
+
+    #[test]
+    fn test_national_id_degenerate() {
+        for degenerate in [
+            "000000000",
+            "00000000000",
+            "999999999",
+            "000000012", // passes the eleven test
+            "ZZZZZZZZZ",
+        ] {
+            assert!(!is_valid_national_id(degenerate, true), "{degenerate}");
+        }
+    }
+}
@@ -11,12 +11,15 @@ use std::collections::{BTreeMap, HashMap};
 use android_trace_log::chrono::{DateTime, Utc};
 use android_trace_log::{AndroidTraceLog, Clock, Vm};
 use data_encoding::BASE64_NOPAD;
+use relay_event_schema::processor::{ProcessingState, ValueType};
 use relay_event_schema::protocol::{EventId, SpanId};
 use serde::{Deserialize, Serialize};
 
+use crate::info::{non_empty, ProfileInfo};
 use crate::measurements::Measurement;
 use crate::native_debug_image::NativeDebugImage;
 use crate::sample::v1::SampleProfile;
+use crate::scrubbing::ProfileScrubber;
 use crate::transaction_metadata::TransactionMetadata;
 use crate::utils::{deserialize_number_from_string, is_zero};
 use crate::{ProfileError, MAX_PROFILE_DURATION};
@@ -93,6 +96,20 @@ pub struct ProfileMetadata {
     debug_meta: Option<DebugMeta>,
 }
 
+impl ProfileMetadata {
+    /// Returns the attributes of the profile that inbound filters apply to.
+    pub(crate) fn info(&self) -> ProfileInfo {
+        ProfileInfo {
+            profile_id: self.profile_id,
+            platform: self.platform.clone(),
+            release: non_empty(&self.release),
+            environment: non_empty(&self.environment),
+            dist: non_empty(&self.dist),
+            transaction: non_empty(&self.transaction_name),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct AndroidProfilingEvent {
     #[serde(flatten)]
@@ -114,6 +131,37 @@ struct DebugMeta {
 }
 
 impl AndroidProfilingEvent {
+    /// Applies PII rules to the transaction metadata and the JavaScript profile.
+    ///
+    /// Method names and source files of the Android trace are not scrubbed.
+    fn scrub(&mut self, scrubber: &mut ProfileScrubber<'_>) {
+        let state = ProcessingState::root();
+
+        if let Some(ref mut js_profile) = self.js_profile {
+            let js_state = state.enter_static("js_profile", None, ValueType::Object);
+            js_profile.scrub(scrubber, &js_state);
+        }
+
+        scrubber.scrub_name(
+            "transaction_name",
+            &mut self.metadata.transaction_name,
+            state,
+        );
+        if let Some(ref mut transaction) = self.metadata.transaction {
+            scrubber.scrub_transaction(transaction, state);
+        }
+        scrubber.scrub_map(
+            "transaction_metadata",
+            &mut self.metadata.transaction_metadata,
+            state,
+        );
+        scrubber.scrub_map(
+            "transaction_tags",
+            &mut self.metadata.transaction_tags,
+            state,
+        );
+    }
+
     fn default() -> AndroidTraceLog {
         AndroidTraceLog {
             data_file_overflow: Default::default(),
@@ -205,6 +253,7 @@ pub fn parse_android_profile(
     payload: &[u8],
     transaction_metadata: BTreeMap<String, String>,
     transaction_tags: BTreeMap<String, String>,
+    scrubber: Option<&mut ProfileScrubber<'_>>,
 ) -> Result<Vec<u8>, ProfileError> {
     let mut profile = parse_profile(payload)?;
 
@@ -240,6 +289,10 @@ pub fn parse_android_profile(
     profile.metadata.transaction_metadata = transaction_metadata;
     profile.metadata.transaction_tags = transaction_tags;
 
+    if let Some(scrubber) = scrubber {
+        profile.scrub(scrubber);
+    }
+
     serde_json::to_vec(&profile).map_err(|_| ProfileError::CannotSerializePayload)
 }
 
@@ -253,9 +306,13 @@ mod tests {
         let profile = parse_profile(payload);
         assert!(profile.is_ok());
         let data = serde_json::to_vec(&profile.unwrap());
-        assert!(
-            parse_android_profile(&(data.unwrap())[..], BTreeMap::new(), BTreeMap::new()).is_ok()
-        );
+        assert!(parse_android_profile(
+            &(data.unwrap())[..],
+            BTreeMap::new(),
+            BTreeMap::new(),
+            None
+        )
+        .is_ok());
     }
 
     #[test]
@@ -264,22 +321,26 @@ mod tests {
         let profile = parse_profile(payload);
         assert!(profile.is_ok());
         let data = serde_json::to_vec(&profile.unwrap());
-        assert!(
-            parse_android_profile(&(data.unwrap())[..], BTreeMap::new(), BTreeMap::new()).is_ok()
-        );
+        assert!(parse_android_profile(
+            &(data.unwrap())[..],
+            BTreeMap::new(),
+            BTreeMap::new(),
+            None
+        )
+        .is_ok());
     }
 
     #[test]
     fn test_no_transaction() {
         let payload = include_bytes!("../tests/fixtures/android/no_transaction.json");
-        let data = parse_android_profile(payload, BTreeMap::new(), BTreeMap::new());
+        let data = parse_android_profile(payload, BTreeMap::new(), BTreeMap::new(), None);
         assert!(data.is_err());
     }
 
     #[test]
     fn test_remove_invalid_events() {
         let payload = include_bytes!("../tests/fixtures/android/remove_invalid_events.json");
-        let data = parse_android_profile(payload, BTreeMap::new(), BTreeMap::new());
+        let data = parse_android_profile(payload, BTreeMap::new(), BTreeMap::new(), None);
         assert!(data.is_err());
     }
 
@@ -315,7 +376,8 @@ mod tests {
         ]);
 
         let payload = include_bytes!("../tests/fixtures/android/valid.json");
-        let profile_json = parse_android_profile(payload, transaction_metadata, BTreeMap::new());
+        let profile_json =
+            parse_android_profile(payload, transaction_metadata, BTreeMap::new(), None);
         assert!(profile_json.is_ok());
 
         let payload = profile_json.unwrap();
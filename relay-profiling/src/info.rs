@@ -0,0 +1,72 @@
+use relay_event_schema::protocol::{Csp, Exception, LogEntry, Values};
+use relay_filter::Filterable;
+use relay_protocol::{Getter, Val};
+use url::Url;
+
+use crate::ProfileId;
+
+/// Attributes of a profile that inbound filters apply to.
+///
+/// This is returned by [`parse_metadata`](crate::parse_metadata) for profiles with valid metadata.
+#[derive(Clone, Debug)]
+pub struct ProfileInfo {
+    /// The unique identifier of the profile.
+    pub profile_id: ProfileId,
+    pub(crate) platform: String,
+    pub(crate) release: Option<String>,
+    pub(crate) environment: Option<String>,
+    pub(crate) dist: Option<String>,
+    pub(crate) transaction: Option<String>,
+}
+
+/// Returns `None` for empty strings, which profiles use for missing values.
+pub(crate) fn non_empty(value: &str) -> Option<String> {
+    (!value.is_empty()).then(|| value.to_owned())
+}
+
+impl Getter for ProfileInfo {
+    fn get_value(&self, path: &str) -> Option<Val<'_>> {
+        Some(match path.strip_prefix("event.")? {
+            "platform" => self.platform.as_str().into(),
+            "release" => self.release.as_deref()?.into(),
+            "environment" => self.environment.as_deref()?.into(),
+            "dist" => self.dist.as_deref()?.into(),
+            "transaction" => self.transaction.as_deref()?.into(),
+            _ => return None,
+        })
+    }
+}
+
+impl Filterable for ProfileInfo {
+    fn csp(&self) -> Option<&Csp> {
+        None
+    }
+
+    fn exceptions(&self) -> Option<&Values<Exception>> {
+        None
+    }
+
+    fn ip_addr(&self) -> Option<&str> {
+        None
+    }
+
+    fn logentry(&self) -> Option<&LogEntry> {
+        None
+    }
+
+    fn release(&self) -> Option<&str> {
+        self.release.as_deref()
+    }
+
+    fn transaction(&self) -> Option<&str> {
+        self.transaction.as_deref()
+    }
+
+    fn url(&self) -> Option<Url> {
+        None
+    }
+
+    fn user_agent(&self) -> Option<&str> {
+        None
+    }
+}
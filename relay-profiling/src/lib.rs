@@ -50,15 +50,19 @@ use serde_json::Deserializer;
 use crate::extract_from_transaction::{extract_transaction_metadata, extract_transaction_tags};
 
 pub use crate::error::ProfileError;
+pub use crate::info::ProfileInfo;
 pub use crate::outcomes::discard_reason;
+pub use crate::scrubbing::ProfileScrubber;
 
 mod android;
 mod error;
 mod extract_from_transaction;
+mod info;
 mod measurements;
 mod native_debug_image;
 mod outcomes;
 mod sample;
+mod scrubbing;
 mod transaction_metadata;
 mod utils;
 
@@ -85,7 +89,10 @@ fn minimal_profile_from_json(
     serde_path_to_error::deserialize(d)
 }
 
-pub fn parse_metadata(payload: &[u8], project_id: ProjectId) -> Result<ProfileId, ProfileError> {
+/// Parses and validates the metadata of a profile.
+///
+/// Returns the attributes of the profile that inbound filters apply to.
+pub fn parse_metadata(payload: &[u8], project_id: ProjectId) -> Result<ProfileInfo, ProfileError> {
     let profile = match minimal_profile_from_json(payload) {
         Ok(profile) => profile,
         Err(err) => {
@@ -97,10 +104,10 @@ pub fn parse_metadata(payload: &[u8], project_id: ProjectId) -> Result<ProfileId
             return Err(ProfileError::InvalidJson(err));
         }
     };
-    match profile.version {
+    let info = match profile.version {
         sample::Version::V1 => {
             let d = &mut Deserializer::from_slice(payload);
-            let _: sample::v1::ProfileMetadata = match serde_path_to_error::deserialize(d) {
+            let metadata: sample::v1::ProfileMetadata = match serde_path_to_error::deserialize(d) {
                 Ok(profile) => profile,
                 Err(err) => {
                     relay_log::warn!(
@@ -113,11 +120,12 @@ pub fn parse_metadata(payload: &[u8], project_id: ProjectId) -> Result<ProfileId
                     return Err(ProfileError::InvalidJson(err));
                 }
             };
+            metadata.info()
         }
         _ => match profile.platform.as_str() {
             "android" => {
                 let d = &mut Deserializer::from_slice(payload);
-                let _: android::ProfileMetadata = match serde_path_to_error::deserialize(d) {
+                let metadata: android::ProfileMetadata = match serde_path_to_error::deserialize(d) {
                     Ok(profile) => profile,
                     Err(err) => {
                         relay_log::warn!(
@@ -130,14 +138,22 @@ pub fn parse_metadata(payload: &[u8], project_id: ProjectId) -> Result<ProfileId
                         return Err(ProfileError::InvalidJson(err));
                     }
                 };
+                metadata.info()
             }
             _ => return Err(ProfileError::PlatformNotSupported),
         },
     };
-    Ok(profile.event_id)
+    Ok(info)
 }
 
-pub fn expand_profile(payload: &[u8], event: &Event) -> Result<(ProfileId, Vec<u8>), ProfileError> {
+/// Parses and normalizes a profile and adds metadata of its transaction.
+///
+/// If a `scrubber` is passed, PII rules are applied to the expanded profile.
+pub fn expand_profile(
+    payload: &[u8],
+    event: &Event,
+    scrubber: Option<&mut ProfileScrubber<'_>>,
+) -> Result<(ProfileId, Vec<u8>), ProfileError> {
     let profile = match minimal_profile_from_json(payload) {
         Ok(profile) => profile,
         Err(err) => {
@@ -157,13 +173,19 @@ pub fn expand_profile(payload: &[u8], event: &Event) -> Result<(ProfileId, Vec<u
     let transaction_metadata = extract_transaction_metadata(event);
     let transaction_tags = extract_transaction_tags(event);
     let processed_payload = match profile.version {
-        sample::Version::V1 => {
-            sample::v1::parse_sample_profile(payload, transaction_metadata, transaction_tags)
-        }
+        sample::Version::V1 => sample::v1::parse_sample_profile(
+            payload,
+            transaction_metadata,
+            transaction_tags,
+            scrubber,
+        ),
         _ => match profile.platform.as_str() {
-            "android" => {
-                android::parse_android_profile(payload, transaction_metadata, transaction_tags)
-            }
+            "android" => android::parse_android_profile(
+                payload,
+                transaction_metadata,
+                transaction_tags,
+                scrubber,
+            ),
             _ => return Err(ProfileError::PlatformNotSupported),
         },
     };
@@ -200,7 +222,13 @@ pub fn expand_profile(payload: &[u8], event: &Event) -> Result<(ProfileId, Vec<u
     }
 }
 
-pub fn expand_profile_chunk(payload: &[u8]) -> Result<Vec<u8>, ProfileError> {
+/// Parses and normalizes a profile chunk.
+///
+/// If a `scrubber` is passed, PII rules are applied to the expanded profile chunk.
+pub fn expand_profile_chunk(
+    payload: &[u8],
+    scrubber: Option<&mut ProfileScrubber<'_>>,
+) -> Result<Vec<u8>, ProfileError> {
     let profile = match minimal_profile_from_json(payload) {
         Ok(profile) => profile,
         Err(err) => {
@@ -216,6 +244,9 @@ pub fn expand_profile_chunk(payload: &[u8]) -> Result<Vec<u8>, ProfileError> {
         sample::Version::V2 => {
             let mut profile = sample::v2::parse(payload)?;
             profile.normalize()?;
+            if let Some(scrubber) = scrubber {
+                profile.scrub(scrubber);
+            }
             serde_json::to_vec(&profile).map_err(|_| ProfileError::CannotSerializePayload)
         }
         _ => Err(ProfileError::PlatformNotSupported),
@@ -242,21 +273,34 @@ mod tests {
         assert_eq!(profile.unwrap().version, sample::Version::Unknown);
     }
 
+    #[test]
+    fn test_parse_metadata_info() {
+        let payload = include_bytes!("../tests/fixtures/sample/v1/valid.json");
+        let info = parse_metadata(payload, ProjectId::new(1)).unwrap();
+        assert_eq!(info.platform, "cocoa");
+        assert_eq!(info.release.as_deref(), Some("0.1 (199)"));
+        assert_eq!(info.environment, None);
+        assert_eq!(
+            info.transaction.as_deref(),
+            Some("example_ios_movies_sources.MoviesViewController")
+        );
+    }
+
     #[test]
     fn test_expand_profile_with_version() {
         let payload = include_bytes!("../tests/fixtures/sample/v1/valid.json");
-        assert!(expand_profile(payload, &Event::default()).is_ok());
+        assert!(expand_profile(payload, &Event::default(), None).is_ok());
     }
 
     #[test]
     fn test_expand_profile_with_version_and_segment_id() {
         let payload = include_bytes!("../tests/fixtures/sample/v1/segment_id.json");
-        assert!(expand_profile(payload, &Event::default()).is_ok());
+        assert!(expand_profile(payload, &Event::default(), None).is_ok());
     }
 
     #[test]
     fn test_expand_profile_without_version() {
         let payload = include_bytes!("../tests/fixtures/android/roundtrip.json");
-        assert!(expand_profile(payload, &Event::default()).is_ok());
+        assert!(expand_profile(payload, &Event::default(), None).is_ok());
     }
 }
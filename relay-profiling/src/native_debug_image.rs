@@ -1,4 +1,5 @@
-use relay_event_schema::protocol::{Addr, DebugId, NativeImagePath};
+use relay_event_normalization::{canonical_debug_id, layout_images, pe_image_size, ImageRange};
+use relay_event_schema::protocol::{Addr, CodeId, DebugId, NativeImagePath};
 use serde::{Deserialize, Serialize};
 
 use crate::utils;
@@ -6,7 +7,9 @@ use crate::utils;
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 #[serde(rename_all = "lowercase")]
 enum ImageType {
+    Elf,
     MachO,
+    Pe,
     Symbolic,
     Sourcemap,
 }
@@ -17,6 +20,8 @@ pub struct NativeDebugImage {
     code_file: NativeImagePath,
     #[serde(alias = "id")]
     debug_id: DebugId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    code_id: Option<CodeId>,
     #[serde(rename = "type")]
     image_type: ImageType,
 
@@ -34,12 +39,61 @@ pub struct NativeDebugImage {
     image_size: u64,
 }
 
+/// Prepares the debug images of a profile for symbolication.
+///
+/// Canonicalizes the debug identifiers of ELF and Mach-O images the same way as for events, reads
+/// the sizes of PE images from their code identifiers, and infers other missing image sizes from
+/// the addresses of the other images. Overlapping images are kept, since profiles cannot carry
+/// errors.
+pub fn normalize_debug_images(images: &mut [NativeDebugImage]) {
+    let mut ranges = Vec::new();
+    let mut native_images = Vec::new();
+
+    for image in images.iter_mut() {
+        match image.image_type {
+            ImageType::Elf | ImageType::MachO => {
+                if let Some(debug_id) = canonical_debug_id(&image.debug_id) {
+                    image.debug_id = debug_id;
+                }
+            }
+            ImageType::Pe if image.image_size == 0 => {
+                if let Some(size) = image.code_id.as_ref().and_then(pe_image_size) {
+                    image.image_size = size;
+                }
+            }
+            _ => (),
+        }
+
+        if let Some(addr) = image.image_addr {
+            ranges.push(ImageRange {
+                addr: addr.0,
+                size: image.image_size,
+                overlaps: false,
+            });
+            native_images.push(image);
+        }
+    }
+
+    layout_images(&mut ranges);
+
+    for (image, range) in native_images.into_iter().zip(ranges) {
+        image.image_size = range.size;
+
+        if range.overlaps {
+            relay_log::debug!(
+                debug_id = %image.debug_id,
+                "profile contains overlapping debug images"
+            );
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use relay_event_schema::protocol::{Addr, DebugImage, NativeDebugImage as SchemaImage};
     use relay_protocol::{Annotated, Map};
 
-    use super::NativeDebugImage;
+    use super::{normalize_debug_images, NativeDebugImage};
 
     #[test]
     fn test_native_debug_image_compatibility() {
@@ -61,4 +115,51 @@ mod tests {
                 other: Map::new(),
             }))), annotated);
     }
+
+    #[test]
+    fn test_normalize_debug_images() {
+        let mut images: Vec<NativeDebugImage> = serde_json::from_str(
+            r#"[
+                {"debug_id":"32420279-25e2-34e6-8bc7-8a006a8f2425-1","image_addr":"0x2000","code_file":"/usr/lib/libb.dylib","type":"macho"},
+                {"debug_id":"84a04d24-0e60-3810-a8c0-90a65e2df61a","image_addr":"0x1000","code_file":"/usr/lib/liba.dylib","type":"macho"},
+                {"debug_id":"c0bcc3f1-9827-fe65-3058-404b2831d9e6","image_addr":"0x3000","code_file":"/usr/lib/libc.dylib","type":"macho","image_size":4096}
+            ]"#,
+        )
+        .unwrap();
+
+        normalize_debug_images(&mut images);
+
+        assert_eq!(
+            images[0].debug_id,
+            "32420279-25e2-34e6-8bc7-8a006a8f2425".parse().unwrap()
+        );
+        assert_eq!(images[0].image_size, 0x1000);
+        assert_eq!(images[1].image_size, 0x1000);
+        assert_eq!(images[2].image_size, 4096);
+    }
+
+    #[test]
+    fn test_normalize_elf_and_pe_debug_images() {
+        let mut images: Vec<NativeDebugImage> = serde_json::from_str(
+            r#"[
+                {"debug_id":"f1c3bcc0-2798-65fe-3058-404b2831d9e6-1","image_addr":"0x1000","code_file":"/lib/libc.so.6","type":"elf"},
+                {"debug_id":"3249d99d-0c40-4931-8610-f4e4fb0b6936-1","code_id":"5ab380779000","image_addr":"0x10000","code_file":"C:\\Windows\\System32\\kernel32.dll","type":"pe"}
+            ]"#,
+        )
+        .unwrap();
+
+        normalize_debug_images(&mut images);
+
+        assert_eq!(
+            images[0].debug_id,
+            "f1c3bcc0-2798-65fe-3058-404b2831d9e6".parse().unwrap()
+        );
+        assert_eq!(images[0].image_size, 0xf000);
+        // PE debug identifiers carry an age that is required for symbolication.
+        assert_eq!(
+            images[1].debug_id,
+            "3249d99d-0c40-4931-8610-f4e4fb0b6936-1".parse().unwrap()
+        );
+        assert_eq!(images[1].image_size, 0x9000);
+    }
 }
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::native_debug_image::NativeDebugImage;
+use crate::native_debug_image::{self, NativeDebugImage};
 use relay_event_schema::protocol::Addr;
 
 pub mod v1;
@@ -81,11 +81,28 @@ pub struct Frame {
 impl Frame {
     pub fn strip_pointer_authentication_code(&mut self, pac_code: u64) {
         if let Some(address) = self.instruction_addr {
-            self.instruction_addr = Some(Addr(address.0 & pac_code));
+            self.instruction_addr = Some(Addr(
+                relay_event_normalization::strip_pointer_authentication_code(address.0, pac_code),
+            ));
         }
     }
 }
 
+/// Strips pointer authentication codes from the instruction addresses of all frames.
+///
+/// Whether addresses are masked is decided by
+/// [`relay_event_normalization::pointer_authentication_mask`], which is shared with events.
+fn strip_pointer_authentication_codes(frames: &mut [Frame], platform: &str, architecture: &str) {
+    let Some(pac_code) =
+        relay_event_normalization::pointer_authentication_mask(platform, Some(architecture))
+    else {
+        return;
+    };
+    for frame in frames {
+        frame.strip_pointer_authentication_code(pac_code);
+    }
+}
+
 #[derive(Default, Debug, Serialize, Deserialize, Clone)]
 pub struct DebugMeta {
     /// A list of debug files needed to symbolicate/deobfuscate this profile.
@@ -97,6 +114,11 @@ impl DebugMeta {
     pub fn is_empty(&self) -> bool {
         self.images.is_empty()
     }
+
+    /// Prepares the debug images for symbolication.
+    pub fn normalize(&mut self) {
+        native_debug_image::normalize_debug_images(&mut self.images);
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
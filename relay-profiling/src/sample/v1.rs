@@ -9,12 +9,17 @@ use std::ops::Range;
 
 use chrono::{DateTime, Utc};
 use itertools::Itertools;
+use relay_event_schema::processor::{ProcessingState, ValueType};
 use relay_event_schema::protocol::{EventId, SpanId};
 use serde::{Deserialize, Serialize};
 
 use crate::error::ProfileError;
+use crate::info::{non_empty, ProfileInfo};
 use crate::measurements::Measurement;
-use crate::sample::{DebugMeta, Frame, ThreadMetadata, Version};
+use crate::sample::{
+    strip_pointer_authentication_codes, DebugMeta, Frame, ThreadMetadata, Version,
+};
+use crate::scrubbing::ProfileScrubber;
 use crate::transaction_metadata::TransactionMetadata;
 use crate::utils::{deserialize_number_from_string, string_is_null_or_empty};
 use crate::MAX_PROFILE_DURATION;
@@ -82,21 +87,22 @@ impl SampleProfile {
             return Err(ProfileError::DurationIsTooLong);
         }
 
-        self.strip_pointer_authentication_code(platform, architecture);
+        strip_pointer_authentication_codes(&mut self.frames, platform, architecture);
         self.remove_unreferenced_threads();
         self.remove_unreferenced_queues();
 
         Ok(())
     }
 
-    fn strip_pointer_authentication_code(&mut self, platform: &str, architecture: &str) {
-        let addr = match (platform, architecture) {
-            // https://github.com/microsoft/plcrashreporter/blob/748087386cfc517936315c107f722b146b0ad1ab/Source/PLCrashAsyncThread_arm.c#L84
-            ("cocoa", "arm64") | ("cocoa", "arm64e") => 0x0000000FFFFFFFFF,
-            _ => return,
-        };
-        for frame in &mut self.frames {
-            frame.strip_pointer_authentication_code(addr);
+    /// Applies PII rules to frames and thread names.
+    pub(crate) fn scrub(
+        &mut self,
+        scrubber: &mut ProfileScrubber<'_>,
+        state: &ProcessingState<'_>,
+    ) {
+        scrubber.scrub_frames(&mut self.frames, state);
+        if let Some(ref mut thread_metadata) = self.thread_metadata {
+            scrubber.scrub_threads(thread_metadata, state);
         }
     }
 
@@ -257,6 +263,22 @@ pub struct ProfileMetadata {
     transaction_tags: BTreeMap<String, String>,
 }
 
+impl ProfileMetadata {
+    /// Returns the attributes of the profile that inbound filters apply to.
+    pub(crate) fn info(&self) -> ProfileInfo {
+        let transaction = self.transaction.as_ref().or(self.transactions.first());
+
+        ProfileInfo {
+            profile_id: self.event_id,
+            platform: self.platform.clone(),
+            release: self.release.clone(),
+            environment: non_empty(&self.environment),
+            dist: non_empty(&self.dist),
+            transaction: transaction.map(|transaction| transaction.name.clone()),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ProfilingEvent {
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -267,6 +289,28 @@ pub struct ProfilingEvent {
 }
 
 impl ProfilingEvent {
+    /// Applies PII rules to the profile and its transaction metadata.
+    fn scrub(&mut self, scrubber: &mut ProfileScrubber<'_>) {
+        let state = ProcessingState::root();
+
+        let profile_state = state.enter_static("profile", None, ValueType::Object);
+        self.profile.scrub(scrubber, &profile_state);
+
+        if let Some(ref mut transaction) = self.metadata.transaction {
+            scrubber.scrub_transaction(transaction, state);
+        }
+        scrubber.scrub_map(
+            "transaction_metadata",
+            &mut self.metadata.transaction_metadata,
+            state,
+        );
+        scrubber.scrub_map(
+            "transaction_tags",
+            &mut self.metadata.transaction_tags,
+            state,
+        );
+    }
+
     fn valid(&self) -> bool {
         match self.metadata.platform.as_str() {
             "cocoa" => {
@@ -317,6 +361,10 @@ fn parse_profile(payload: &[u8]) -> Result<ProfilingEvent, ProfileError> {
         profile.metadata.device.architecture.as_str(),
     )?;
 
+    if let Some(debug_meta) = profile.metadata.debug_meta.as_mut() {
+        debug_meta.normalize();
+    }
+
     Ok(profile)
 }
 
@@ -324,6 +372,7 @@ pub fn parse_sample_profile(
     payload: &[u8],
     transaction_metadata: BTreeMap<String, String>,
     transaction_tags: BTreeMap<String, String>,
+    scrubber: Option<&mut ProfileScrubber<'_>>,
 ) -> Result<Vec<u8>, ProfileError> {
     let mut profile = parse_profile(payload)?;
 
@@ -357,6 +406,10 @@ pub fn parse_sample_profile(
     profile.metadata.transaction_metadata = transaction_metadata;
     profile.metadata.transaction_tags = transaction_tags;
 
+    if let Some(scrubber) = scrubber {
+        profile.scrub(scrubber);
+    }
+
     serde_json::to_vec(&profile).map_err(|_| ProfileError::CannotSerializePayload)
 }
 
@@ -377,7 +430,7 @@ mod tests {
     #[test]
     fn test_expand() {
         let payload = include_bytes!("../../tests/fixtures/sample/v1/valid.json");
-        let profile = parse_sample_profile(payload, BTreeMap::new(), BTreeMap::new());
+        let profile = parse_sample_profile(payload, BTreeMap::new(), BTreeMap::new(), None);
         assert!(profile.is_ok());
     }
 
@@ -593,7 +646,7 @@ mod tests {
         ]);
 
         let payload = serde_json::to_vec(&profile).unwrap();
-        let data = parse_sample_profile(&payload[..], BTreeMap::new(), BTreeMap::new());
+        let data = parse_sample_profile(&payload[..], BTreeMap::new(), BTreeMap::new(), None);
 
         assert!(data.is_err());
     }
@@ -873,7 +926,8 @@ mod tests {
         )]);
 
         let payload = include_bytes!("../../tests/fixtures/sample/v1/valid.json");
-        let profile_json = parse_sample_profile(payload, transaction_metadata, BTreeMap::new());
+        let profile_json =
+            parse_sample_profile(payload, transaction_metadata, BTreeMap::new(), None);
         assert!(profile_json.is_ok());
 
         let payload = profile_json.unwrap();
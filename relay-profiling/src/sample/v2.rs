@@ -13,12 +13,14 @@ use std::collections::{BTreeMap, HashSet};
 
 use serde::{Deserialize, Serialize};
 
+use relay_event_schema::processor::{ProcessingState, ValueType};
 use relay_event_schema::protocol::EventId;
 use relay_metrics::FiniteF64;
 
 use crate::error::ProfileError;
 use crate::measurements::Measurement;
 use crate::sample::{DebugMeta, Frame, ThreadMetadata, Version};
+use crate::scrubbing::ProfileScrubber;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ProfileMetadata {
@@ -64,7 +66,16 @@ pub struct ProfileChunk {
 
 impl ProfileChunk {
     pub fn normalize(&mut self) -> Result<(), ProfileError> {
-        self.profile.normalize(self.metadata.platform.as_str())
+        self.profile.normalize()?;
+        self.metadata.debug_meta.normalize();
+        Ok(())
+    }
+
+    /// Applies PII rules to frames and thread names.
+    pub fn scrub(&mut self, scrubber: &mut ProfileScrubber<'_>) {
+        let state = ProcessingState::root().enter_static("profile", None, ValueType::Object);
+        scrubber.scrub_frames(&mut self.profile.frames, &state);
+        scrubber.scrub_threads(&mut self.profile.thread_metadata, &state);
     }
 }
 
@@ -93,7 +104,7 @@ impl ProfileData {
     /// Mutates the profile chunk. Removes invalid samples and threads.
     /// Throws an error if the profile chunk is malformed.
     /// Removes extra metadata that are not referenced in the samples.
-    pub fn normalize(&mut self, platform: &str) -> Result<(), ProfileError> {
+    pub fn normalize(&mut self) -> Result<(), ProfileError> {
         if self.samples.is_empty() {
             return Err(ProfileError::NotEnoughSamples);
         }
@@ -108,23 +119,13 @@ impl ProfileData {
             return Err(ProfileError::MalformedStacks);
         }
 
-        self.strip_pointer_authentication_code(platform);
+        // Chunks do not declare the architecture of the device, so pointer authentication codes
+        // cannot be stripped safely and are left to symbolication.
         self.remove_unreferenced_threads();
 
         Ok(())
     }
 
-    fn strip_pointer_authentication_code(&mut self, platform: &str) {
-        let addr = match platform {
-            // https://github.com/microsoft/plcrashreporter/blob/748087386cfc517936315c107f722b146b0ad1ab/Source/PLCrashAsyncThread_arm.c#L84
-            "cocoa" => 0x0000000FFFFFFFFF,
-            _ => return,
-        };
-        for frame in &mut self.frames {
-            frame.strip_pointer_authentication_code(addr);
-        }
-    }
-
     /// Checks that all stacks referenced by the samples exist in the stacks.
     fn all_stacks_referenced_by_samples_exist(&self) -> bool {
         self.samples
@@ -193,7 +194,7 @@ mod tests {
             ..Default::default()
         };
 
-        assert!(chunk.normalize("python").is_ok());
+        assert!(chunk.normalize().is_ok());
 
         let timestamps: Vec<FiniteF64> = chunk.samples.iter().map(|s| s.timestamp).collect();
 
@@ -0,0 +1,280 @@
+//! PII scrubbing of profiles.
+//!
+//! Profiles are not [`Annotated`](relay_protocol::Annotated) structures, so the [`PiiProcessor`]
+//! cannot traverse them. Instead, [`ProfileScrubber`] applies the PII rules to the individual
+//! string fields that can contain PII, using processing states that mirror the paths of these
+//! fields in the JSON payload. Selectors like `$frame.abs_path` or
+//! `transaction_tags.user` address these fields.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+use relay_base_schema::data_category::DataCategory;
+use relay_event_schema::processor::{FieldAttrs, Pii, ProcessingState, Processor, ValueType};
+use relay_pii::{PiiConfig, PiiProcessor};
+use relay_protocol::Meta;
+
+use crate::sample::{Frame, ThreadMetadata};
+use crate::transaction_metadata::TransactionMetadata;
+
+/// Field attributes for names and paths, which are only scrubbed by rules selecting them.
+///
+/// This is equivalent to frame fields in events, which are marked as `pii = "maybe"`.
+const FIELD_ATTRS_PII_MAYBE: FieldAttrs = FieldAttrs::new().pii(Pii::Maybe);
+
+/// Field attributes for arbitrary user provided values, which are scrubbed by all rules.
+const FIELD_ATTRS_PII_TRUE: FieldAttrs = FieldAttrs::new().pii(Pii::True);
+
+/// Returns the PII config that replaces user names in absolute paths of frames.
+fn userpath_config() -> &'static PiiConfig {
+    static CONFIG: OnceLock<PiiConfig> = OnceLock::new();
+    CONFIG.get_or_init(|| {
+        serde_json::from_value(serde_json::json!({
+            "applications": {
+                "abs_path": ["@userpath"],
+            }
+        }))
+        .expect("userpath PII config is valid")
+    })
+}
+
+/// Applies PII rules to the fields of a profile.
+///
+/// Frames, thread names and transaction metadata are scrubbed with the given PII configs, using
+/// the rules scoped to the `profile` data category. User names in absolute paths of frames are
+/// always replaced, equivalent to the `@userpath` rule.
+pub struct ProfileScrubber<'a> {
+    /// PII processors that are applied one by one on each value.
+    processors: Vec<PiiProcessor<'a>>,
+}
+
+impl<'a> ProfileScrubber<'a> {
+    /// Creates a new `ProfileScrubber` from the project's PII configs.
+    ///
+    /// Typically, these are the project's PII config and the PII config converted from the legacy
+    /// data scrubbing settings. This compiles the configs if they are not compiled yet, which can
+    /// be a CPU-intensive process.
+    pub fn new(config1: Option<&'a PiiConfig>, config2: Option<&'a PiiConfig>) -> Self {
+        let processors = std::iter::once(userpath_config())
+            .chain(config1)
+            .chain(config2)
+            .map(|config| PiiProcessor::new(config.compiled()).with_scope(DataCategory::Profile))
+            .collect();
+
+        Self { processors }
+    }
+
+    /// Sets the secret key for keyed hash redactions in the PII configs.
+    ///
+    /// See [`PiiProcessor::with_hash_key`].
+    pub fn with_hash_key(mut self, hash_key: Option<&'a str>) -> Self {
+        self.processors = self
+            .processors
+            .into_iter()
+            .map(|processor| processor.with_hash_key(hash_key))
+            .collect();
+        self
+    }
+
+    /// Scrubs a single string value.
+    ///
+    /// Returns `false` if the value should be removed.
+    fn scrub_string(&mut self, value: &mut String, state: &ProcessingState<'_>) -> bool {
+        for processor in &mut self.processors {
+            if processor
+                .process_string(value, &mut Meta::default(), state)
+                .is_err()
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Scrubs an optional field of an object, removing it if requested by a rule.
+    fn scrub_field(
+        &mut self,
+        key: &'static str,
+        value: &mut Option<String>,
+        state: &ProcessingState<'_>,
+    ) {
+        if let Some(ref mut inner) = value {
+            let state = state.enter_static(
+                key,
+                Some(Cow::Borrowed(&FIELD_ATTRS_PII_MAYBE)),
+                ValueType::String,
+            );
+
+            if !self.scrub_string(inner, &state) {
+                *value = None;
+            }
+        }
+    }
+
+    /// Scrubs the paths, file names, function and module names of `frames`.
+    pub(crate) fn scrub_frames(&mut self, frames: &mut [Frame], state: &ProcessingState<'_>) {
+        let state = state.enter_static("frames", None, ValueType::Array);
+
+        for (index, frame) in frames.iter_mut().enumerate() {
+            let state = state.enter_index(index, None, ValueType::Frame);
+            self.scrub_field("abs_path", &mut frame.abs_path, &state);
+            self.scrub_field("filename", &mut frame.filename, &state);
+            self.scrub_field("function", &mut frame.function, &state);
+            self.scrub_field("module", &mut frame.module, &state);
+        }
+    }
+
+    /// Scrubs the names of threads in `thread_metadata`.
+    pub(crate) fn scrub_threads<'t>(
+        &mut self,
+        threads: impl IntoIterator<Item = (&'t String, &'t mut ThreadMetadata)>,
+        state: &ProcessingState<'_>,
+    ) {
+        let state = state.enter_static("thread_metadata", None, ValueType::Object);
+
+        for (thread_id, thread) in threads {
+            let state = state.enter_borrowed(thread_id, None, ValueType::Object);
+            self.scrub_field("name", &mut thread.name, &state);
+        }
+    }
+
+    /// Scrubs the name of the transaction associated with a profile.
+    pub(crate) fn scrub_transaction(
+        &mut self,
+        transaction: &mut TransactionMetadata,
+        state: &ProcessingState<'_>,
+    ) {
+        let state = state.enter_static("transaction", None, ValueType::Object);
+        self.scrub_name("name", &mut transaction.name, &state);
+    }
+
+    /// Scrubs a required name field, which is cleared if requested by a rule.
+    pub(crate) fn scrub_name(
+        &mut self,
+        key: &'static str,
+        value: &mut String,
+        state: &ProcessingState<'_>,
+    ) {
+        let state = state.enter_static(
+            key,
+            Some(Cow::Borrowed(&FIELD_ATTRS_PII_MAYBE)),
+            ValueType::String,
+        );
+
+        if !self.scrub_string(value, &state) {
+            value.clear();
+        }
+    }
+
+    /// Scrubs the values of a map with transaction metadata or tags.
+    ///
+    /// Entries are removed if requested by a rule.
+    pub(crate) fn scrub_map(
+        &mut self,
+        key: &'static str,
+        map: &mut BTreeMap<String, String>,
+        state: &ProcessingState<'_>,
+    ) {
+        let state = state.enter_static(key, None, ValueType::Object);
+
+        map.retain(|name, value| {
+            let state = state.enter_borrowed(
+                name,
+                Some(Cow::Borrowed(&FIELD_ATTRS_PII_TRUE)),
+                ValueType::String,
+            );
+
+            self.scrub_string(value, &state)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scrub_frames_userpath() {
+        let mut scrubber = ProfileScrubber::new(None, None);
+        let mut frames = vec![Frame {
+            abs_path: Some("/Users/jane/code/app/main.swift".to_owned()),
+            function: Some("main".to_owned()),
+            ..Default::default()
+        }];
+
+        scrubber.scrub_frames(&mut frames, ProcessingState::root());
+
+        assert_eq!(
+            frames[0].abs_path.as_deref(),
+            Some("/Users/[user]/code/app/main.swift")
+        );
+        assert_eq!(frames[0].function.as_deref(), Some("main"));
+    }
+
+    #[test]
+    fn test_scrub_keyed_hash() {
+        let config: PiiConfig = serde_json::from_value(serde_json::json!({
+            "rules": {"0": {"type": "email", "redaction": {"method": "keyed_hash"}}},
+            "applications": {"$frame.function": ["0"]}
+        }))
+        .unwrap();
+
+        let scrub = |hash_key| {
+            let mut scrubber = ProfileScrubber::new(Some(&config), None).with_hash_key(hash_key);
+            let mut frames = vec![Frame {
+                function: Some("jane@example.com".to_owned()),
+                ..Default::default()
+            }];
+            scrubber.scrub_frames(&mut frames, ProcessingState::root());
+            frames[0].function.clone().unwrap()
+        };
+
+        let hashed = scrub(Some("secret"));
+        assert!(!hashed.is_empty());
+        assert_ne!(hashed, "jane@example.com");
+        assert_ne!(hashed, scrub(Some("other")));
+        assert_eq!(scrub(None), "");
+    }
+
+    #[test]
+    fn test_scrub_with_config() {
+        let config: PiiConfig = serde_json::from_value(serde_json::json!({
+            "applications": {
+                "$frame.function": ["@email"],
+                "thread_metadata.*.name": ["@anything:remove"],
+                "$string": ["@ip"],
+            }
+        }))
+        .unwrap();
+
+        let mut scrubber = ProfileScrubber::new(Some(&config), None);
+        let state = ProcessingState::root();
+
+        let mut frames = vec![Frame {
+            function: Some("send(jane@example.com)".to_owned()),
+            module: Some("127.0.0.1".to_owned()),
+            ..Default::default()
+        }];
+        scrubber.scrub_frames(&mut frames, state);
+        assert_eq!(frames[0].function.as_deref(), Some("send([email])"));
+        // Frame fields are only scrubbed by rules selecting them explicitly.
+        assert_eq!(frames[0].module.as_deref(), Some("127.0.0.1"));
+
+        let mut threads = BTreeMap::from([(
+            "1".to_owned(),
+            ThreadMetadata {
+                name: Some("worker-jane".to_owned()),
+                priority: Some(1),
+            },
+        )]);
+        scrubber.scrub_threads(threads.iter_mut(), state);
+        assert_eq!(threads["1"].name, None);
+        assert_eq!(threads["1"].priority, Some(1));
+
+        let mut tags = BTreeMap::from([("client".to_owned(), "127.0.0.1".to_owned())]);
+        scrubber.scrub_map("transaction_tags", &mut tags, state);
+        assert_eq!(tags["client"], "[ip]");
+    }
+}
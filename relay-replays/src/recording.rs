@@ -21,6 +21,7 @@ use flate2::bufread::ZlibDecoder;
 use flate2::write::ZlibEncoder;
 use flate2::Compression;
 use once_cell::sync::Lazy;
+use relay_base_schema::data_category::DataCategory;
 use relay_event_schema::processor::{FieldAttrs, Pii, ProcessingState, Processor, ValueType};
 use relay_pii::{PiiConfig, PiiProcessor};
 use relay_protocol::Meta;
@@ -311,14 +312,37 @@ impl<'a> RecordingScrubber<'a> {
         Self {
             limit,
             transform: Rc::new(RefCell::new(ScrubberTransform {
-                processor1: config1.map(|c| PiiProcessor::new(c.compiled())),
-                processor2: config2.map(|c| PiiProcessor::new(c.compiled())),
+                processor1: config1.map(Self::processor),
+                processor2: config2.map(Self::processor),
                 state: ProcessingState::new_root(None, None),
                 path: vec![],
             })),
         }
     }
 
+    /// Sets the secret key for keyed hash redactions in both PII configs.
+    ///
+    /// See [`PiiProcessor::with_hash_key`].
+    pub fn with_hash_key(self, hash_key: Option<&'a str>) -> Self {
+        {
+            let mut transform = self.transform.borrow_mut();
+            transform.processor1 = transform
+                .processor1
+                .take()
+                .map(|processor| processor.with_hash_key(hash_key));
+            transform.processor2 = transform
+                .processor2
+                .take()
+                .map(|processor| processor.with_hash_key(hash_key));
+        }
+        self
+    }
+
+    /// Creates a processor for the rules of `config` that apply to replays.
+    fn processor(config: &'a PiiConfig) -> PiiProcessor<'a> {
+        PiiProcessor::new(config.compiled()).with_scope(DataCategory::Replay)
+    }
+
     /// Returns `true` if both configs are empty and no scrubbing would occur.
     pub fn is_empty(&self) -> bool {
         let tmp = self.transform.borrow();
@@ -625,6 +649,29 @@ mod tests {
         // assert!(scrubbed_result.contains("\"message\":\"[email]\""));
     }
 
+    #[test]
+    fn test_scrub_pii_keyed_hash() {
+        let payload = include_bytes!("../tests/fixtures/rrweb-event-5.json");
+        let config = serde_json::from_str::<PiiConfig>(
+            r#"{
+                "rules": {"0": {"type": "email", "redaction": {"method": "keyed_hash"}}},
+                "applications": {"$string": ["0"]}
+            }"#,
+        )
+        .unwrap();
+
+        let mut transcoded = Vec::new();
+        scrubber(&config)
+            .with_hash_key(Some("secret"))
+            .scrub_replay(payload.as_slice(), &mut transcoded)
+            .unwrap();
+
+        let scrubbed_result = std::str::from_utf8(&transcoded).unwrap();
+        assert!(!scrubbed_result.contains("user@email.com"));
+        // With a key, the value is pseudonymized instead of removed.
+        assert!(!scrubbed_result.contains("\"message\":\"\""));
+    }
+
     #[test]
     fn test_scrub_pii_key_based() {
         let payload = include_bytes!("../tests/fixtures/rrweb-request.json");
@@ -3,22 +3,60 @@
 use axum::extract::Path;
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
+use relay_metrics::MetricNamespace;
 use serde::Serialize;
 
 use crate::service::ServiceState;
-use crate::services::health_check::{IsHealthy, Status as HealthStatus};
+use crate::services::health_check::{HealthReport, IsHealthy, Status as HealthStatus};
 
 #[derive(Serialize)]
 struct Status {
     is_healthy: bool,
+    /// Metric namespaces that are currently rejected due to back pressure.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    backpressure: Vec<MetricNamespace>,
 }
 
+/// Responds with the health status of Relay.
+///
+/// A healthy Relay that rejects metrics of some namespaces due to back pressure responds with
+/// `429 Too Many Requests`, so that load balancers can tell it apart from both a fully healthy and
+/// an unhealthy Relay.
 pub async fn handle(state: ServiceState, Path(kind): Path<IsHealthy>) -> impl IntoResponse {
     match state.health_check().send(kind).await {
-        Ok(HealthStatus::Healthy) => (StatusCode::OK, axum::Json(Status { is_healthy: true })),
-        _ => (
+        Ok(HealthReport {
+            status: HealthStatus::Healthy,
+            backpressure,
+        }) if !backpressure.is_empty() => (
+            StatusCode::TOO_MANY_REQUESTS,
+            axum::Json(Status {
+                is_healthy: true,
+                backpressure,
+            }),
+        ),
+        Ok(HealthReport {
+            status: HealthStatus::Healthy,
+            backpressure,
+        }) => (
+            StatusCode::OK,
+            axum::Json(Status {
+                is_healthy: true,
+                backpressure,
+            }),
+        ),
+        Ok(HealthReport { backpressure, .. }) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            axum::Json(Status {
+                is_healthy: false,
+                backpressure,
+            }),
+        ),
+        Err(_) => (
             StatusCode::SERVICE_UNAVAILABLE,
-            axum::Json(Status { is_healthy: false }),
+            axum::Json(Status {
+                is_healthy: false,
+                backpressure: Vec::new(),
+            }),
         ),
     }
 }
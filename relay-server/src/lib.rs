@@ -288,6 +288,16 @@ pub fn run(config: Config) -> anyhow::Result<()> {
     let config = Arc::new(config);
     relay_log::info!("relay server starting");
 
+    if let Some(path) = config.user_agent_regexes_path() {
+        match relay_ua::init_parser_from_file(path) {
+            Ok(()) => relay_log::info!("loaded user agent regexes from {}", path.display()),
+            Err(error) => relay_log::error!(
+                error = &error as &dyn std::error::Error,
+                "failed to load user agent regexes, falling back to built-in regexes"
+            ),
+        }
+    }
+
     // Creates the main runtime.
     let main_runtime = crate::service::create_runtime("main-rt", config.cpu_concurrency());
 
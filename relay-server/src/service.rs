@@ -133,8 +133,10 @@ impl ServiceState {
             config.default_aggregator_config().clone(),
             config.secondary_aggregator_configs().clone(),
             Some(project_cache.clone().recipient()),
-        )
-        .start();
+            config.spool_metrics_snapshot(),
+        );
+        let metrics_backpressure = aggregator.backpressure();
+        let aggregator = aggregator.start();
 
         let metric_stats = MetricStats::new(
             config.clone(),
@@ -202,6 +204,7 @@ impl ServiceState {
             buffer_guard.clone(),
             project_cache_services,
             metric_outcomes,
+            metrics_backpressure.clone(),
             redis_pool,
         )
         .spawn_handler(project_cache_rx);
@@ -209,6 +212,7 @@ impl ServiceState {
         let health_check = HealthCheckService::new(
             config.clone(),
             aggregator.clone(),
+            metrics_backpressure,
             upstream_relay.clone(),
             project_cache.clone(),
         )
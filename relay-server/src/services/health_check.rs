@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use relay_config::Config;
-use relay_metrics::{AcceptsMetrics, Aggregator};
+use relay_metrics::{AcceptsMetrics, Aggregator, MetricNamespace, NamespaceBackpressure};
 use relay_statsd::metric;
 use relay_system::{Addr, AsyncResponse, Controller, FromMessage, Interface, Sender, Service};
 use std::future::Future;
@@ -52,15 +52,28 @@ impl FromIterator<Status> for Status {
     }
 }
 
+/// Health check result, the response to [`IsHealthy`].
+#[derive(Debug, Clone)]
+pub struct HealthReport {
+    /// Overall health status of Relay.
+    pub status: Status,
+    /// Metric namespaces that exceed their budget in the metrics aggregator.
+    ///
+    /// Metrics of these namespaces are rejected, while Relay remains healthy for all other
+    /// traffic. The readiness endpoint responds with `429 Too Many Requests` while this is not
+    /// empty. Always empty for liveness checks.
+    pub backpressure: Vec<MetricNamespace>,
+}
+
 /// Service interface for the [`IsHealthy`] message.
-pub struct HealthCheck(IsHealthy, Sender<Status>);
+pub struct HealthCheck(IsHealthy, Sender<HealthReport>);
 
 impl Interface for HealthCheck {}
 
 impl FromMessage<IsHealthy> for HealthCheck {
-    type Response = AsyncResponse<Status>;
+    type Response = AsyncResponse<HealthReport>;
 
-    fn from_message(message: IsHealthy, sender: Sender<Status>) -> Self {
+    fn from_message(message: IsHealthy, sender: Sender<HealthReport>) -> Self {
         Self(message, sender)
     }
 }
@@ -85,6 +98,7 @@ impl StatusUpdate {
 pub struct HealthCheckService {
     config: Arc<Config>,
     aggregator: Addr<Aggregator>,
+    metrics_backpressure: NamespaceBackpressure,
     upstream_relay: Addr<UpstreamRelay>,
     project_cache: Addr<ProjectCache>,
     system: System,
@@ -97,12 +111,14 @@ impl HealthCheckService {
     pub fn new(
         config: Arc<Config>,
         aggregator: Addr<Aggregator>,
+        metrics_backpressure: NamespaceBackpressure,
         upstream_relay: Addr<UpstreamRelay>,
         project_cache: Addr<ProjectCache>,
     ) -> Self {
         Self {
             system: System::new(),
             aggregator,
+            metrics_backpressure,
             upstream_relay,
             project_cache,
             config,
@@ -215,6 +231,7 @@ impl Service for HealthCheckService {
         let check_interval = self.config.health_refresh_interval();
         // Add 10% buffer to the internal timeouts to avoid race conditions.
         let status_timeout = (check_interval + self.config.health_probe_timeout()).mul_f64(1.1);
+        let metrics_backpressure = self.metrics_backpressure.clone();
 
         tokio::spawn(async move {
             let shutdown = Controller::shutdown_handle();
@@ -238,11 +255,20 @@ impl Service for HealthCheckService {
                 let update = update_rx.borrow();
 
                 sender.send(if matches!(message, IsHealthy::Liveness) {
-                    Status::Healthy
+                    HealthReport {
+                        status: Status::Healthy,
+                        backpressure: Vec::new(),
+                    }
                 } else if update.instant.elapsed() >= status_timeout {
-                    Status::Unhealthy
+                    HealthReport {
+                        status: Status::Unhealthy,
+                        backpressure: metrics_backpressure.exceeded(),
+                    }
                 } else {
-                    update.status
+                    HealthReport {
+                        status: update.status,
+                        backpressure: metrics_backpressure.exceeded(),
+                    }
                 });
             }
         });
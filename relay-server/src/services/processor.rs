@@ -18,7 +18,7 @@ use relay_base_schema::project::{ProjectId, ProjectKey};
 use relay_cogs::{AppFeature, Cogs, FeatureWeights, ResourceId, Token};
 use relay_common::time::UnixTimestamp;
 use relay_config::{Config, HttpEncoding, NormalizationLevel, RelayMode};
-use relay_dynamic_config::{CombinedMetricExtractionConfig, ErrorBoundary, Feature};
+use relay_dynamic_config::{CombinedMetricExtractionConfig, ErrorBoundary, Feature, ProjectConfig};
 use relay_event_normalization::{
     normalize_event, validate_event_timestamps, validate_transaction, ClockDriftProcessor,
     CombinedMeasurementsConfig, EventValidationConfig, GeoIpLookup, MeasurementsConfig,
@@ -28,6 +28,7 @@ use relay_event_schema::processor::ProcessingAction;
 use relay_event_schema::protocol::{
     ClientReport, Event, EventId, EventType, IpAddr, Metrics, NetworkReportError,
 };
+use relay_filter::repeated_errors::{InMemoryRepeatedErrors, RepeatedErrorsCounter};
 use relay_filter::FilterStatKey;
 use relay_metrics::aggregator::AggregatorConfig;
 use relay_metrics::{Bucket, BucketMetadata, BucketView, BucketsView, MetricMeta, MetricNamespace};
@@ -54,6 +55,7 @@ use {
         RedisSetLimiterOptions,
     },
     relay_dynamic_config::{CardinalityLimiterMode, GlobalConfig, MetricExtractionGroups},
+    relay_filter::repeated_errors::RedisRepeatedErrors,
     relay_metrics::RedisMetricMetaStore,
     relay_quotas::{Quota, RateLimitingError, RateLimits, RedisRateLimiter},
     relay_redis::RedisPool,
@@ -88,6 +90,8 @@ use crate::utils::{
 use crate::{http, metrics};
 
 mod attachment;
+#[cfg(feature = "processing")]
+mod check_in;
 mod dynamic_sampling;
 mod event;
 mod profile;
@@ -683,6 +687,22 @@ impl ProcessingExtractedMetrics {
     }
 }
 
+/// Returns the secret key for keyed hash redactions in the project's PII config.
+///
+/// The key from the project config takes precedence over the key configured in Relay. Keys are
+/// only used by processing Relays and Relays that request full project configs. All other Relays
+/// remove values matched by keyed hash rules.
+fn pii_hash_key<'a>(config: &'a Config, project_config: &'a ProjectConfig) -> Option<&'a str> {
+    if !config.processing_enabled() && !config.request_full_project_config() {
+        return None;
+    }
+
+    project_config
+        .pii_hash_key
+        .as_deref()
+        .or_else(|| config.pii_hash_key())
+}
+
 /// A state container for envelope processing.
 #[derive(Debug)]
 struct ProcessEnvelopeState<'a, Group> {
@@ -1054,6 +1074,7 @@ struct InnerProcessor {
     #[cfg(feature = "processing")]
     rate_limiter: Option<RedisRateLimiter>,
     geoip_lookup: Option<GeoIpLookup>,
+    repeated_errors: Box<dyn RepeatedErrorsCounter + Send + Sync>,
     #[cfg(feature = "processing")]
     metric_meta_store: Option<RedisMetricMetaStore>,
     #[cfg(feature = "processing")]
@@ -1075,15 +1096,37 @@ impl EnvelopeProcessorService {
         #[cfg(feature = "processing")] buffer_guard: Arc<BufferGuard>,
     ) -> Self {
         let geoip_lookup = config.geoip_path().and_then(|p| {
-            match GeoIpLookup::open(p).context(ServiceError::GeoIp) {
-                Ok(geoip) => Some(geoip),
+            let mut geoip = match GeoIpLookup::open(p).context(ServiceError::GeoIp) {
+                Ok(geoip) => geoip,
                 Err(err) => {
                     relay_log::error!("failed to open GeoIP db {p:?}: {err:?}");
-                    None
+                    return None;
+                }
+            };
+
+            // Without network information, the City database is still used for lookups.
+            if let Some(network_path) = config.geoip_network_path() {
+                if let Err(err) = geoip
+                    .open_network(network_path)
+                    .context(ServiceError::GeoIp)
+                {
+                    relay_log::error!("failed to open GeoIP network db {network_path:?}: {err:?}");
                 }
             }
+
+            Some(geoip)
         });
 
+        // Processing Relays share counts of repeated errors through Redis, all others count locally.
+        #[cfg(feature = "processing")]
+        let repeated_errors: Box<dyn RepeatedErrorsCounter + Send + Sync> = match redis.clone() {
+            Some(pool) => Box::new(RedisRepeatedErrors::new(pool)),
+            None => Box::new(InMemoryRepeatedErrors::new()),
+        };
+        #[cfg(not(feature = "processing"))]
+        let repeated_errors: Box<dyn RepeatedErrorsCounter + Send + Sync> =
+            Box::new(InMemoryRepeatedErrors::new());
+
         let inner = InnerProcessor {
             global_config,
             cogs,
@@ -1095,6 +1138,7 @@ impl EnvelopeProcessorService {
                 .map(|pool| RedisRateLimiter::new(pool).max_limit(config.max_rate_limit())),
             addrs,
             geoip_lookup,
+            repeated_errors,
             #[cfg(feature = "processing")]
             metric_meta_store: redis.clone().map(|pool| {
                 RedisMetricMetaStore::new(pool, config.metrics_meta_locations_expiry())
@@ -1123,9 +1167,24 @@ impl EnvelopeProcessorService {
         }
     }
 
-    /// Normalize monitor check-ins and remove invalid ones.
+    /// Reloads the GeoIP databases if they were replaced on disk.
+    fn reload_geoip(&self) {
+        let Some(ref geoip_lookup) = self.inner.geoip_lookup else {
+            return;
+        };
+
+        if geoip_lookup.reload() {
+            relay_log::info!("reloaded GeoIP databases");
+        }
+    }
+
+    /// Normalize monitor check-ins and remove invalid and filtered ones.
     #[cfg(feature = "processing")]
     fn process_check_ins(&self, state: &mut ProcessEnvelopeState<CheckInGroup>) {
+        let global_config = self.inner.global_config.current();
+        let client_ip = state.managed_envelope.envelope().meta().client_addr();
+        let filter_settings = &state.project_state.config.filter_settings;
+
         state.managed_envelope.retain_items(|item| {
             if item.ty() != &ItemType::CheckIn {
                 return ItemAction::Keep;
@@ -1133,6 +1192,18 @@ impl EnvelopeProcessorService {
 
             match relay_monitors::process_check_in(&item.payload(), state.project_id) {
                 Ok(result) => {
+                    if let Err(filter_stat_key) = relay_filter::should_filter(
+                        &check_in::FilterableCheckIn(&result.check_in),
+                        client_ip,
+                        filter_settings,
+                        global_config.filters(),
+                        global_config.web_crawlers(),
+                        global_config.legacy_browsers(),
+                        self.inner.geoip_lookup.as_ref(),
+                    ) {
+                        return ItemAction::Drop(Outcome::Filtered(filter_stat_key));
+                    }
+
                     item.set_routing_hint(result.routing_hint);
                     item.set_payload(ContentType::Json, result.payload);
                     ItemAction::Keep
@@ -1513,6 +1584,10 @@ impl EnvelopeProcessorService {
                 normalize_user_agent: Some(true),
                 transaction_name_config: TransactionNameConfig {
                     rules: &state.project_state.config.tx_name_rules,
+                    grouping_rules: &state.project_state.config.span_grouping_rules,
+                    graphql_operations: state
+                        .project_state
+                        .has_feature(Feature::GraphQlTransactionNames),
                 },
                 device_class_synthesis_config: state
                     .project_state
@@ -1532,6 +1607,15 @@ impl EnvelopeProcessorService {
                 remove_other: full_normalization,
                 emit_event_errors: full_normalization,
                 span_description_rules: state.project_state.config.span_description_rules.as_ref(),
+                span_grouping_rules: &state.project_state.config.span_grouping_rules,
+                fingerprinting: match state.project_state.config.fingerprinting {
+                    Some(ErrorBoundary::Ok(ref config)) => Some(config),
+                    _ => None,
+                },
+                stacktrace_rules: match state.project_state.config.stacktrace_rules {
+                    Some(ErrorBoundary::Ok(ref rules)) => Some(rules),
+                    _ => None,
+                },
                 geoip_lookup: self.inner.geoip_lookup.as_ref(),
                 ai_model_costs: ai_model_costs.as_ref(),
                 enable_trimming: true,
@@ -1590,7 +1674,12 @@ impl EnvelopeProcessorService {
 
         event::finalize(state, &self.inner.config)?;
         self.normalize_event(state)?;
-        let filter_run = event::filter(state, &self.inner.global_config.current())?;
+        let filter_run = event::filter(
+            state,
+            &self.inner.global_config.current(),
+            self.inner.repeated_errors.as_ref(),
+            self.inner.geoip_lookup.as_ref(),
+        )?;
 
         if self.inner.config.processing_enabled() || matches!(filter_run, FiltersStatus::Ok) {
             dynamic_sampling::tag_error_with_sampling_decision(state, &self.inner.config);
@@ -1601,12 +1690,12 @@ impl EnvelopeProcessorService {
         });
 
         if state.has_event() {
-            event::scrub(state)?;
+            event::scrub(state, &self.inner.config)?;
             event::serialize(state)?;
             event::emit_feedback_metrics(state.envelope());
         }
 
-        attachment::scrub(state);
+        attachment::scrub(state, &self.inner.config);
 
         if self.inner.config.processing_enabled() && !state.event_fully_normalized {
             relay_log::error!(
@@ -1632,7 +1721,7 @@ impl EnvelopeProcessorService {
             &self.inner.global_config.current(),
         )?;
 
-        let profile_id = profile::filter(state);
+        let profile_id = profile::filter(state, &global_config, self.inner.geoip_lookup.as_ref());
         profile::transfer_id(state, profile_id);
 
         if_processing!(self.inner.config, {
@@ -1644,7 +1733,12 @@ impl EnvelopeProcessorService {
 
         dynamic_sampling::ensure_dsc(state);
 
-        let filter_run = event::filter(state, &self.inner.global_config.current())?;
+        let filter_run = event::filter(
+            state,
+            &self.inner.global_config.current(),
+            self.inner.repeated_errors.as_ref(),
+            self.inner.geoip_lookup.as_ref(),
+        )?;
 
         // Always run dynamic sampling on processing Relays,
         // but delay decision until inbound filters have been fully processed.
@@ -1683,8 +1777,8 @@ impl EnvelopeProcessorService {
         // Need to scrub the transaction before extracting spans.
         //
         // Unconditionally scrub to make sure PII is removed as early as possible.
-        event::scrub(state)?;
-        attachment::scrub(state);
+        event::scrub(state, &self.inner.config)?;
+        attachment::scrub(state, &self.inner.config);
 
         if_processing!(self.inner.config, {
             // Always extract metrics in processing Relays for sampled items.
@@ -1736,14 +1830,18 @@ impl EnvelopeProcessorService {
         &self,
         state: &mut ProcessEnvelopeState<StandaloneGroup>,
     ) -> Result<(), ProcessingError> {
-        profile::filter(state);
+        profile::filter(
+            state,
+            &self.inner.global_config.current(),
+            self.inner.geoip_lookup.as_ref(),
+        );
 
         if_processing!(self.inner.config, {
             self.enforce_quotas(state)?;
         });
 
         report::process_user_reports(state);
-        attachment::scrub(state);
+        attachment::scrub(state, &self.inner.config);
         Ok(())
     }
 
@@ -1752,7 +1850,12 @@ impl EnvelopeProcessorService {
         &self,
         state: &mut ProcessEnvelopeState<SessionGroup>,
     ) -> Result<(), ProcessingError> {
-        session::process(state, &self.inner.config);
+        session::process(
+            state,
+            &self.inner.config,
+            &self.inner.global_config.current(),
+            self.inner.geoip_lookup.as_ref(),
+        );
         if_processing!(self.inner.config, {
             self.enforce_quotas(state)?;
         });
@@ -1786,6 +1889,7 @@ impl EnvelopeProcessorService {
             state,
             &self.inner.config,
             &self.inner.global_config.current(),
+            self.inner.geoip_lookup.as_ref(),
         )?;
         if_processing!(self.inner.config, {
             self.enforce_quotas(state)?;
@@ -1821,6 +1925,7 @@ impl EnvelopeProcessorService {
                 state,
                 self.inner.config.clone(),
                 &global_config,
+                self.inner.geoip_lookup.as_ref(),
                 &self.inner.addrs,
                 &self.inner.buffer_guard,
             );
@@ -2811,6 +2916,21 @@ impl Service for EnvelopeProcessorService {
         };
         relay_log::info!("starting {thread_count} envelope processing workers");
 
+        if let Some(interval) = self.inner.config.geoip_reload_interval() {
+            if self.inner.geoip_lookup.is_some() {
+                let service = self.clone();
+                tokio::spawn(async move {
+                    loop {
+                        tokio::time::sleep(interval).await;
+                        let service = service.clone();
+                        tokio::task::spawn_blocking(move || service.reload_geoip())
+                            .await
+                            .ok();
+                    }
+                });
+            }
+        }
+
         tokio::spawn(async move {
             let semaphore = Arc::new(Semaphore::new(thread_count));
 
@@ -3468,6 +3588,7 @@ mod tests {
                                 substitution: "*".to_owned(),
                             },
                         }],
+                        ..Default::default()
                     },
                     ..Default::default()
                 };
@@ -3738,4 +3859,26 @@ mod tests {
             assert_eq!(buckets[0].metadata.received_at, expected_received_at);
         }
     }
+
+    #[test]
+    fn test_pii_hash_key_only_in_trusted_relays() {
+        let mut project_config = ProjectConfig::default();
+        project_config.pii_hash_key = Some("project".to_owned());
+
+        let config = Config::from_json_value(serde_json::json!({
+            "pii": {"hash_key": "relay"}
+        }))
+        .unwrap();
+        assert_eq!(pii_hash_key(&config, &project_config), None);
+
+        let config = Config::from_json_value(serde_json::json!({
+            "pii": {"hash_key": "relay"},
+            "cache": {"project_request_full_config": true}
+        }))
+        .unwrap();
+        assert_eq!(pii_hash_key(&config, &project_config), Some("project"));
+
+        project_config.pii_hash_key = None;
+        assert_eq!(pii_hash_key(&config, &project_config), Some("relay"));
+    }
 }
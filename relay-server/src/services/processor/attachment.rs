@@ -3,12 +3,15 @@
 use std::error::Error;
 use std::time::Instant;
 
-use relay_pii::PiiAttachmentsProcessor;
+use relay_config::Config;
+use relay_pii::{PiiAttachmentsProcessor, ScrubContainerError};
 use relay_statsd::metric;
 
 use crate::envelope::{AttachmentType, ContentType};
-use crate::services::processor::ProcessEnvelopeState;
-use crate::statsd::RelayTimers;
+use crate::services::outcome::{DiscardReason, Outcome};
+use crate::services::processor::{pii_hash_key, ProcessEnvelopeState};
+use crate::statsd::{RelayCounters, RelayTimers};
+use crate::utils::ItemAction;
 
 #[cfg(feature = "processing")]
 use {
@@ -48,9 +51,17 @@ pub fn create_placeholders<G: EventProcessing>(state: &mut ProcessEnvelopeState<
 /// This only applies the new PII rules that explicitly select `ValueType::Binary` or one of the
 /// attachment types. When special attachments are detected, these are scrubbed with custom
 /// logic; otherwise the entire attachment is treated as a single binary blob.
-pub fn scrub<G>(state: &mut ProcessEnvelopeState<G>) {
+///
+/// Plain attachments in compressed or structured formats are unpacked and scrubbed if the PII
+/// config has selectors for attachments. Attachments that cannot be unpacked or exceed the maximum
+/// scrubbing size when decompressed are dropped, since they could contain unscrubbed PII.
+pub fn scrub<G>(state: &mut ProcessEnvelopeState<G>, config: &Config) {
     let envelope = state.managed_envelope.envelope_mut();
-    if let Some(ref config) = state.project_state.config.pii_config {
+    let project_config = &state.project_state.config;
+    if let Some(ref pii_config) = project_config.pii_config {
+        let processor = PiiAttachmentsProcessor::new(pii_config.compiled())
+            .with_hash_key(pii_hash_key(config, project_config));
+
         let minidump = envelope
             .get_item_by_mut(|item| item.attachment_type() == Some(&AttachmentType::Minidump));
 
@@ -58,8 +69,6 @@ pub fn scrub<G>(state: &mut ProcessEnvelopeState<G>) {
             let filename = item.filename().unwrap_or_default();
             let mut payload = item.payload().to_vec();
 
-            let processor = PiiAttachmentsProcessor::new(config.compiled());
-
             // Minidump scrubbing can fail if the minidump cannot be parsed. In this case, we
             // must be conservative and treat it as a plain attachment. Under extreme
             // conditions, this could destroy stack memory.
@@ -93,5 +102,60 @@ pub fn scrub<G>(state: &mut ProcessEnvelopeState<G>) {
 
             item.set_payload(content_type, payload);
         }
+
+        if !pii_config.compiled().has_attachment_selectors() {
+            return;
+        }
+
+        state.managed_envelope.retain_items(|item| {
+            if item.attachment_type() != Some(&AttachmentType::Attachment) {
+                return ItemAction::Keep;
+            }
+
+            let filename = item.filename().unwrap_or_default();
+            let content_type = item.content_type().map(ContentType::as_str);
+            let payload = item.payload();
+
+            let start = Instant::now();
+            let result = processor.scrub_container(
+                filename,
+                content_type,
+                &payload,
+                config.max_attachment_scrubbing_size(),
+            );
+            let (status, action) = match result {
+                Ok(Some(scrubbed)) => {
+                    let content_type = item
+                        .content_type()
+                        .unwrap_or(&ContentType::OctetStream)
+                        .clone();
+                    item.set_payload(content_type, scrubbed);
+                    ("ok", ItemAction::Keep)
+                }
+                Ok(None) => ("n/a", ItemAction::Keep),
+                Err(scrub_error) => {
+                    relay_log::debug!(
+                        error = &scrub_error as &dyn Error,
+                        "dropping attachment that cannot be scrubbed",
+                    );
+                    let (reason, discard_reason) = match scrub_error {
+                        ScrubContainerError::TooLarge => ("too_large", DiscardReason::TooLarge),
+                        _ => ("invalid", DiscardReason::InvalidCompression),
+                    };
+                    metric!(
+                        counter(RelayCounters::AttachmentScrubbingFailed) += 1,
+                        reason = reason,
+                    );
+                    ("error", ItemAction::Drop(Outcome::Invalid(discard_reason)))
+                }
+            };
+
+            metric!(
+                timer(RelayTimers::AttachmentContainerScrubbing) = start.elapsed(),
+                status = status,
+            );
+
+            action
+        });
     }
 }
@@ -0,0 +1,90 @@
+//! Contains the monitor check-in related processor code.
+
+use relay_event_schema::protocol::{Csp, Exception, LogEntry, Values};
+use relay_filter::Filterable;
+use relay_monitors::CheckIn;
+use relay_protocol::{Getter, Val};
+use url::Url;
+
+/// A processed monitor check-in, to which inbound filters can be applied.
+///
+/// Check-ins only carry an environment, monitor slug and status, which generic filters can match
+/// with the `event.environment`, `event.monitor_slug` and `event.status` fields.
+pub struct FilterableCheckIn<'a>(pub &'a CheckIn);
+
+impl Getter for FilterableCheckIn<'_> {
+    fn get_value(&self, path: &str) -> Option<Val<'_>> {
+        let check_in = self.0;
+        Some(match path.strip_prefix("event.")? {
+            "environment" => check_in.environment.as_deref()?.into(),
+            "monitor_slug" => check_in.monitor_slug.as_str().into(),
+            "status" => check_in.status.as_str().into(),
+            _ => return None,
+        })
+    }
+}
+
+impl Filterable for FilterableCheckIn<'_> {
+    fn csp(&self) -> Option<&Csp> {
+        None
+    }
+
+    fn exceptions(&self) -> Option<&Values<Exception>> {
+        None
+    }
+
+    fn ip_addr(&self) -> Option<&str> {
+        None
+    }
+
+    fn logentry(&self) -> Option<&LogEntry> {
+        None
+    }
+
+    fn release(&self) -> Option<&str> {
+        None
+    }
+
+    fn transaction(&self) -> Option<&str> {
+        None
+    }
+
+    fn url(&self) -> Option<Url> {
+        None
+    }
+
+    fn user_agent(&self) -> Option<&str> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_in_getter() {
+        let json = r#"{
+          "check_in_id": "a460c25ff2554577b920fcfacae4e5eb",
+          "monitor_slug": "my-monitor",
+          "status": "in_progress",
+          "environment": "dev"
+        }"#;
+
+        let check_in = serde_json::from_str::<CheckIn>(json).unwrap();
+        let filterable = FilterableCheckIn(&check_in);
+        assert_eq!(
+            filterable.get_value("event.environment"),
+            Some(Val::String("dev"))
+        );
+        assert_eq!(
+            filterable.get_value("event.monitor_slug"),
+            Some(Val::String("my-monitor"))
+        );
+        assert_eq!(
+            filterable.get_value("event.status"),
+            Some(Val::String("in_progress"))
+        );
+        assert_eq!(filterable.get_value("event.release"), None);
+    }
+}
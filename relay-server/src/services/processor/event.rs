@@ -8,12 +8,13 @@ use relay_auth::RelayVersion;
 use relay_base_schema::events::EventType;
 use relay_config::Config;
 use relay_dynamic_config::{Feature, GlobalConfig};
-use relay_event_normalization::{nel, ClockDriftProcessor};
+use relay_event_normalization::{nel, ClockDriftProcessor, GeoIpLookup};
 use relay_event_schema::processor::{self, ProcessingState};
 use relay_event_schema::protocol::{
     Breadcrumb, Csp, Event, ExpectCt, ExpectStaple, Hpkp, LenientString, NetworkReportError,
     OtelContext, RelayInfo, SecurityReportType, Values,
 };
+use relay_filter::repeated_errors::RepeatedErrorsCounter;
 use relay_pii::PiiProcessor;
 use relay_protocol::{Annotated, Array, Empty, FromValue, Object, Value};
 use relay_quotas::DataCategory;
@@ -24,7 +25,8 @@ use crate::envelope::{AttachmentType, ContentType, Envelope, Item, ItemType};
 use crate::extractors::RequestMeta;
 use crate::services::outcome::Outcome;
 use crate::services::processor::{
-    EventProcessing, ExtractedEvent, ProcessEnvelopeState, ProcessingError, MINIMUM_CLOCK_DRIFT,
+    pii_hash_key, EventProcessing, ExtractedEvent, ProcessEnvelopeState, ProcessingError,
+    MINIMUM_CLOCK_DRIFT,
 };
 use crate::statsd::{PlatformTag, RelayCounters, RelayHistograms, RelayTimers};
 use crate::utils::{self, ChunkedFormDataAggregator, FormDataIter};
@@ -295,6 +297,8 @@ pub enum FiltersStatus {
 pub fn filter<G: EventProcessing>(
     state: &mut ProcessEnvelopeState<G>,
     global_config: &GlobalConfig,
+    repeated_errors: &dyn RepeatedErrorsCounter,
+    geoip_lookup: Option<&GeoIpLookup>,
 ) -> Result<FiltersStatus, ProcessingError> {
     let event = match state.event.value_mut() {
         Some(event) => event,
@@ -307,13 +311,34 @@ pub fn filter<G: EventProcessing>(
     let filter_settings = &state.project_state.config.filter_settings;
 
     metric!(timer(RelayTimers::EventProcessingFiltering), {
-        relay_filter::should_filter(event, client_ip, filter_settings, global_config.filters())
-            .map_err(|err| {
-                state
-                    .managed_envelope
-                    .reject(Outcome::Filtered(err.clone()));
-                ProcessingError::EventFiltered(err)
-            })
+        relay_filter::should_filter(
+            event,
+            client_ip,
+            filter_settings,
+            global_config.filters(),
+            global_config.web_crawlers(),
+            global_config.legacy_browsers(),
+            geoip_lookup,
+        )
+        .and_then(|()| {
+            // Transactions are never grouped into issues, so they are not repeated errors.
+            if event.ty.value() == Some(&EventType::Transaction) {
+                return Ok(());
+            }
+
+            relay_filter::repeated_errors::should_filter(
+                event,
+                state.project_id,
+                &filter_settings.repeated_errors,
+                repeated_errors,
+            )
+        })
+        .map_err(|err| {
+            state
+                .managed_envelope
+                .reject(Outcome::Filtered(err.clone()));
+            ProcessingError::EventFiltered(err)
+        })
     })?;
 
     // Don't extract metrics if relay can't apply generic filters.  A filter
@@ -337,27 +362,36 @@ pub fn filter<G: EventProcessing>(
 /// This uses both the general `datascrubbing_settings`, as well as the the PII rules.
 pub fn scrub<G: EventProcessing>(
     state: &mut ProcessEnvelopeState<G>,
+    config: &Config,
 ) -> Result<(), ProcessingError> {
+    let category = state.event_category();
     let event = &mut state.event;
-    let config = &state.project_state.config;
+    let project_config = &state.project_state.config;
 
-    if config.datascrubbing_settings.scrub_data {
+    if project_config.datascrubbing_settings.scrub_data {
         if let Some(event) = event.value_mut() {
             relay_pii::scrub_graphql(event);
         }
     }
 
     metric!(timer(RelayTimers::EventProcessingPii), {
-        if let Some(ref config) = config.pii_config {
-            let mut processor = PiiProcessor::new(config.compiled());
+        if let Some(ref pii_config) = project_config.pii_config {
+            let mut processor = PiiProcessor::new(pii_config.compiled())
+                .with_hash_key(pii_hash_key(config, project_config));
+            if let Some(category) = category {
+                processor = processor.with_scope(category);
+            }
             processor::process_value(event, &mut processor, ProcessingState::root())?;
         }
-        let pii_config = config
+        let pii_config = project_config
             .datascrubbing_settings
             .pii_config()
             .map_err(|e| ProcessingError::PiiConfigError(e.clone()))?;
         if let Some(config) = pii_config {
             let mut processor = PiiProcessor::new(config.compiled());
+            if let Some(category) = category {
+                processor = processor.with_scope(category);
+            }
             processor::process_value(event, &mut processor, ProcessingState::root())?;
         }
     });
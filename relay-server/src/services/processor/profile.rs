@@ -1,27 +1,35 @@
 //! Profiles related processor code.
 
-use relay_dynamic_config::Feature;
+use relay_dynamic_config::{Feature, GlobalConfig};
 
 use relay_base_schema::events::EventType;
 use relay_config::Config;
+use relay_event_normalization::GeoIpLookup;
 use relay_event_schema::protocol::{Contexts, Event, ProfileContext};
-use relay_profiling::{ProfileError, ProfileId};
+use relay_profiling::{ProfileError, ProfileId, ProfileScrubber};
 use relay_protocol::Annotated;
 
 use crate::envelope::{ContentType, Item, ItemType};
 use crate::services::outcome::{DiscardReason, Outcome};
-use crate::services::processor::{ProcessEnvelopeState, TransactionGroup};
+use crate::services::processor::{pii_hash_key, ProcessEnvelopeState, TransactionGroup};
+use crate::services::project::ProjectState;
 use crate::utils::ItemAction;
 
-/// Filters out invalid and duplicate profiles.
+/// Filters out invalid and duplicate profiles, as well as profiles matching inbound filters.
 ///
 /// Returns the profile id of the single remaining profile, if there is one.
-pub fn filter<G>(state: &mut ProcessEnvelopeState<G>) -> Option<ProfileId> {
+pub fn filter<G>(
+    state: &mut ProcessEnvelopeState<G>,
+    global_config: &GlobalConfig,
+    geoip_lookup: Option<&GeoIpLookup>,
+) -> Option<ProfileId> {
     let profiling_enabled = state.project_state.has_feature(Feature::Profiling);
     let has_transaction = state.event_type() == Some(EventType::Transaction);
     let keep_unsampled_profiles = state
         .project_state
         .has_feature(Feature::IngestUnsampledProfiles);
+    let client_ip = state.managed_envelope.envelope().meta().client_addr();
+    let filter_settings = &state.project_state.config.filter_settings;
 
     let mut profile_id = None;
     state.managed_envelope.retain_items(|item| match item.ty() {
@@ -39,8 +47,20 @@ pub fn filter<G>(state: &mut ProcessEnvelopeState<G>) -> Option<ProfileId> {
             }
 
             match relay_profiling::parse_metadata(&item.payload(), state.project_id) {
-                Ok(id) => {
-                    profile_id = Some(id);
+                Ok(info) => {
+                    if let Err(filter_stat_key) = relay_filter::should_filter(
+                        &info,
+                        client_ip,
+                        filter_settings,
+                        global_config.filters(),
+                        global_config.web_crawlers(),
+                        global_config.legacy_browsers(),
+                        geoip_lookup,
+                    ) {
+                        return ItemAction::Drop(Outcome::Filtered(filter_stat_key));
+                    }
+
+                    profile_id = Some(info.profile_id);
                     ItemAction::Keep
                 }
                 Err(err) => ItemAction::Drop(Outcome::Invalid(DiscardReason::Profiling(
@@ -89,6 +109,8 @@ pub fn transfer_id(
 /// Processes profiles and set the profile ID in the profile context on the transaction if successful.
 pub fn process(state: &mut ProcessEnvelopeState<TransactionGroup>, config: &Config) {
     let profiling_enabled = state.project_state.has_feature(Feature::Profiling);
+    let mut scrubber = scrubber(&state.project_state, config);
+
     state.managed_envelope.retain_items(|item| match item.ty() {
         ItemType::Profile => {
             if !profiling_enabled {
@@ -101,15 +123,50 @@ pub fn process(state: &mut ProcessEnvelopeState<TransactionGroup>, config: &Conf
                 return ItemAction::DropSilently;
             };
 
-            expand_profile(item, event, config)
+            match scrubber {
+                Ok(ref mut scrubber) => expand_profile(item, event, config, scrubber.as_mut()),
+                Err(ref outcome) => ItemAction::Drop(outcome.clone()),
+            }
         }
         _ => ItemAction::Keep,
     });
 }
 
+/// Creates a scrubber for profiles if data scrubbing of profiles is enabled for the project.
+///
+/// Profiles must not be forwarded unscrubbed, so an outcome is returned if the data scrubbing
+/// settings cannot be converted to a PII config.
+pub fn scrubber<'a>(
+    project_state: &'a ProjectState,
+    config: &'a Config,
+) -> Result<Option<ProfileScrubber<'a>>, Outcome> {
+    if !project_state.has_feature(Feature::ProfileScrubbing) {
+        return Ok(None);
+    }
+
+    let project_config = &project_state.config;
+    let datascrubbing_config = project_config
+        .datascrubbing_settings
+        .pii_config()
+        .map_err(|_| Outcome::Invalid(DiscardReason::ProjectStatePii))?;
+
+    Ok(Some(
+        ProfileScrubber::new(
+            project_config.pii_config.as_ref(),
+            datascrubbing_config.as_ref(),
+        )
+        .with_hash_key(pii_hash_key(config, project_config)),
+    ))
+}
+
 /// Transfers transaction metadata to profile and check its size.
-fn expand_profile(item: &mut Item, event: &Event, config: &Config) -> ItemAction {
-    match relay_profiling::expand_profile(&item.payload(), event) {
+fn expand_profile(
+    item: &mut Item,
+    event: &Event,
+    config: &Config,
+    scrubber: Option<&mut ProfileScrubber<'_>>,
+) -> ItemAction {
+    match relay_profiling::expand_profile(&item.payload(), event, scrubber) {
         Ok((_id, payload)) => {
             if payload.len() <= config.max_profile_size() {
                 item.set_payload(ContentType::Json, payload);
@@ -140,7 +197,6 @@ mod tests {
     use crate::envelope::Envelope;
     use crate::extractors::RequestMeta;
     use crate::services::processor::{ProcessEnvelope, ProcessingGroup};
-    use crate::services::project::ProjectState;
     use crate::testutils::create_test_processor;
     use crate::utils::ManagedEnvelope;
 
@@ -9,7 +9,7 @@ use crate::utils::ItemAction;
 use {
     crate::envelope::ContentType,
     crate::services::outcome::{DiscardReason, Outcome},
-    crate::services::processor::ProfileChunkGroup,
+    crate::services::processor::{profile, ProfileChunkGroup},
     relay_config::Config,
 };
 
@@ -30,12 +30,18 @@ pub fn process(state: &mut ProcessEnvelopeState<ProfileChunkGroup>, config: &Con
     let continuous_profiling_enabled = state
         .project_state
         .has_feature(Feature::ContinuousProfiling);
+    let mut scrubber = profile::scrubber(&state.project_state, config);
+
     state.managed_envelope.retain_items(|item| match item.ty() {
         ItemType::ProfileChunk => {
             if !continuous_profiling_enabled {
                 return ItemAction::DropSilently;
             }
-            match relay_profiling::expand_profile_chunk(&item.payload()) {
+            let scrubber = match scrubber {
+                Ok(ref mut scrubber) => scrubber.as_mut(),
+                Err(ref outcome) => return ItemAction::Drop(outcome.clone()),
+            };
+            match relay_profiling::expand_profile_chunk(&item.payload(), scrubber) {
                 Ok(payload) => {
                     if payload.len() <= config.max_profile_size() {
                         item.set_payload(ContentType::Json, payload);
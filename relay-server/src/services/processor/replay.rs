@@ -6,18 +6,21 @@ use bytes::Bytes;
 use relay_config::Config;
 use relay_dynamic_config::{Feature, GlobalConfig, ProjectConfig};
 use relay_event_normalization::replay::{self, ReplayError};
-use relay_event_normalization::RawUserAgentInfo;
+use relay_event_normalization::{GeoIpLookup, RawUserAgentInfo};
 use relay_event_schema::processor::{self, ProcessingState};
 use relay_event_schema::protocol::{EventId, Replay};
 use relay_pii::PiiProcessor;
 use relay_protocol::Annotated;
+use relay_quotas::DataCategory;
 use relay_replays::recording::RecordingScrubber;
 use relay_statsd::metric;
 use serde::{Deserialize, Serialize};
 
 use crate::envelope::{ContentType, ItemType};
 use crate::services::outcome::DiscardReason;
-use crate::services::processor::{ProcessEnvelopeState, ProcessingError, ReplayGroup};
+use crate::services::processor::{
+    pii_hash_key, ProcessEnvelopeState, ProcessingError, ReplayGroup,
+};
 use crate::statsd::RelayTimers;
 
 /// Removes replays if the feature flag is not enabled.
@@ -25,6 +28,7 @@ pub fn process(
     state: &mut ProcessEnvelopeState<ReplayGroup>,
     config: &Config,
     global_config: &GlobalConfig,
+    geoip_lookup: Option<&GeoIpLookup>,
 ) -> Result<(), ProcessingError> {
     let project_state = &state.project_state;
     let replays_enabled = project_state.has_feature(Feature::SessionReplay);
@@ -36,6 +40,7 @@ pub fn process(
 
     let limit = config.max_replay_uncompressed_size();
     let project_config = project_state.config();
+    let hash_key = pii_hash_key(config, project_config);
     let datascrubbing_config = project_config
         .datascrubbing_settings
         .pii_config()
@@ -45,7 +50,8 @@ pub fn process(
         limit,
         project_config.pii_config.as_ref(),
         datascrubbing_config,
-    );
+    )
+    .with_hash_key(hash_key);
 
     let user_agent = &RawUserAgentInfo {
         user_agent: meta.user_agent(),
@@ -70,7 +76,9 @@ pub fn process(
                     item.payload(),
                     &event_id,
                     project_config,
+                    hash_key,
                     global_config,
+                    geoip_lookup,
                     client_addr,
                     user_agent,
                 )?;
@@ -90,7 +98,9 @@ pub fn process(
                     item.payload(),
                     &event_id,
                     project_config,
+                    hash_key,
                     global_config,
+                    geoip_lookup,
                     client_addr,
                     user_agent,
                     scrubbing_enabled,
@@ -107,17 +117,20 @@ pub fn process(
 
 // Replay Event Processing.
 
+#[allow(clippy::too_many_arguments)]
 fn handle_replay_event_item(
     payload: Bytes,
     event_id: &Option<EventId>,
     config: &ProjectConfig,
+    hash_key: Option<&str>,
     global_config: &GlobalConfig,
+    geoip_lookup: Option<&GeoIpLookup>,
     client_ip: Option<IpAddr>,
     user_agent: &RawUserAgentInfo<&str>,
 ) -> Result<Bytes, ProcessingError> {
     let filter_settings = &config.filter_settings;
 
-    match process_replay_event(&payload, config, client_ip, user_agent) {
+    match process_replay_event(&payload, config, hash_key, client_ip, user_agent) {
         Ok(replay) => {
             if let Some(replay_type) = replay.value() {
                 relay_filter::should_filter(
@@ -125,6 +138,9 @@ fn handle_replay_event_item(
                     client_ip,
                     filter_settings,
                     global_config.filters(),
+                    global_config.web_crawlers(),
+                    global_config.legacy_browsers(),
+                    geoip_lookup,
                 )
                 .map_err(ProcessingError::ReplayFiltered)?;
             }
@@ -169,6 +185,7 @@ fn handle_replay_event_item(
 fn process_replay_event(
     payload: &[u8],
     config: &ProjectConfig,
+    hash_key: Option<&str>,
     client_ip: Option<IpAddr>,
     user_agent: &RawUserAgentInfo<&str>,
 ) -> Result<Annotated<Replay>, ReplayError> {
@@ -183,7 +200,9 @@ fn process_replay_event(
     replay::normalize(&mut replay, client_ip, user_agent);
 
     if let Some(ref config) = config.pii_config {
-        let mut processor = PiiProcessor::new(config.compiled());
+        let mut processor = PiiProcessor::new(config.compiled())
+            .with_hash_key(hash_key)
+            .with_scope(DataCategory::Replay);
         processor::process_value(&mut replay, &mut processor, ProcessingState::root())
             .map_err(|e| ReplayError::CouldNotScrub(e.to_string()))?;
     }
@@ -193,7 +212,7 @@ fn process_replay_event(
         .pii_config()
         .map_err(|e| ReplayError::CouldNotScrub(e.to_string()))?;
     if let Some(config) = pii_config {
-        let mut processor = PiiProcessor::new(config.compiled());
+        let mut processor = PiiProcessor::new(config.compiled()).with_scope(DataCategory::Replay);
         processor::process_value(&mut replay, &mut processor, ProcessingState::root())
             .map_err(|e| ReplayError::CouldNotScrub(e.to_string()))?;
     }
@@ -248,7 +267,9 @@ fn handle_replay_video_item(
     payload: Bytes,
     event_id: &Option<EventId>,
     config: &ProjectConfig,
+    hash_key: Option<&str>,
     global_config: &GlobalConfig,
+    geoip_lookup: Option<&GeoIpLookup>,
     client_ip: Option<IpAddr>,
     user_agent: &RawUserAgentInfo<&str>,
     scrubbing_enabled: bool,
@@ -273,7 +294,9 @@ fn handle_replay_video_item(
         replay_event,
         event_id,
         config,
+        hash_key,
         global_config,
+        geoip_lookup,
         client_ip,
         user_agent,
     )?;
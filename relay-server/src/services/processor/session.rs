@@ -4,27 +4,47 @@ use std::error::Error;
 use std::net;
 
 use chrono::{DateTime, Duration as SignedDuration, Utc};
+use relay_base_schema::data_category::DataCategory;
 use relay_config::Config;
-use relay_dynamic_config::SessionMetricsConfig;
-use relay_event_normalization::ClockDriftProcessor;
+use relay_dynamic_config::{GlobalConfig, SessionMetricsConfig};
+use relay_event_normalization::{ClockDriftProcessor, GeoIpLookup};
 use relay_event_schema::protocol::{
     IpAddr, SessionAggregates, SessionAttributes, SessionStatus, SessionUpdate,
 };
+use relay_filter::{
+    FilterStatKey, Filterable, GenericFiltersConfig, LegacyBrowsersConfig, ProjectFiltersConfig,
+    WebCrawlersConfig,
+};
 use relay_metrics::Bucket;
+use relay_protocol::Getter;
 use relay_statsd::metric;
 
 use crate::envelope::{ContentType, Item, ItemType};
+use crate::services::outcome::Outcome;
 use crate::services::processor::{ProcessEnvelopeState, SessionGroup, MINIMUM_CLOCK_DRIFT};
 use crate::statsd::RelayTimers;
 use crate::utils::ItemAction;
 
 /// Validates all sessions and session aggregates in the envelope, if any.
 ///
-/// Both are removed from the envelope if they contain invalid JSON or if their timestamps
-/// are out of range after clock drift correction.
-pub fn process(state: &mut ProcessEnvelopeState<SessionGroup>, config: &Config) {
+/// Both are removed from the envelope if they contain invalid JSON, if their timestamps
+/// are out of range after clock drift correction, or if they match inbound filters.
+pub fn process(
+    state: &mut ProcessEnvelopeState<SessionGroup>,
+    config: &Config,
+    global_config: &GlobalConfig,
+    geoip_lookup: Option<&GeoIpLookup>,
+) {
     let received = state.managed_envelope.received_at();
     let metrics_config = state.project_state.config().session_metrics;
+    let mut filters = SessionFilters {
+        project: &state.project_state.config.filter_settings,
+        global: global_config.filters(),
+        web_crawlers: global_config.web_crawlers(),
+        legacy_browsers: global_config.legacy_browsers(),
+        geoip_lookup,
+        filtered: Vec::new(),
+    };
     let envelope = state.managed_envelope.envelope_mut();
     let client = envelope.meta().client().map(|x| x.to_owned());
     let client_addr = envelope.meta().client_addr();
@@ -43,6 +63,7 @@ pub fn process(state: &mut ProcessEnvelopeState<SessionGroup>, config: &Config)
                 client_addr,
                 metrics_config,
                 &clock_drift_processor,
+                &mut filters,
                 &mut extracted_metrics,
             ),
             ItemType::Sessions => process_session_aggregates(
@@ -53,6 +74,7 @@ pub fn process(state: &mut ProcessEnvelopeState<SessionGroup>, config: &Config)
                 client_addr,
                 metrics_config,
                 &clock_drift_processor,
+                &mut filters,
                 &mut extracted_metrics,
             ),
             _ => true, // Keep all other item types
@@ -60,15 +82,64 @@ pub fn process(state: &mut ProcessEnvelopeState<SessionGroup>, config: &Config)
         if should_keep {
             ItemAction::Keep
         } else {
-            ItemAction::DropSilently // sessions never log outcomes.
+            ItemAction::DropSilently // sessions only log outcomes when filtered, see below.
         }
     });
 
+    for (filter_stat_key, quantity) in filters.filtered {
+        state.managed_envelope.track_outcome(
+            Outcome::Filtered(filter_stat_key),
+            DataCategory::Session,
+            quantity,
+        );
+    }
+
     state
         .extracted_metrics
         .extend_project_metrics(extracted_metrics, None);
 }
 
+/// Inbound filters of the project and global config that apply to sessions.
+struct SessionFilters<'a> {
+    project: &'a ProjectFiltersConfig,
+    global: Option<&'a GenericFiltersConfig>,
+    web_crawlers: Option<&'a WebCrawlersConfig>,
+    legacy_browsers: Option<&'a LegacyBrowsersConfig>,
+    geoip_lookup: Option<&'a GeoIpLookup>,
+    /// Filter keys and the number of sessions dropped by inbound filters.
+    filtered: Vec<(FilterStatKey, usize)>,
+}
+
+impl SessionFilters<'_> {
+    /// Returns `false` if the session matches an inbound filter.
+    ///
+    /// Filtered sessions are recorded with their `quantity`, so that a [`Outcome::Filtered`]
+    /// can be emitted for them once all items have been processed.
+    fn should_keep<F: Filterable + Getter>(
+        &mut self,
+        session: &F,
+        client_addr: Option<net::IpAddr>,
+        quantity: usize,
+    ) -> bool {
+        match relay_filter::should_filter(
+            session,
+            client_addr,
+            self.project,
+            self.global,
+            self.web_crawlers,
+            self.legacy_browsers,
+            self.geoip_lookup,
+        ) {
+            Ok(()) => true,
+            Err(filter_stat_key) => {
+                relay_log::trace!("skipping session matching inbound filter {filter_stat_key}");
+                self.filtered.push((filter_stat_key, quantity));
+                false
+            }
+        }
+    }
+}
+
 /// Returns Ok(true) if attributes were modified.
 /// Returns Err if the session should be dropped.
 fn validate_attributes(
@@ -143,6 +214,7 @@ fn process_session(
     client_addr: Option<net::IpAddr>,
     metrics_config: SessionMetricsConfig,
     clock_drift_processor: &ClockDriftProcessor,
+    filters: &mut SessionFilters<'_>,
     extracted_metrics: &mut Vec<Bucket>,
 ) -> bool {
     let mut changed = false;
@@ -204,6 +276,10 @@ fn process_session(
         }
     }
 
+    if !filters.should_keep(&session, client_addr, 1) {
+        return false;
+    }
+
     if config.processing_enabled() && matches!(session.status, SessionStatus::Unknown(_)) {
         return false;
     }
@@ -259,6 +335,7 @@ fn process_session_aggregates(
     client_addr: Option<net::IpAddr>,
     metrics_config: SessionMetricsConfig,
     clock_drift_processor: &ClockDriftProcessor,
+    filters: &mut SessionFilters<'_>,
     extracted_metrics: &mut Vec<Bucket>,
 ) -> bool {
     let mut changed = false;
@@ -308,6 +385,17 @@ fn process_session_aggregates(
         }
     }
 
+    let quantity = session
+        .aggregates
+        .iter()
+        .map(|aggregate| {
+            aggregate.exited + aggregate.errored + aggregate.abnormal + aggregate.crashed
+        })
+        .sum::<u32>();
+    if !filters.should_keep(&session, client_addr, quantity as usize) {
+        return false;
+    }
+
     // Extract metrics if they haven't been extracted by a prior Relay
     if metrics_config.is_enabled() && !item.metrics_extracted() {
         for aggregate in &session.aggregates {
@@ -355,12 +443,23 @@ mod tests {
         client_addr: Option<net::IpAddr>,
         metrics_config: SessionMetricsConfig,
         clock_drift_processor: ClockDriftProcessor,
+        filters: ProjectFiltersConfig,
+        filtered: Vec<(FilterStatKey, usize)>,
         extracted_metrics: Vec<Bucket>,
     }
 
     impl<'a> TestProcessSessionArguments<'a> {
         fn run_session_producer(&mut self) -> bool {
-            process_session(
+            let mut filters = SessionFilters {
+                project: &self.filters,
+                global: None,
+                web_crawlers: None,
+                legacy_browsers: None,
+                geoip_lookup: None,
+                filtered: Vec::new(),
+            };
+
+            let keep = process_session(
                 &mut self.item,
                 &Config::default(),
                 self.received,
@@ -368,8 +467,12 @@ mod tests {
                 self.client_addr,
                 self.metrics_config,
                 &self.clock_drift_processor,
+                &mut filters,
                 &mut self.extracted_metrics,
-            )
+            );
+
+            self.filtered.extend(filters.filtered);
+            keep
         }
 
         fn default() -> Self {
@@ -404,6 +507,8 @@ mod tests {
                 )
                 .unwrap(),
                 clock_drift_processor: ClockDriftProcessor::new(None, received),
+                filters: ProjectFiltersConfig::default(),
+                filtered: vec![],
                 extracted_metrics: vec![],
             }
         }
@@ -459,4 +564,13 @@ mod tests {
         args.item.set_metrics_extracted(true);
         assert!(!args.run_session_producer());
     }
+
+    #[test]
+    fn test_process_session_filtered_release() {
+        let mut args = TestProcessSessionArguments::default();
+        args.filters = serde_json::from_str(r#"{"releases": {"releases": ["1.*"]}}"#).unwrap();
+        assert!(!args.run_session_producer());
+        assert!(args.extracted_metrics.is_empty());
+        assert_eq!(args.filtered, vec![(FilterStatKey::ReleaseVersion, 1)]);
+    }
 }
@@ -14,7 +14,7 @@ use relay_event_normalization::{
     span::tag_extraction, validate_span, CombinedMeasurementsConfig, MeasurementsConfig,
     PerformanceScoreConfig, RawUserAgentInfo, TransactionsProcessor,
 };
-use relay_event_normalization::{normalize_transaction_name, ModelCosts};
+use relay_event_normalization::{normalize_transaction_name, GeoIpLookup, ModelCosts};
 use relay_event_schema::processor::{process_value, ProcessingState};
 use relay_event_schema::protocol::{BrowserContext, Contexts, Event, Span, SpanData};
 use relay_log::protocol::{Attachment, AttachmentType};
@@ -29,7 +29,7 @@ use crate::metrics_extraction::generic::extract_metrics;
 use crate::services::outcome::{DiscardReason, Outcome};
 use crate::services::processor::span::extract_transaction_span;
 use crate::services::processor::{
-    dynamic_sampling, Addrs, ProcessEnvelope, ProcessEnvelopeState, ProcessingError,
+    dynamic_sampling, pii_hash_key, Addrs, ProcessEnvelope, ProcessEnvelopeState, ProcessingError,
     ProcessingGroup, SpanGroup, TransactionGroup,
 };
 use crate::statsd::{RelayCounters, RelayHistograms};
@@ -45,6 +45,7 @@ pub fn process(
     state: &mut ProcessEnvelopeState<SpanGroup>,
     config: Arc<Config>,
     global_config: &GlobalConfig,
+    geoip_lookup: Option<&GeoIpLookup>,
     addrs: &Addrs,
     buffer_guard: &BufferGuard,
 ) {
@@ -135,6 +136,9 @@ pub fn process(
                 client_ip,
                 filter_settings,
                 global_config.filters(),
+                global_config.web_crawlers(),
+                global_config.legacy_browsers(),
+                geoip_lookup,
             ) {
                 relay_log::trace!(
                     "filtering span {:?} that matched an inbound filter",
@@ -173,7 +177,7 @@ pub fn process(
             return ItemAction::DropSilently;
         }
 
-        if let Err(e) = scrub(&mut annotated_span, &state.project_state.config) {
+        if let Err(e) = scrub(&mut annotated_span, &config, &state.project_state.config) {
             relay_log::error!("failed to scrub span: {e}");
         }
 
@@ -569,7 +573,16 @@ fn normalize(
 
     // Tag extraction:
     let is_mobile = false; // TODO: find a way to determine is_mobile from a standalone span.
-    let tags = tag_extraction::extract_tags(span, max_tag_value_size, None, None, is_mobile, None);
+    let tags = tag_extraction::extract_tags(
+        span,
+        max_tag_value_size,
+        None,
+        None,
+        is_mobile,
+        None,
+        &project_config.span_grouping_rules,
+        Utc::now(),
+    );
     span.sentry_tags = Annotated::new(
         tags.into_iter()
             .map(|(k, v)| (k.sentry_tag_key().to_owned(), Annotated::new(v)))
@@ -601,10 +614,13 @@ fn normalize(
 
 fn scrub(
     annotated_span: &mut Annotated<Span>,
+    config: &Config,
     project_config: &ProjectConfig,
 ) -> Result<(), ProcessingError> {
-    if let Some(ref config) = project_config.pii_config {
-        let mut processor = PiiProcessor::new(config.compiled());
+    if let Some(ref pii_config) = project_config.pii_config {
+        let mut processor = PiiProcessor::new(pii_config.compiled())
+            .with_hash_key(pii_hash_key(config, project_config))
+            .with_scope(DataCategory::Span);
         process_value(annotated_span, &mut processor, ProcessingState::root())?;
     }
     let pii_config = project_config
@@ -612,7 +628,7 @@ fn scrub(
         .pii_config()
         .map_err(|e| ProcessingError::PiiConfigError(e.clone()))?;
     if let Some(config) = pii_config {
-        let mut processor = PiiProcessor::new(config.compiled());
+        let mut processor = PiiProcessor::new(config.compiled()).with_scope(DataCategory::Span);
         process_value(annotated_span, &mut processor, ProcessingState::root())?;
     }
 
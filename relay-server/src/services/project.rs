@@ -11,6 +11,7 @@ use relay_dynamic_config::{ErrorBoundary, Feature, LimitedProjectConfig, Project
 use relay_filter::matches_any_origin;
 use relay_metrics::{
     Aggregator, Bucket, MergeBuckets, MetaAggregator, MetricMeta, MetricNamespace,
+    NamespaceBackpressure,
 };
 use relay_quotas::{
     CachedRateLimits, DataCategory, MetricNamespaceScoping, Quota, RateLimits, Scoping,
@@ -27,7 +28,9 @@ use crate::envelope::{Envelope, ItemType};
 use crate::metrics::{MetricOutcomes, MetricsLimiter};
 use crate::services::outcome::{DiscardReason, Outcome, TrackOutcome};
 use crate::services::processor::{EncodeMetricMeta, EnvelopeProcessor};
-use crate::services::project::metrics::{apply_project_state, filter_namespaces};
+use crate::services::project::metrics::{
+    apply_backpressure, apply_project_state, filter_namespaces,
+};
 use crate::services::project_cache::{BucketSource, CheckedEnvelope, ProjectCache, RequestUpdate};
 use crate::utils::{Enforcement, SeqCount};
 
@@ -558,17 +561,22 @@ impl Project {
         aggregator: &Addr<Aggregator>,
         metric_outcomes: &MetricOutcomes,
         outcome_aggregator: &Addr<TrackOutcome>,
+        backpressure: &NamespaceBackpressure,
         buckets: Vec<Bucket>,
         source: BucketSource,
     ) {
         // Best effort check for rate limits and project state. Continue if there is no project state.
-        let buckets = match self.check_buckets(metric_outcomes, outcome_aggregator, buckets) {
-            CheckedBuckets::NoProject(buckets) => buckets,
-            CheckedBuckets::Checked { buckets, .. } => buckets,
+        let mut buckets = match self.check_buckets(metric_outcomes, outcome_aggregator, buckets) {
+            CheckedBuckets::NoProject(buckets) => {
+                apply_backpressure(buckets, backpressure, metric_outcomes, None)
+            }
+            CheckedBuckets::Checked {
+                buckets, scoping, ..
+            } => apply_backpressure(buckets, backpressure, metric_outcomes, Some(scoping)),
             CheckedBuckets::Dropped => return,
         };
 
-        let buckets = filter_namespaces(buckets, source);
+        buckets = filter_namespaces(buckets, source);
 
         aggregator.send(MergeBuckets::new(
             self.project_key,
@@ -1,12 +1,16 @@
 use relay_dynamic_config::{ErrorBoundary, Feature, Metrics};
 use relay_filter::FilterStatKey;
-use relay_metrics::{Bucket, MetricNamespace};
-use relay_quotas::Scoping;
+use relay_metrics::{Bucket, MetricNamespace, NamespaceBackpressure};
+use relay_quotas::{ReasonCode, Scoping};
 
 use crate::metrics::MetricOutcomes;
 use crate::services::outcome::Outcome;
 use crate::services::project::ProjectState;
 use crate::services::project_cache::BucketSource;
+use crate::utils;
+
+/// Reason code for metrics rejected because their namespace exceeds its aggregator budget.
+const NAMESPACE_BUDGET_REASON: &str = "metrics_namespace_budget";
 
 pub fn filter_namespaces(mut buckets: Vec<Bucket>, source: BucketSource) -> Vec<Bucket> {
     buckets.retain(|bucket| match bucket.name.namespace() {
@@ -22,6 +26,36 @@ pub fn filter_namespaces(mut buckets: Vec<Bucket>, source: BucketSource) -> Vec<
     buckets
 }
 
+/// Rejects buckets of namespaces that currently exceed their budget in the aggregator.
+///
+/// The aggregator would reject these buckets anyway, but without reporting outcomes. Outcomes can
+/// only be reported if the `scoping` of the project is known.
+pub fn apply_backpressure(
+    buckets: Vec<Bucket>,
+    backpressure: &NamespaceBackpressure,
+    metric_outcomes: &MetricOutcomes,
+    scoping: Option<Scoping>,
+) -> Vec<Bucket> {
+    let (buckets, rejected) = utils::split_off(buckets, |bucket| {
+        backpressure.is_exceeded(bucket.name.namespace())
+    });
+
+    if rejected.is_empty() {
+        return buckets;
+    }
+
+    relay_log::trace!("dropping {} buckets due to back pressure", rejected.len());
+    if let Some(scoping) = scoping {
+        metric_outcomes.track(
+            scoping,
+            &rejected,
+            Outcome::RateLimited(Some(ReasonCode::new(NAMESPACE_BUDGET_REASON))),
+        );
+    }
+
+    buckets
+}
+
 pub fn apply_project_state(
     mut buckets: Vec<Bucket>,
     metric_outcomes: &MetricOutcomes,
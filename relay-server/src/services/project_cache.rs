@@ -8,7 +8,7 @@ use crate::metrics::MetricOutcomes;
 use hashbrown::HashSet;
 use relay_base_schema::project::ProjectKey;
 use relay_config::{Config, RelayMode};
-use relay_metrics::{Aggregator, Bucket, FlushBuckets, MetricMeta};
+use relay_metrics::{Aggregator, Bucket, FlushBuckets, MetricMeta, NamespaceBackpressure};
 use relay_quotas::RateLimits;
 use relay_redis::RedisPool;
 use relay_statsd::metric;
@@ -556,6 +556,8 @@ struct ProjectCacheBroker {
     config: Arc<Config>,
     services: Services,
     metric_outcomes: MetricOutcomes,
+    /// Namespaces that exceed their budget in the metrics aggregator.
+    metrics_backpressure: NamespaceBackpressure,
     // Need hashbrown because extract_if is not stable in std yet.
     projects: hashbrown::HashMap<ProjectKey, Project>,
     /// Utility for disposing of expired project data in a background thread.
@@ -870,6 +872,7 @@ impl ProjectCacheBroker {
         let aggregator = self.services.aggregator.clone();
         let metric_outcomes = self.metric_outcomes.clone();
         let outcome_aggregator = self.services.outcome_aggregator.clone();
+        let metrics_backpressure = self.metrics_backpressure.clone();
 
         let project = self.get_or_create_project(message.project_key);
         project.prefetch(project_cache, false);
@@ -877,6 +880,7 @@ impl ProjectCacheBroker {
             &aggregator,
             &metric_outcomes,
             &outcome_aggregator,
+            &metrics_backpressure,
             message.buckets,
             message.source,
         );
@@ -1099,6 +1103,7 @@ pub struct ProjectCacheService {
     config: Arc<Config>,
     services: Services,
     metric_outcomes: MetricOutcomes,
+    metrics_backpressure: NamespaceBackpressure,
     redis: Option<RedisPool>,
 }
 
@@ -1109,6 +1114,7 @@ impl ProjectCacheService {
         buffer_guard: Arc<BufferGuard>,
         services: Services,
         metric_outcomes: MetricOutcomes,
+        metrics_backpressure: NamespaceBackpressure,
         redis: Option<RedisPool>,
     ) -> Self {
         Self {
@@ -1116,6 +1122,7 @@ impl ProjectCacheService {
             config,
             services,
             metric_outcomes,
+            metrics_backpressure,
             redis,
         }
     }
@@ -1130,6 +1137,7 @@ impl Service for ProjectCacheService {
             config,
             services,
             metric_outcomes,
+            metrics_backpressure,
             redis,
         } = self;
         let project_cache = services.project_cache.clone();
@@ -1209,6 +1217,7 @@ impl Service for ProjectCacheService {
                 buffer,
                 global_config,
                 metric_outcomes,
+                metrics_backpressure,
             };
 
             loop {
@@ -1376,6 +1385,7 @@ mod tests {
                 buffer_unspool_handle: SleepHandle::idle(),
                 buffer_unspool_backoff: RetryBackoff::new(Duration::from_millis(100)),
                 metric_outcomes,
+                metrics_backpressure: NamespaceBackpressure::default(),
             },
             buffer,
         )
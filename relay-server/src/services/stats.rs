@@ -6,7 +6,7 @@ use relay_system::{Addr, Service};
 use tokio::time::interval;
 
 use crate::services::upstream::{IsNetworkOutage, UpstreamRelay};
-use crate::statsd::{RelayGauges, TokioGauges};
+use crate::statsd::{RelayCounters, RelayGauges, TokioGauges};
 
 /// Relay Stats Service.
 ///
@@ -82,6 +82,13 @@ impl RelayStats {
         }
     }
 
+    async fn user_agent_metrics(&self) {
+        let stats = relay_ua::take_cache_stats();
+        metric!(counter(RelayCounters::UserAgentCacheHits) += stats.hits as i64);
+        metric!(counter(RelayCounters::UserAgentCacheMisses) += stats.misses as i64);
+        metric!(gauge(RelayGauges::UserAgentCacheSize) = stats.entries as u64);
+    }
+
     async fn upstream_status(&self) {
         if self.config.relay_mode() == RelayMode::Managed {
             if let Ok(is_outage) = self.upstream_relay.send(IsNetworkOutage).await {
@@ -101,7 +108,11 @@ impl Service for RelayStats {
 
         tokio::spawn(async move {
             loop {
-                let _ = tokio::join!(self.tokio_metrics(), self.upstream_status());
+                let _ = tokio::join!(
+                    self.tokio_metrics(),
+                    self.user_agent_metrics(),
+                    self.upstream_status(),
+                );
                 ticker.tick().await;
             }
         });
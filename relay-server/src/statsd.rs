@@ -34,6 +34,8 @@ pub enum RelayGauges {
     ///
     /// Relay uses the same value for its memory health check.
     SystemMemoryTotal,
+    /// The number of parse results currently held in the user agent parse cache.
+    UserAgentCacheSize,
 }
 
 impl GaugeMetric for RelayGauges {
@@ -46,6 +48,7 @@ impl GaugeMetric for RelayGauges {
             RelayGauges::BufferPeriodicUnspool => "buffer.unspool.periodic",
             RelayGauges::SystemMemoryUsed => "health.system_memory.used",
             RelayGauges::SystemMemoryTotal => "health.system_memory.total",
+            RelayGauges::UserAgentCacheSize => "user_agent.cache.size",
         }
     }
 }
@@ -420,6 +423,17 @@ pub enum RelayTimers {
     /// scrubbing.minidumps.duration) will be scrubbed as plain attachments and count
     /// towards this.
     AttachmentScrubbing,
+    /// Time spent on scrubbing compressed and structured attachments.
+    ///
+    /// This includes decompressing the attachment, scrubbing its contents and compressing it
+    /// again.
+    ///
+    /// This metric is tagged with:
+    ///
+    /// - `status`: Scrubbing status: "ok" means the attachment was modified, "error" means the
+    ///       attachment could not be unpacked or exceeded the size limit and was dropped, and
+    ///       "n/a" means the attachment was not modified or is not in a supported format.
+    AttachmentContainerScrubbing,
     /// Total time spent to send request to upstream Relay and handle the response.
     ///
     /// This metric is tagged with:
@@ -520,6 +534,9 @@ impl TimerMetric for RelayTimers {
             RelayTimers::RequestsDuration => "requests.duration",
             RelayTimers::MinidumpScrubbing => "scrubbing.minidumps.duration",
             RelayTimers::AttachmentScrubbing => "scrubbing.attachments.duration",
+            RelayTimers::AttachmentContainerScrubbing => {
+                "scrubbing.attachments.containers.duration"
+            }
             RelayTimers::UpstreamRequestsDuration => "upstream.requests.duration",
             RelayTimers::TimestampDelay => "requests.timestamp_delay",
             RelayTimers::OutcomeAggregatorFlushTime => "outcomes.aggregator.flush_time",
@@ -778,6 +795,16 @@ pub enum RelayCounters {
     /// - `decision`: the decision relay makes on the event.
     /// - `attachment_type`: the type of the attachment in the envelope.
     NormalizationDecision,
+    /// The number of user agent lookups answered from the parse cache.
+    UserAgentCacheHits,
+    /// The number of user agent lookups that required parsing.
+    UserAgentCacheMisses,
+    /// The number of attachments dropped because they could not be unpacked for PII scrubbing.
+    ///
+    /// This metric is tagged with:
+    /// - `reason`: "too_large" if the decompressed attachment exceeds the scrubbing size limit,
+    ///   or "invalid" if it could not be decompressed or parsed.
+    AttachmentScrubbingFailed,
 }
 
 impl CounterMetric for RelayCounters {
@@ -823,6 +850,9 @@ impl CounterMetric for RelayCounters {
             RelayCounters::CogsUsage => "cogs.usage",
             RelayCounters::ProjectStateFlushMetricsNoProject => "project_state.metrics.no_project",
             RelayCounters::NormalizationDecision => "normalization.decision",
+            RelayCounters::UserAgentCacheHits => "user_agent.cache.hits",
+            RelayCounters::UserAgentCacheMisses => "user_agent.cache.misses",
+            RelayCounters::AttachmentScrubbingFailed => "scrubbing.attachments.failed",
         }
     }
 }
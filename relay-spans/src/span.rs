@@ -33,10 +33,10 @@ fn convert_from_otel_to_sentry_status(
     }
 
     if let Some(code) = grpc_status_code {
-        if let Some(sentry_status) = status_codes::GRPC.get(&code) {
-            if let Ok(span_status) = SpanStatus::from_str(sentry_status) {
-                return span_status;
-            }
+        // Status code 0 is OK, which is already covered by the status code of the span.
+        if let Some(span_status) = SpanStatus::from_grpc_code(code).filter(|&s| s != SpanStatus::Ok)
+        {
+            return span_status;
         }
     }
 
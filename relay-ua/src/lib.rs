@@ -1,25 +1,71 @@
 //! User agent parser with built-in rules.
 //!
+//! The parser uses the regexes of [uap-core](https://github.com/ua-parser/uap-core) embedded at
+//! build time. A newer regexes file can be loaded at startup with [`init_parser_from_file`], so
+//! that new browsers and devices are detected without upgrading Relay.
+//!
+//! Parse results are cached per user agent string. See [`take_cache_stats`] for statistics on the
+//! cache.
+//!
 //! # Test Performance
 //!
 //! Adding user agent parsing to your module will incur a latency penalty on first use. Because of
 //! this, integration tests could fail. To fix this, you will need to add a timeout to your
 //! consumer.
 
-use once_cell::sync::Lazy;
+use std::borrow::Cow;
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+use lru::LruCache;
+use once_cell::sync::{Lazy, OnceCell};
 use uaparser::{Parser, UserAgentParser};
 
 #[doc(inline)]
 pub use uaparser::{Device, UserAgent, OS};
 
-/// The global [`UserAgentParser`] already configured with a user agent database.
+/// The maximum number of user agents cached for each kind of parse result.
+const CACHE_SIZE: usize = 1024;
+
+/// The number of independently locked shards of each parse cache.
+const CACHE_SHARDS: usize = 16;
+
+/// The global [`UserAgentParser`].
 ///
 /// For usage, see [`Parser`].
-static UA_PARSER: Lazy<UserAgentParser> = Lazy::new(|| {
+static UA_PARSER: OnceCell<UserAgentParser> = OnceCell::new();
+
+static USER_AGENT_CACHE: Lazy<ParseCache<UserAgent>> = Lazy::new(ParseCache::new);
+static DEVICE_CACHE: Lazy<ParseCache<Device>> = Lazy::new(ParseCache::new);
+static OS_CACHE: Lazy<ParseCache<OS>> = Lazy::new(ParseCache::new);
+
+/// An error returned by [`init_parser_from_file`].
+#[derive(Debug, thiserror::Error)]
+pub enum ParserError {
+    /// The regexes file could not be read.
+    #[error("failed to read user agent regexes")]
+    Io(#[from] std::io::Error),
+    /// The regexes file does not contain valid user agent patterns.
+    #[error("invalid user agent regexes: {0}")]
+    InvalidRegexes(String),
+    /// The parser was initialized before the regexes file was loaded.
+    #[error("user agent parser is already initialized")]
+    AlreadyInitialized,
+}
+
+fn embedded_parser() -> UserAgentParser {
     let ua_regexes = include_bytes!("../uap-core/regexes.yaml");
     UserAgentParser::from_bytes(ua_regexes)
         .expect("Could not create UserAgent. You are probably using a bad build of relay.")
-});
+}
+
+fn parser() -> &'static UserAgentParser {
+    UA_PARSER.get_or_init(embedded_parser)
+}
 
 /// Initializes the user agent parser.
 ///
@@ -27,26 +73,256 @@ static UA_PARSER: Lazy<UserAgentParser> = Lazy::new(|| {
 /// agent parser initializes on-demand when using one of the parse methods. This function forces
 /// initialization at a convenient point without introducing unwanted delays.
 pub fn init_parser() {
-    Lazy::force(&UA_PARSER);
+    parser();
+}
+
+/// Initializes the user agent parser with regexes from a file.
+///
+/// The file must be in the format of `regexes.yaml` in uap-core. This must be called before any
+/// user agent is parsed. If the file cannot be loaded, an error is returned and the parser falls
+/// back to the embedded regexes.
+pub fn init_parser_from_file(path: &Path) -> Result<(), ParserError> {
+    if UA_PARSER.get().is_some() {
+        return Err(ParserError::AlreadyInitialized);
+    }
+
+    UA_PARSER
+        .set(load_parser(path)?)
+        .map_err(|_| ParserError::AlreadyInitialized)
+}
+
+fn load_parser(path: &Path) -> Result<UserAgentParser, ParserError> {
+    let ua_regexes = std::fs::read(path)?;
+    UserAgentParser::from_bytes(&ua_regexes)
+        .map_err(|e| ParserError::InvalidRegexes(format!("{e:?}")))
+}
+
+/// A bounded cache of parse results, keyed by the user agent string.
+///
+/// The cache is split into shards by the hash of the user agent, so that concurrent lookups of
+/// different user agents rarely contend for the same lock.
+struct ParseCache<T> {
+    shards: Vec<Mutex<LruCache<String, T>>>,
+    hasher: RandomState,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<T: Clone> ParseCache<T> {
+    fn new() -> Self {
+        let capacity = NonZeroUsize::new(CACHE_SIZE / CACHE_SHARDS).unwrap();
+
+        Self {
+            shards: (0..CACHE_SHARDS)
+                .map(|_| Mutex::new(LruCache::new(capacity)))
+                .collect(),
+            hasher: RandomState::new(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    fn shard(&self, user_agent: &str) -> &Mutex<LruCache<String, T>> {
+        let hash = self.hasher.hash_one(user_agent);
+        &self.shards[hash as usize % self.shards.len()]
+    }
+
+    fn get_or_parse(&self, user_agent: &str, parse: impl FnOnce(&str) -> T) -> T {
+        let shard = self.shard(user_agent);
+
+        let cached = shard
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(user_agent)
+            .cloned();
+
+        if let Some(result) = cached {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return result;
+        }
+
+        // Parse outside of the lock, since matching all patterns is expensive.
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let result = parse(user_agent);
+
+        shard
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .put(user_agent.to_owned(), result.clone());
+
+        result
+    }
+
+    /// Returns statistics and resets the hit and miss counts.
+    fn take_stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.swap(0, Ordering::Relaxed),
+            misses: self.misses.swap(0, Ordering::Relaxed),
+            entries: self
+                .shards
+                .iter()
+                .map(|shard| shard.lock().unwrap_or_else(PoisonError::into_inner).len())
+                .sum(),
+        }
+    }
+}
+
+/// Statistics of a parse result cache.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct CacheStats {
+    /// Number of lookups answered from the cache since the statistics were last taken.
+    pub hits: u64,
+    /// Number of lookups that required parsing since the statistics were last taken.
+    pub misses: u64,
+    /// Number of user agents currently in the cache.
+    pub entries: usize,
+}
+
+impl std::ops::Add for CacheStats {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            hits: self.hits + other.hits,
+            misses: self.misses + other.misses,
+            entries: self.entries + other.entries,
+        }
+    }
+}
+
+/// Returns statistics of the parse caches, summed over user agents, devices, and operating
+/// systems.
+///
+/// Hits and misses are counted since the previous call, so that they can be reported as counters.
+pub fn take_cache_stats() -> CacheStats {
+    USER_AGENT_CACHE.take_stats() + DEVICE_CACHE.take_stats() + OS_CACHE.take_stats()
+}
+
+/// Detaches a parsed value from the user agent string, so that it can be cached.
+fn owned(value: Cow<'_, str>) -> Cow<'static, str> {
+    Cow::Owned(value.into_owned())
 }
 
 /// Returns the family and version of a user agent client.
 ///
 /// Defaults to an empty user agent.
-pub fn parse_user_agent(user_agent: &str) -> UserAgent {
-    UA_PARSER.parse_user_agent(user_agent)
+pub fn parse_user_agent(user_agent: &str) -> UserAgent<'static> {
+    USER_AGENT_CACHE.get_or_parse(user_agent, |ua| {
+        let client = parser().parse_user_agent(ua);
+        UserAgent {
+            family: owned(client.family),
+            major: client.major.map(owned),
+            minor: client.minor.map(owned),
+            patch: client.patch.map(owned),
+        }
+    })
 }
 
 /// Returns the family, brand, and model of the device of the requesting client.
 ///
 /// Defaults to an empty device.
-pub fn parse_device(user_agent: &str) -> Device {
-    UA_PARSER.parse_device(user_agent)
+pub fn parse_device(user_agent: &str) -> Device<'static> {
+    DEVICE_CACHE.get_or_parse(user_agent, |ua| {
+        let device = parser().parse_device(ua);
+        Device {
+            family: owned(device.family),
+            brand: device.brand.map(owned),
+            model: device.model.map(owned),
+        }
+    })
 }
 
 /// Returns the family and version of the operating system of the requesting client.
 ///
 /// Defaults to an empty operating system.
-pub fn parse_os(user_agent: &str) -> OS {
-    UA_PARSER.parse_os(user_agent)
+pub fn parse_os(user_agent: &str) -> OS<'static> {
+    OS_CACHE.get_or_parse(user_agent, |ua| {
+        let os = parser().parse_os(ua);
+        OS {
+            family: owned(os.family),
+            major: os.major.map(owned),
+            minor: os.minor.map(owned),
+            patch: os.patch.map(owned),
+            patch_minor: os.patch_minor.map(owned),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cache() {
+        let cache = ParseCache::new();
+        let mut calls = 0;
+
+        for _ in 0..3 {
+            let result = cache.get_or_parse("Mozilla/5.0", |ua| {
+                calls += 1;
+                ua.len()
+            });
+            assert_eq!(result, 11);
+        }
+
+        assert_eq!(calls, 1);
+        assert_eq!(
+            cache.take_stats(),
+            CacheStats {
+                hits: 2,
+                misses: 1,
+                entries: 1,
+            }
+        );
+
+        // Hits and misses are reset, entries remain.
+        assert_eq!(
+            cache.take_stats(),
+            CacheStats {
+                hits: 0,
+                misses: 0,
+                entries: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_cache_bounded() {
+        let cache = ParseCache::new();
+
+        for i in 0..2 * CACHE_SIZE {
+            cache.get_or_parse(&i.to_string(), |ua| ua.len());
+        }
+
+        let stats = cache.take_stats();
+        assert_eq!(stats.misses, 2 * CACHE_SIZE as u64);
+        assert!(stats.entries <= CACHE_SIZE);
+    }
+
+    #[test]
+    fn test_load_parser_missing_file() {
+        let result = load_parser(Path::new("does/not/exist.yaml"));
+        assert!(matches!(result, Err(ParserError::Io(_))));
+    }
+
+    #[test]
+    fn test_load_parser_invalid_file() {
+        let result = load_parser(Path::new("tests/fixtures/invalid.yaml"));
+        assert!(matches!(result, Err(ParserError::InvalidRegexes(_))));
+    }
+
+    #[test]
+    fn test_load_parser_from_file() {
+        let parser = load_parser(Path::new("tests/fixtures/regexes.yaml")).unwrap();
+        let user_agent = "Mozilla/5.0 (Relay Test OS 3) RelayTestBrowser/1.2";
+
+        let client = parser.parse_user_agent(user_agent);
+        assert_eq!(client.family, "RelayTestBrowser");
+        assert_eq!(client.major.as_deref(), Some("1"));
+        assert_eq!(client.minor.as_deref(), Some("2"));
+
+        let os = parser.parse_os(user_agent);
+        assert_eq!(os.family, "Relay Test OS");
+        assert_eq!(os.major.as_deref(), Some("3"));
+    }
 }
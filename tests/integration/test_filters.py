@@ -309,6 +309,34 @@ def test_client_ip_filters_are_applied(
     assert mini_sentry.captured_events.empty()
 
 
+def test_repeated_errors_filter_is_applied(
+    mini_sentry,
+    relay,
+):
+    relay = relay(mini_sentry)
+
+    project_id = 42
+    project_config = mini_sentry.add_full_project_config(project_id)
+    filter_settings = project_config["config"]["filterSettings"]
+    filter_settings["repeatedErrors"] = {"isEnabled": True, "limit": 2, "window": 60}
+
+    event = {
+        "exception": {"values": [{"type": "ValueError", "value": "invalid value"}]}
+    }
+    for _ in range(3):
+        relay.send_event(project_id, event)
+
+    for _ in range(2):
+        mini_sentry.captured_events.get(timeout=5)
+
+    report = mini_sentry.get_client_report()
+    assert report["filtered_events"] == [
+        {"reason": "repeated-errors", "category": "error", "quantity": 1}
+    ]
+
+    assert mini_sentry.captured_events.empty()
+
+
 def test_global_filters_drop_events(
     mini_sentry, relay_with_processing, events_consumer, outcomes_consumer
 ):
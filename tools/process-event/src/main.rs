@@ -15,7 +15,7 @@ use relay_event_normalization::{
 };
 use relay_event_schema::processor::{process_value, ProcessingState};
 use relay_event_schema::protocol::Event;
-use relay_pii::{PiiConfig, PiiProcessor};
+use relay_pii::{explain_pii, PiiConfig, PiiProcessor};
 use relay_protocol::Annotated;
 
 /// Processes a Sentry event payload.
@@ -29,6 +29,10 @@ struct Cli {
     #[arg(short = 'c', long)]
     pii_config: Option<PathBuf>,
 
+    /// Secret key for keyed hash redactions in the PII config.
+    #[arg(long, requires = "pii_config")]
+    hash_key: Option<String>,
+
     /// Path to an event payload JSON file (defaults to stdin).
     #[arg(short, long)]
     event: Option<PathBuf>,
@@ -37,6 +41,10 @@ struct Cli {
     #[arg(long)]
     store: bool,
 
+    /// Print which PII rules redact which values instead of the processed event.
+    #[arg(long, requires = "pii_config", conflicts_with = "store")]
+    explain: bool,
+
     /// Pretty print the output JSON.
     #[arg(long, conflicts_with = "debug")]
     pretty: bool,
@@ -78,7 +86,25 @@ impl Cli {
         let mut event = self.load_event()?;
 
         if let Some(pii_config) = self.load_pii_config()? {
-            let mut processor = PiiProcessor::new(pii_config.compiled());
+            if self.explain {
+                let processor = PiiProcessor::new(pii_config.compiled())
+                    .with_hash_key(self.hash_key.as_deref());
+                let explanations =
+                    explain_pii(&event, processor).map_err(|e| format_err!("{e}"))?;
+
+                if self.debug {
+                    println!("{explanations:#?}");
+                } else if self.pretty {
+                    println!("{}", serde_json::to_string_pretty(&explanations)?);
+                } else {
+                    println!("{}", serde_json::to_string(&explanations)?);
+                }
+
+                return Ok(());
+            }
+
+            let mut processor =
+                PiiProcessor::new(pii_config.compiled()).with_hash_key(self.hash_key.as_deref());
             process_value(&mut event, &mut processor, ProcessingState::root())
                 .map_err(|e| format_err!("{e}"))?;
         }